@@ -0,0 +1,342 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Expr, Field, Fields, GenericArgument, Ident, LitStr, PathArguments,
+    Result, Type,
+};
+
+enum FieldKind {
+    Text,
+    File,
+    Json,
+    Parse,
+}
+
+struct MultipartField {
+    ident: Ident,
+    name: String,
+    label: String,
+    ty: Type,
+    kind: FieldKind,
+    optional: bool,
+    repeated: bool,
+    limit: Option<Expr>,
+}
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let validate = parse_struct_attrs(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(parse_field)
+                .collect::<Result<Vec<_>>>()?,
+            _ => {
+                return Err(Error::new_spanned(
+                    ident,
+                    "FromMultipart needs a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                ident,
+                "FromMultipart can only be derived for structs",
+            ));
+        }
+    };
+
+    let declarations = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        if field.repeated {
+            quote! { let mut #ident: ::std::vec::Vec<#ty> = ::std::vec::Vec::new(); }
+        } else {
+            quote! { let mut #ident: ::std::option::Option<#ty> = ::std::option::Option::None; }
+        }
+    });
+    let arms = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        let read = read_field(field);
+        if field.repeated {
+            quote! {
+                ::std::option::Option::Some(#name) => {
+                    #ident.push(#read);
+                }
+            }
+        } else {
+            quote! {
+                ::std::option::Option::Some(#name) => {
+                    #ident = ::std::option::Option::Some(#read);
+                }
+            }
+        }
+    });
+    let assignments = fields.iter().map(|field| {
+        let ident = &field.ident;
+        if field.optional || field.repeated {
+            quote! { #ident }
+        } else {
+            let missing = format!("Missing {}", field.label.to_lowercase());
+            quote! { #ident: #ident.ok_or(ServiceError::DtoError(#missing.into()))? }
+        }
+    });
+    let validation = validate.then(|| quote! { ::validator::Validate::validate(&__dto)?; });
+
+    Ok(quote! {
+        impl<S: ::std::marker::Send + ::std::marker::Sync> ::axum::extract::FromRequest<S> for #ident {
+            type Rejection = crate::services::error::ServiceError;
+
+            async fn from_request(
+                __req: ::axum::extract::Request,
+                __state: &S,
+            ) -> ::std::result::Result<Self, Self::Rejection> {
+                use crate::services::error::ServiceError;
+
+                let mut __form = <::axum::extract::Multipart as ::axum::extract::FromRequest<S>>::from_request(__req, __state)
+                    .await
+                    .map_err(|rejection| ServiceError::DtoError(rejection.body_text()))?;
+
+                #(#declarations)*
+
+                while let ::std::option::Option::Some(mut __field) = __form.next_field().await? {
+                    let __name = __field.name().map(::std::string::ToString::to_string);
+                    match __name.as_deref() {
+                        #(#arms)*
+                        _ => {}
+                    }
+                }
+
+                let __dto = Self {
+                    #(#assignments,)*
+                };
+                #validation
+
+                ::std::result::Result::Ok(__dto)
+            }
+        }
+    })
+}
+
+fn parse_struct_attrs(input: &DeriveInput) -> Result<bool> {
+    let mut validate = false;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("multipart"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("validate") {
+                validate = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown multipart attribute"))
+            }
+        })?;
+    }
+
+    Ok(validate)
+}
+
+fn parse_field(field: &Field) -> Result<MultipartField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| Error::new_spanned(field, "FromMultipart needs named fields"))?;
+
+    let mut name = to_camel_case(&ident.to_string());
+    let mut limit: Option<Expr> = None;
+    let mut json = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("multipart"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("limit") {
+                limit = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("json") {
+                json = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown multipart attribute"))
+            }
+        })?;
+    }
+
+    // A json field holds the whole Vec, otherwise every field with the name adds an element
+    let (ty, optional, repeated) = match (
+        option_inner(&field.ty),
+        generic_inner(&field.ty, "Vec").filter(|_| !json),
+    ) {
+        (Some(inner), _) => (inner.clone(), true, false),
+        (None, Some(inner)) => (inner.clone(), false, true),
+        (None, None) => (field.ty.clone(), false, false),
+    };
+    if optional && !json && generic_inner(&ty, "Vec").is_some() {
+        return Err(Error::new_spanned(
+            field,
+            "repeated fields are a plain Vec, an empty Vec means the field wasn't sent",
+        ));
+    }
+    let kind = match (json, type_name(&ty).as_deref()) {
+        (true, _) => FieldKind::Json,
+        (false, Some("Bytes")) => FieldKind::File,
+        (false, Some("String")) => FieldKind::Text,
+        _ => FieldKind::Parse,
+    };
+    if limit.is_some() && !matches!(kind, FieldKind::File) {
+        return Err(Error::new_spanned(
+            field,
+            "multipart(limit) only applies to Bytes fields",
+        ));
+    }
+
+    Ok(MultipartField {
+        label: to_label(&ident.to_string()),
+        ident,
+        name,
+        ty,
+        kind,
+        optional,
+        repeated,
+        limit,
+    })
+}
+
+fn read_field(field: &MultipartField) -> TokenStream {
+    let invalid = format!("{} is invalid", field.label);
+    let text = quote! {
+        __field
+            .text()
+            .await
+            .map_err(|_| ServiceError::DtoError(#invalid.into()))?
+    };
+
+    match &field.kind {
+        FieldKind::Text => text,
+        FieldKind::Json => quote! {
+            ::serde_json::from_str(&#text)
+                .map_err(|_| ServiceError::DtoError(#invalid.into()))?
+        },
+        FieldKind::Parse => quote! {
+            #text
+                .trim()
+                .parse()
+                .map_err(|_| ServiceError::DtoError(#invalid.into()))?
+        },
+        FieldKind::File => match &field.limit {
+            // Read in chunks so an oversized file is rejected without buffering all of it
+            Some(limit) => {
+                let too_large = format!("{} is too large", field.label);
+                quote! {{
+                    let __limit: usize = #limit;
+                    let mut __file: ::std::vec::Vec<u8> = ::std::vec::Vec::new();
+                    while let ::std::option::Option::Some(__chunk) = __field
+                        .chunk()
+                        .await
+                        .map_err(|_| ServiceError::DtoError(#invalid.into()))?
+                    {
+                        if __file.len() + __chunk.len() > __limit {
+                            return ::std::result::Result::Err(ServiceError::DtoError(#too_large.into()));
+                        }
+                        __file.extend_from_slice(&__chunk);
+                    }
+                    ::axum::body::Bytes::from(__file)
+                }}
+            }
+            None => quote! {
+                __field
+                    .bytes()
+                    .await
+                    .map_err(|_| ServiceError::DtoError(#invalid.into()))?
+            },
+        },
+    }
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+// Type argument of `Wrapper<T>`
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+fn type_name(ty: &Type) -> Option<String> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    path.path
+        .segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+// display_name -> displayName
+fn to_camel_case(value: &str) -> String {
+    let mut parts = value.split('_').filter(|part| !part.is_empty());
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+
+    camel
+}
+
+// display_name -> Display name
+fn to_label(value: &str) -> String {
+    let words = value
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_camel_case, to_label};
+
+    #[test]
+    fn test_field_names() {
+        assert_eq!(to_camel_case("display_name"), "displayName");
+        assert_eq!(
+            to_camel_case("business_registration_number"),
+            "businessRegistrationNumber"
+        );
+        assert_eq!(to_camel_case("cover"), "cover");
+        assert_eq!(to_label("category_id"), "Category id");
+        assert_eq!(to_label("cover"), "Cover");
+    }
+}
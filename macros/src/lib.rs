@@ -0,0 +1,32 @@
+mod from_multipart;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+/**
+ * Generates an axum `FromRequest` impl that reads a struct from a multipart form.
+ *
+ * Field names are matched in camelCase. `String` fields take the raw text, `Bytes` fields take
+ * the file and every other type is parsed from the trimmed text with `FromStr`. `Option` fields
+ * are optional, the rest are required. A `Vec` field collects every field sent with its name and
+ * is empty when there's none.
+ *
+ * The generated code rejects with `crate::services::error::ServiceError`, so it only compiles
+ * inside the backend crate, which has to convert `MultipartError` and `ValidationErrors` into it.
+ *
+ * Struct attributes:
+ * - `#[multipart(validate)]` runs `validator::Validate` before the handler gets the dto
+ *
+ * Field attributes:
+ * - `#[multipart(rename = "name")]` matches a different field name
+ * - `#[multipart(limit = EXPR)]` rejects `Bytes` fields larger than `EXPR` bytes, for a `Vec` each
+ *   file
+ * - `#[multipart(json)]` parses the text with `serde_json` instead of `FromStr`
+ */
+#[proc_macro_derive(FromMultipart, attributes(multipart))]
+pub fn derive_from_multipart(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_multipart::expand(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -5,6 +5,37 @@ mod m20250614_041623_transfer_table_migrations;
 mod m20250614_090509_add_referral_code_migrations;
 mod m20250702_100052_add_merchant_table_migrations;
 mod m20250705_112951_remove_username_migrations;
+mod m20250712_094210_add_payment_split_migrations;
+mod m20250714_153302_add_escrow_migrations;
+mod m20250717_110847_add_promotion_migrations;
+mod m20250719_142315_add_statement_export_migrations;
+mod m20250721_091204_add_merchant_analytics_migrations;
+mod m20250723_163740_add_ledger_migrations;
+mod m20250725_103518_add_reconciliation_migrations;
+mod m20250727_094512_add_settlement_wallet_migrations;
+mod m20250729_152036_add_wallet_auth_migrations;
+mod m20250731_110342_add_user_google_sub_migrations;
+mod m20250802_134905_add_user_privy_id_migrations;
+mod m20250804_091527_add_two_factor_migrations;
+mod m20250806_143210_add_api_key_migrations;
+mod m20250808_101744_add_user_role_migrations;
+mod m20250810_083015_add_merchant_member_migrations;
+mod m20250812_140322_add_merchant_verification_migrations;
+mod m20250814_102455_add_store_migrations;
+mod m20250816_091733_add_merchant_discovery_migrations;
+mod m20250818_104210_add_category_migrations;
+mod m20250820_093518_add_merchant_slug_history_migrations;
+mod m20250822_110406_add_user_avatar_migrations;
+mod m20250824_150912_add_upload_migrations;
+mod m20250826_101522_add_escrow_payout_migrations;
+mod m20250828_094317_add_ledger_merchant_account_migrations;
+mod m20250828_141205_add_reconciliation_lookup_failed_migrations;
+mod m20250828_160244_add_user_privy_sync_migrations;
+mod m20250829_101847_add_merchant_invitation_notification_migrations;
+mod m20250830_090215_add_rollup_refreshed_at_migrations;
+mod m20250830_101532_add_user_wallet_address_unique_migrations;
+mod m20250830_102214_add_wallet_challenge_purpose_migrations;
+mod m20250830_113406_add_notification_reference_migrations;
 
 pub struct Migrator;
 
@@ -17,6 +48,37 @@ impl MigratorTrait for Migrator {
             Box::new(m20250614_090509_add_referral_code_migrations::Migration),
             Box::new(m20250702_100052_add_merchant_table_migrations::Migration),
             Box::new(m20250705_112951_remove_username_migrations::Migration),
+            Box::new(m20250712_094210_add_payment_split_migrations::Migration),
+            Box::new(m20250714_153302_add_escrow_migrations::Migration),
+            Box::new(m20250717_110847_add_promotion_migrations::Migration),
+            Box::new(m20250719_142315_add_statement_export_migrations::Migration),
+            Box::new(m20250721_091204_add_merchant_analytics_migrations::Migration),
+            Box::new(m20250723_163740_add_ledger_migrations::Migration),
+            Box::new(m20250725_103518_add_reconciliation_migrations::Migration),
+            Box::new(m20250727_094512_add_settlement_wallet_migrations::Migration),
+            Box::new(m20250729_152036_add_wallet_auth_migrations::Migration),
+            Box::new(m20250731_110342_add_user_google_sub_migrations::Migration),
+            Box::new(m20250802_134905_add_user_privy_id_migrations::Migration),
+            Box::new(m20250804_091527_add_two_factor_migrations::Migration),
+            Box::new(m20250806_143210_add_api_key_migrations::Migration),
+            Box::new(m20250808_101744_add_user_role_migrations::Migration),
+            Box::new(m20250810_083015_add_merchant_member_migrations::Migration),
+            Box::new(m20250812_140322_add_merchant_verification_migrations::Migration),
+            Box::new(m20250814_102455_add_store_migrations::Migration),
+            Box::new(m20250816_091733_add_merchant_discovery_migrations::Migration),
+            Box::new(m20250818_104210_add_category_migrations::Migration),
+            Box::new(m20250820_093518_add_merchant_slug_history_migrations::Migration),
+            Box::new(m20250822_110406_add_user_avatar_migrations::Migration),
+            Box::new(m20250824_150912_add_upload_migrations::Migration),
+            Box::new(m20250826_101522_add_escrow_payout_migrations::Migration),
+            Box::new(m20250828_094317_add_ledger_merchant_account_migrations::Migration),
+            Box::new(m20250828_141205_add_reconciliation_lookup_failed_migrations::Migration),
+            Box::new(m20250828_160244_add_user_privy_sync_migrations::Migration),
+            Box::new(m20250829_101847_add_merchant_invitation_notification_migrations::Migration),
+            Box::new(m20250830_090215_add_rollup_refreshed_at_migrations::Migration),
+            Box::new(m20250830_101532_add_user_wallet_address_unique_migrations::Migration),
+            Box::new(m20250830_102214_add_wallet_challenge_purpose_migrations::Migration),
+            Box::new(m20250830_113406_add_notification_reference_migrations::Migration),
         ]
     }
 }
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(SplitType::Type)
+                    .values([SplitType::Percentage, SplitType::Fixed])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Table
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaymentSplit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaymentSplit::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaymentSplit::PaymentId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_split_payment_id")
+                            .from(PaymentSplit::Table, PaymentSplit::PaymentId)
+                            .to(Payment::Table, Payment::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PaymentSplit::WalletAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaymentSplit::SplitType)
+                            .custom(SplitType::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PaymentSplit::Value).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(PaymentSplit::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(SplitType::Type).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PaymentSplit {
+    Table,
+    Id,
+    PaymentId,
+    WalletAddress,
+    SplitType,
+    Value,
+}
+
+#[derive(DeriveIden)]
+enum SplitType {
+    #[sea_orm(iden = "split_type")]
+    Type,
+    Percentage,
+    Fixed,
+}
+
+#[derive(DeriveIden)]
+enum Payment {
+    Table,
+    Id,
+}
@@ -0,0 +1,241 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .add_column(
+                        ColumnDef::new(Payment::IsEscrow)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::IsAdmin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(EscrowStatus::Type)
+                    .values([
+                        EscrowStatus::Held,
+                        EscrowStatus::Disputed,
+                        EscrowStatus::Released,
+                        EscrowStatus::Refunded,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(Escrow::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Escrow::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Escrow::TransferId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_escrow_transfer_id")
+                            .from(Escrow::Table, Escrow::TransferId)
+                            .to(Transfer::Table, Transfer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Escrow::Status)
+                            .custom(EscrowStatus::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Escrow::Amount).big_integer().not_null())
+                    .col(ColumnDef::new(Escrow::ReleaseAfter).date_time().not_null())
+                    .col(ColumnDef::new(Escrow::Signature).string())
+                    .col(
+                        ColumnDef::new(Escrow::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Escrow::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(EscrowEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EscrowEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(EscrowEvent::EscrowId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_escrow_event_escrow_id")
+                            .from(EscrowEvent::Table, EscrowEvent::EscrowId)
+                            .to(Escrow::Table, Escrow::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(EscrowEvent::FromStatus).custom(EscrowStatus::Type))
+                    .col(
+                        ColumnDef::new(EscrowEvent::ToStatus)
+                            .custom(EscrowStatus::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(EscrowEvent::ActorId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_escrow_event_actor_id")
+                            .from(EscrowEvent::Table, EscrowEvent::ActorId)
+                            .to(User::Table, User::Id),
+                    )
+                    .col(ColumnDef::new(EscrowEvent::Note).string())
+                    .col(
+                        ColumnDef::new(EscrowEvent::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(EscrowEvent::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Escrow::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(EscrowStatus::Type).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::IsAdmin)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .drop_column(Payment::IsEscrow)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Escrow {
+    Table,
+    Id,
+    TransferId,
+    Status,
+    Amount,
+    ReleaseAfter,
+    Signature,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EscrowEvent {
+    Table,
+    Id,
+    EscrowId,
+    FromStatus,
+    ToStatus,
+    ActorId,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum EscrowStatus {
+    #[sea_orm(iden = "escrow_status")]
+    Type,
+    Held,
+    Disputed,
+    Released,
+    Refunded,
+}
+
+#[derive(DeriveIden)]
+enum Payment {
+    Table,
+    IsEscrow,
+}
+
+#[derive(DeriveIden)]
+enum Transfer {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    IsAdmin,
+}
@@ -0,0 +1,257 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Amount charged by the transfer in token base units, can differ from payment amount with promotions
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transfer::Table)
+                    .add_column(
+                        ColumnDef::new(Transfer::Amount)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE transfer SET amount = payment.amount * 1000000 \
+                 FROM payment WHERE transfer.payment_id = payment.id",
+            )
+            .await?;
+
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(DiscountType::Type)
+                    .values([DiscountType::Percentage, DiscountType::Fixed])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(Promotion::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Promotion::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Promotion::MerchantId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_promotion_merchant_id")
+                            .from(Promotion::Table, Promotion::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Promotion::Code).string().not_null())
+                    .col(
+                        ColumnDef::new(Promotion::DiscountType)
+                            .custom(DiscountType::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Promotion::Value).big_integer().not_null())
+                    .col(ColumnDef::new(Promotion::StartsAt).date_time().not_null())
+                    .col(ColumnDef::new(Promotion::EndsAt).date_time())
+                    .col(ColumnDef::new(Promotion::MaxRedemptions).integer())
+                    .col(
+                        ColumnDef::new(Promotion::RedemptionCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Promotion::PerWalletLimit).integer())
+                    .col(
+                        ColumnDef::new(Promotion::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(
+                        ColumnDef::new(Promotion::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_promotion_merchant_id_code")
+                    .table(Promotion::Table)
+                    .col(Promotion::MerchantId)
+                    .col(Promotion::Code)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PromotionRedemption::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PromotionRedemption::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PromotionRedemption::PromotionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_promotion_redemption_promotion_id")
+                            .from(PromotionRedemption::Table, PromotionRedemption::PromotionId)
+                            .to(Promotion::Table, Promotion::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PromotionRedemption::TransferId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_promotion_redemption_transfer_id")
+                            .from(PromotionRedemption::Table, PromotionRedemption::TransferId)
+                            .to(Transfer::Table, Transfer::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PromotionRedemption::WalletAddress)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PromotionRedemption::DiscountAmount)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PromotionRedemption::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_promotion_redemption_promotion_id_wallet_address")
+                    .table(PromotionRedemption::Table)
+                    .col(PromotionRedemption::PromotionId)
+                    .col(PromotionRedemption::WalletAddress)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(PromotionRedemption::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Promotion::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(DiscountType::Type).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transfer::Table)
+                    .drop_column(Transfer::Amount)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Promotion {
+    Table,
+    Id,
+    MerchantId,
+    Code,
+    DiscountType,
+    Value,
+    StartsAt,
+    EndsAt,
+    MaxRedemptions,
+    RedemptionCount,
+    PerWalletLimit,
+    IsActive,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum PromotionRedemption {
+    Table,
+    Id,
+    PromotionId,
+    TransferId,
+    WalletAddress,
+    DiscountAmount,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum DiscountType {
+    #[sea_orm(iden = "discount_type")]
+    Type,
+    Percentage,
+    Fixed,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Transfer {
+    Table,
+    Id,
+    Amount,
+}
@@ -0,0 +1,157 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enums
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(StatementFormat::Type)
+                    .values([StatementFormat::Csv, StatementFormat::Pdf])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(StatementStatus::Type)
+                    .values([
+                        StatementStatus::Pending,
+                        StatementStatus::Completed,
+                        StatementStatus::Failed,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Table
+        manager
+            .create_table(
+                Table::create()
+                    .table(StatementExport::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(StatementExport::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(StatementExport::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_statement_export_user_id")
+                            .from(StatementExport::Table, StatementExport::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(StatementExport::Format)
+                            .custom(StatementFormat::Type)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StatementExport::Status)
+                            .custom(StatementStatus::Type)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StatementExport::PeriodStart)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(StatementExport::PeriodEnd)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(StatementExport::S3Key).string())
+                    .col(ColumnDef::new(StatementExport::Error).string())
+                    .col(
+                        ColumnDef::new(StatementExport::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(StatementExport::CompletedAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(StatementExport::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(StatementStatus::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(StatementFormat::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum StatementExport {
+    Table,
+    Id,
+    UserId,
+    Format,
+    Status,
+    PeriodStart,
+    PeriodEnd,
+    S3Key,
+    Error,
+    CreatedAt,
+    CompletedAt,
+}
+
+#[derive(DeriveIden)]
+enum StatementFormat {
+    #[sea_orm(iden = "statement_format")]
+    Type,
+    Csv,
+    Pdf,
+}
+
+#[derive(DeriveIden)]
+enum StatementStatus {
+    #[sea_orm(iden = "statement_status")]
+    Type,
+    Pending,
+    Completed,
+    Failed,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transfer_payment_id_created_at")
+                    .table(Transfer::Table)
+                    .col(Transfer::PaymentId)
+                    .col(Transfer::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        /*
+         * Daily transfer counts and completed revenue per merchant (payment owner).
+         * The unique index lets the rollup job refresh it concurrently without blocking reads.
+         */
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE MATERIALIZED VIEW merchant_daily_rollup AS
+                SELECT
+                    p.user_id,
+                    date_trunc('day', t.created_at) AS day,
+                    count(*) AS transfers,
+                    count(*) FILTER (WHERE t.status = 'pending') AS pending,
+                    count(*) FILTER (WHERE t.status = 'completed') AS completed,
+                    count(*) FILTER (WHERE t.status = 'rejected') AS rejected,
+                    coalesce(sum(t.amount) FILTER (WHERE t.status = 'completed'), 0)::bigint AS revenue
+                FROM transfer t
+                JOIN payment p ON p.id = t.payment_id
+                GROUP BY p.user_id, date_trunc('day', t.created_at);
+
+                CREATE UNIQUE INDEX idx_merchant_daily_rollup_user_id_day
+                    ON merchant_daily_rollup (user_id, day);
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP MATERIALIZED VIEW IF EXISTS merchant_daily_rollup")
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transfer_payment_id_created_at")
+                    .table(Transfer::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Transfer {
+    Table,
+    PaymentId,
+    CreatedAt,
+}
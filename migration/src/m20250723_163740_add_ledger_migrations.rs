@@ -0,0 +1,322 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enums
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(LedgerAccountType::Type)
+                    .values([
+                        LedgerAccountType::User,
+                        LedgerAccountType::Wallet,
+                        LedgerAccountType::Treasury,
+                        LedgerAccountType::Escrow,
+                        LedgerAccountType::Adjustment,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(LedgerTransactionKind::Type)
+                    .values([
+                        LedgerTransactionKind::Transfer,
+                        LedgerTransactionKind::EscrowRelease,
+                        LedgerTransactionKind::EscrowRefund,
+                        LedgerTransactionKind::ReferralReward,
+                        LedgerTransactionKind::Adjustment,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(LedgerAccount::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LedgerAccount::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LedgerAccount::AccountType)
+                            .custom(LedgerAccountType::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LedgerAccount::Reference).string().not_null())
+                    .col(
+                        ColumnDef::new(LedgerAccount::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ledger_account_account_type_reference")
+                    .table(LedgerAccount::Table)
+                    .col(LedgerAccount::AccountType)
+                    .col(LedgerAccount::Reference)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(LedgerTransaction::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LedgerTransaction::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LedgerTransaction::Kind)
+                            .custom(LedgerTransactionKind::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(LedgerTransaction::TransferId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ledger_transaction_transfer_id")
+                            .from(LedgerTransaction::Table, LedgerTransaction::TransferId)
+                            .to(Transfer::Table, Transfer::Id),
+                    )
+                    .col(ColumnDef::new(LedgerTransaction::EscrowId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ledger_transaction_escrow_id")
+                            .from(LedgerTransaction::Table, LedgerTransaction::EscrowId)
+                            .to(Escrow::Table, Escrow::Id),
+                    )
+                    .col(ColumnDef::new(LedgerTransaction::Signature).string())
+                    .col(ColumnDef::new(LedgerTransaction::Note).string())
+                    .col(
+                        ColumnDef::new(LedgerTransaction::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A transfer or escrow settlement is only ever posted once
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ledger_transaction_kind_transfer_id")
+                    .table(LedgerTransaction::Table)
+                    .col(LedgerTransaction::Kind)
+                    .col(LedgerTransaction::TransferId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ledger_transaction_kind_escrow_id")
+                    .table(LedgerTransaction::Table)
+                    .col(LedgerTransaction::Kind)
+                    .col(LedgerTransaction::EscrowId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(LedgerEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LedgerEntry::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(LedgerEntry::LedgerTransactionId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ledger_entry_ledger_transaction_id")
+                            .from(LedgerEntry::Table, LedgerEntry::LedgerTransactionId)
+                            .to(LedgerTransaction::Table, LedgerTransaction::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(LedgerEntry::LedgerAccountId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ledger_entry_ledger_account_id")
+                            .from(LedgerEntry::Table, LedgerEntry::LedgerAccountId)
+                            .to(LedgerAccount::Table, LedgerAccount::Id),
+                    )
+                    .col(ColumnDef::new(LedgerEntry::Amount).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(LedgerEntry::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_ledger_entry_ledger_account_id_created_at")
+                    .table(LedgerEntry::Table)
+                    .col(LedgerEntry::LedgerAccountId)
+                    .col(LedgerEntry::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(LedgerEntry::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(LedgerTransaction::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(LedgerAccount::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(LedgerTransactionKind::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(LedgerAccountType::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum LedgerAccount {
+    Table,
+    Id,
+    AccountType,
+    Reference,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum LedgerTransaction {
+    Table,
+    Id,
+    Kind,
+    TransferId,
+    EscrowId,
+    Signature,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum LedgerEntry {
+    Table,
+    Id,
+    LedgerTransactionId,
+    LedgerAccountId,
+    Amount,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum LedgerAccountType {
+    #[sea_orm(iden = "ledger_account_type")]
+    Type,
+    User,
+    Wallet,
+    Treasury,
+    Escrow,
+    Adjustment,
+}
+
+#[derive(DeriveIden)]
+enum LedgerTransactionKind {
+    #[sea_orm(iden = "ledger_transaction_kind")]
+    Type,
+    Transfer,
+    EscrowRelease,
+    EscrowRefund,
+    ReferralReward,
+    Adjustment,
+}
+
+#[derive(DeriveIden)]
+enum Transfer {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Escrow {
+    Table,
+    Id,
+}
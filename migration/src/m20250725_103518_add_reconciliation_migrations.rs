@@ -0,0 +1,203 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ReconciliationIssueKind::Type)
+                    .values([
+                        ReconciliationIssueKind::UnmatchedReceipt,
+                        ReconciliationIssueKind::MissingOnChain,
+                        ReconciliationIssueKind::AmountMismatch,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReconciliationReport::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReconciliationReport::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::WindowStart)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::WindowEnd)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::AccountsScanned)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::SignaturesScanned)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationReport::IssueCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(ReconciliationReport::Error).string())
+                    .col(
+                        ColumnDef::new(ReconciliationReport::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(ReconciliationReport::FinishedAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReconciliationIssue::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReconciliationIssue::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationIssue::ReportId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reconciliation_issue_report_id")
+                            .from(ReconciliationIssue::Table, ReconciliationIssue::ReportId)
+                            .to(ReconciliationReport::Table, ReconciliationReport::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(ReconciliationIssue::Kind)
+                            .custom(ReconciliationIssueKind::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReconciliationIssue::Account).string())
+                    .col(ColumnDef::new(ReconciliationIssue::Signature).string())
+                    .col(ColumnDef::new(ReconciliationIssue::TransferId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reconciliation_issue_transfer_id")
+                            .from(ReconciliationIssue::Table, ReconciliationIssue::TransferId)
+                            .to(Transfer::Table, Transfer::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(ReconciliationIssue::ExpectedAmount).big_integer())
+                    .col(ColumnDef::new(ReconciliationIssue::ActualAmount).big_integer())
+                    .col(
+                        ColumnDef::new(ReconciliationIssue::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ReconciliationIssue::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ReconciliationReport::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(ReconciliationIssueKind::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReconciliationReport {
+    Table,
+    Id,
+    WindowStart,
+    WindowEnd,
+    AccountsScanned,
+    SignaturesScanned,
+    IssueCount,
+    Error,
+    CreatedAt,
+    FinishedAt,
+}
+
+#[derive(DeriveIden)]
+enum ReconciliationIssue {
+    Table,
+    Id,
+    ReportId,
+    Kind,
+    Account,
+    Signature,
+    TransferId,
+    ExpectedAmount,
+    ActualAmount,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ReconciliationIssueKind {
+    #[sea_orm(iden = "reconciliation_issue_kind")]
+    Type,
+    UnmatchedReceipt,
+    MissingOnChain,
+    AmountMismatch,
+}
+
+#[derive(DeriveIden)]
+enum Transfer {
+    Table,
+    Id,
+}
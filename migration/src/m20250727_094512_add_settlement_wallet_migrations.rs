@@ -0,0 +1,198 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SettlementWallet::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SettlementWallet::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::MerchantId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_settlement_wallet_merchant_id")
+                            .from(SettlementWallet::Table, SettlementWallet::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::Address)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::TokenMint)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::IsDefault)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::VerifiedAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SettlementWallet::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_settlement_wallet_merchant_id_address_token_mint")
+                    .table(SettlementWallet::Table)
+                    .col(SettlementWallet::MerchantId)
+                    .col(SettlementWallet::Address)
+                    .col(SettlementWallet::TokenMint)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // At most one default wallet per merchant and token
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "CREATE UNIQUE INDEX idx_settlement_wallet_merchant_id_token_mint_default \
+                 ON settlement_wallet (merchant_id, token_mint) WHERE is_default",
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletNonce::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WalletNonce::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WalletNonce::MerchantId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_wallet_nonce_merchant_id")
+                            .from(WalletNonce::Table, WalletNonce::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(WalletNonce::Address).string().not_null())
+                    .col(ColumnDef::new(WalletNonce::Nonce).string().not_null())
+                    .col(
+                        ColumnDef::new(WalletNonce::ExpiresAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletNonce::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_wallet_nonce_merchant_id_address")
+                    .table(WalletNonce::Table)
+                    .col(WalletNonce::MerchantId)
+                    .col(WalletNonce::Address)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing merchants keep settling to the wallet provisioned at sign up (USDC)
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO settlement_wallet (merchant_id, address, token_mint, is_default, verified_at, created_at) \
+                 SELECT merchant.id, \"user\".wallet_address, 'EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v', true, now(), now() \
+                 FROM merchant JOIN \"user\" ON \"user\".id = merchant.user_id \
+                 WHERE \"user\".wallet_address IS NOT NULL",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WalletNonce::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SettlementWallet::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SettlementWallet {
+    Table,
+    Id,
+    MerchantId,
+    Address,
+    TokenMint,
+    IsDefault,
+    VerifiedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WalletNonce {
+    Table,
+    Id,
+    MerchantId,
+    Address,
+    Nonce,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Wallet users sign up without an email
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .modify_column(ColumnDef::new(User::Email).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WalletChallenge::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WalletChallenge::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WalletChallenge::Address).string().not_null())
+                    .col(
+                        ColumnDef::new(WalletChallenge::Nonce)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    // Set when an existing user links the wallet, empty for sign in
+                    .col(ColumnDef::new(WalletChallenge::UserId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_wallet_challenge_user_id")
+                            .from(WalletChallenge::Table, WalletChallenge::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(WalletChallenge::ExpiresAt)
+                            .date_time()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WalletChallenge::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WalletChallenge::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .modify_column(ColumnDef::new(User::Email).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WalletChallenge {
+    Table,
+    Id,
+    Address,
+    Nonce,
+    UserId,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    Email,
+}
@@ -0,0 +1,196 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(ApiKeyMode::Type)
+                    .values([ApiKeyMode::Test, ApiKeyMode::Live])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKey::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiKey::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiKey::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_api_key_user_id")
+                            .from(ApiKey::Table, ApiKey::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(ApiKey::Name).string().not_null())
+                    // Visible start of the key so merchants can tell keys apart
+                    .col(
+                        ColumnDef::new(ApiKey::Prefix)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiKey::KeyHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiKey::Mode)
+                            .custom(ApiKeyMode::Type)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ApiKey::Scopes)
+                            .array(ColumnType::Text)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ApiKey::LastUsedAt).date_time())
+                    .col(ColumnDef::new(ApiKey::RevokedAt).date_time())
+                    .col(
+                        ColumnDef::new(ApiKey::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_api_key_user_id")
+                    .table(ApiKey::Table)
+                    .col(ApiKey::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Payments created with test keys are settled on the test cluster
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .add_column(
+                        ColumnDef::new(Payment::Livemode)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Test payments are left out of merchant analytics
+        manager
+            .get_connection()
+            .execute_unprepared(&merchant_daily_rollup("WHERE p.livemode"))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&merchant_daily_rollup(""))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .drop_column(Payment::Livemode)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ApiKey::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(ApiKeyMode::Type).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn merchant_daily_rollup(filter: &str) -> String {
+    format!(
+        r#"
+        DROP MATERIALIZED VIEW IF EXISTS merchant_daily_rollup;
+
+        CREATE MATERIALIZED VIEW merchant_daily_rollup AS
+        SELECT
+            p.user_id,
+            date_trunc('day', t.created_at) AS day,
+            count(*) AS transfers,
+            count(*) FILTER (WHERE t.status = 'pending') AS pending,
+            count(*) FILTER (WHERE t.status = 'completed') AS completed,
+            count(*) FILTER (WHERE t.status = 'rejected') AS rejected,
+            coalesce(sum(t.amount) FILTER (WHERE t.status = 'completed'), 0)::bigint AS revenue
+        FROM transfer t
+        JOIN payment p ON p.id = t.payment_id
+        {}
+        GROUP BY p.user_id, date_trunc('day', t.created_at);
+
+        CREATE UNIQUE INDEX idx_merchant_daily_rollup_user_id_day
+            ON merchant_daily_rollup (user_id, day);
+        "#,
+        filter
+    )
+}
+
+#[derive(DeriveIden)]
+enum ApiKey {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Prefix,
+    KeyHash,
+    Mode,
+    Scopes,
+    LastUsedAt,
+    RevokedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ApiKeyMode {
+    #[sea_orm(iden = "api_key_mode")]
+    Type,
+    Test,
+    Live,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payment {
+    Table,
+    Livemode,
+}
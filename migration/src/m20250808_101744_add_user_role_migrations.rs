@@ -0,0 +1,167 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enum, every user is a customer so only the other roles are stored
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(UserRoleType::Type)
+                    .values([
+                        UserRoleType::MerchantOwner,
+                        UserRoleType::MerchantStaff,
+                        UserRoleType::Admin,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserRole::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserRole::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserRole::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_role_user_id")
+                            .from(UserRole::Table, UserRole::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(UserRole::Role)
+                            .custom(UserRoleType::Type)
+                            .not_null(),
+                    )
+                    // Admin who granted the role, empty for roles granted by the platform itself
+                    .col(ColumnDef::new(UserRole::GrantedById).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_role_granted_by_id")
+                            .from(UserRole::Table, UserRole::GrantedById)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(UserRole::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_role_user_id_role")
+                    .table(UserRole::Table)
+                    .col(UserRole::UserId)
+                    .col(UserRole::Role)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Move existing admins and merchant owners over to roles
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO user_role (user_id, role)
+                SELECT id, 'admin'::user_role_type FROM "user" WHERE is_admin;
+
+                INSERT INTO user_role (user_id, role)
+                SELECT user_id, 'merchant_owner'::user_role_type FROM merchant;
+                "#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::IsAdmin)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::IsAdmin)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE "user" SET is_admin = true
+                WHERE id IN (SELECT user_id FROM user_role WHERE role = 'admin')
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(UserRole::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().if_exists().name(UserRoleType::Type).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum UserRole {
+    Table,
+    Id,
+    UserId,
+    Role,
+    GrantedById,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UserRoleType {
+    #[sea_orm(iden = "user_role_type")]
+    Type,
+    MerchantOwner,
+    MerchantStaff,
+    Admin,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+    IsAdmin,
+}
@@ -0,0 +1,230 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enum
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(MerchantMemberRole::Type)
+                    .values([MerchantMemberRole::Manager, MerchantMemberRole::Cashier])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(MerchantMember::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MerchantMember::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(MerchantMember::MerchantId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merchant_member_merchant_id")
+                            .from(MerchantMember::Table, MerchantMember::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    // Invitations are addressed by email, the user is set once accepted
+                    .col(ColumnDef::new(MerchantMember::Email).string().not_null())
+                    // A user works for at most one merchant
+                    .col(
+                        ColumnDef::new(MerchantMember::UserId)
+                            .integer()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merchant_member_user_id")
+                            .from(MerchantMember::Table, MerchantMember::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(MerchantMember::Role)
+                            .custom(MerchantMemberRole::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(MerchantMember::InvitedById).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_merchant_member_invited_by_id")
+                            .from(MerchantMember::Table, MerchantMember::InvitedById)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    // Hash of the token the invitee accepts with, cleared once accepted
+                    .col(
+                        ColumnDef::new(MerchantMember::InviteTokenHash)
+                            .string()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(MerchantMember::AcceptedAt).date_time())
+                    .col(
+                        ColumnDef::new(MerchantMember::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_merchant_member_merchant_id_email")
+                    .table(MerchantMember::Table)
+                    .col(MerchantMember::MerchantId)
+                    .col(MerchantMember::Email)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Payments belong to the merchant and remember who created them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .add_column(ColumnDef::new(Payment::MerchantId).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_payment_merchant_id")
+                            .from_tbl(Payment::Table)
+                            .from_col(Payment::MerchantId)
+                            .to_tbl(Merchant::Table)
+                            .to_col(Merchant::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .add_column(ColumnDef::new(Payment::CreatedById).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_payment_created_by_id")
+                            .from_tbl(Payment::Table)
+                            .from_col(Payment::CreatedById)
+                            .to_tbl(User::Table)
+                            .to_col(User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE payment p
+                SET merchant_id = m.id
+                FROM merchant m
+                WHERE m.user_id = p.user_id;
+
+                UPDATE payment SET created_by_id = user_id;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for name in ["fk_payment_created_by_id", "fk_payment_merchant_id"] {
+            manager
+                .drop_foreign_key(
+                    ForeignKey::drop()
+                        .table(Payment::Table)
+                        .name(name)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .drop_column(Payment::CreatedById)
+                    .drop_column(Payment::MerchantId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(MerchantMember::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(MerchantMemberRole::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MerchantMember {
+    Table,
+    Id,
+    MerchantId,
+    Email,
+    UserId,
+    Role,
+    InvitedById,
+    InviteTokenHash,
+    AcceptedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum MerchantMemberRole {
+    #[sea_orm(iden = "merchant_member_role")]
+    Type,
+    Manager,
+    Cashier,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payment {
+    Table,
+    MerchantId,
+    CreatedById,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
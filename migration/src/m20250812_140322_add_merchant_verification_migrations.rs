@@ -0,0 +1,373 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enums
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(VerificationStatus::Type)
+                    .values([
+                        VerificationStatus::Submitted,
+                        VerificationStatus::InReview,
+                        VerificationStatus::NeedsInfo,
+                        VerificationStatus::Approved,
+                        VerificationStatus::Rejected,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(VerificationDocumentKind::Type)
+                    .values([
+                        VerificationDocumentKind::BusinessRegistration,
+                        VerificationDocumentKind::ProofOfAddress,
+                        VerificationDocumentKind::OwnerIdentity,
+                        VerificationDocumentKind::Other,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(NotificationKind::Type)
+                    .values([NotificationKind::MerchantVerification])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(VerificationCase::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VerificationCase::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // One case per merchant, resubmissions reopen it
+                    .col(
+                        ColumnDef::new(VerificationCase::MerchantId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_verification_case_merchant_id")
+                            .from(VerificationCase::Table, VerificationCase::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationCase::Status)
+                            .custom(VerificationStatus::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VerificationCase::ReviewerId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_verification_case_reviewer_id")
+                            .from(VerificationCase::Table, VerificationCase::ReviewerId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationCase::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationCase::UpdatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_verification_case_status_updated_at")
+                    .table(VerificationCase::Table)
+                    .col(VerificationCase::Status)
+                    .col(VerificationCase::UpdatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VerificationDocument::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VerificationDocument::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationDocument::CaseId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_verification_document_case_id")
+                            .from(VerificationDocument::Table, VerificationDocument::CaseId)
+                            .to(VerificationCase::Table, VerificationCase::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationDocument::Kind)
+                            .custom(VerificationDocumentKind::Type)
+                            .not_null(),
+                    )
+                    // Private object, only shared through presigned links
+                    .col(
+                        ColumnDef::new(VerificationDocument::S3Key)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationDocument::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(VerificationEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(VerificationEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationEvent::CaseId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_verification_event_case_id")
+                            .from(VerificationEvent::Table, VerificationEvent::CaseId)
+                            .to(VerificationCase::Table, VerificationCase::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationEvent::FromStatus)
+                            .custom(VerificationStatus::Type),
+                    )
+                    .col(
+                        ColumnDef::new(VerificationEvent::ToStatus)
+                            .custom(VerificationStatus::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(VerificationEvent::ActorId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_verification_event_actor_id")
+                            .from(VerificationEvent::Table, VerificationEvent::ActorId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .col(ColumnDef::new(VerificationEvent::Note).string())
+                    .col(
+                        ColumnDef::new(VerificationEvent::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Notification::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Notification::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Notification::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notification_user_id")
+                            .from(Notification::Table, Notification::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Notification::Kind)
+                            .custom(NotificationKind::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Notification::Title).string().not_null())
+                    .col(ColumnDef::new(Notification::Body).string().not_null())
+                    .col(ColumnDef::new(Notification::ReadAt).date_time())
+                    .col(
+                        ColumnDef::new(Notification::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_notification_user_id_created_at")
+                    .table(Notification::Table)
+                    .col(Notification::UserId)
+                    .col(Notification::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            Notification::Table.into_iden(),
+            VerificationEvent::Table.into_iden(),
+            VerificationDocument::Table.into_iden(),
+            VerificationCase::Table.into_iden(),
+        ] {
+            manager
+                .drop_table(Table::drop().table(table).if_exists().to_owned())
+                .await?;
+        }
+
+        for name in [
+            NotificationKind::Type.into_iden(),
+            VerificationDocumentKind::Type.into_iden(),
+            VerificationStatus::Type.into_iden(),
+        ] {
+            manager
+                .drop_type(Type::drop().if_exists().name(name).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum VerificationCase {
+    Table,
+    Id,
+    MerchantId,
+    Status,
+    ReviewerId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum VerificationDocument {
+    Table,
+    Id,
+    CaseId,
+    Kind,
+    S3Key,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum VerificationEvent {
+    Table,
+    Id,
+    CaseId,
+    FromStatus,
+    ToStatus,
+    ActorId,
+    Note,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Notification {
+    Table,
+    Id,
+    UserId,
+    Kind,
+    Title,
+    Body,
+    ReadAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum VerificationStatus {
+    #[sea_orm(iden = "verification_status")]
+    Type,
+    Submitted,
+    InReview,
+    NeedsInfo,
+    Approved,
+    Rejected,
+}
+
+#[derive(DeriveIden)]
+enum VerificationDocumentKind {
+    #[sea_orm(iden = "verification_document_kind")]
+    Type,
+    BusinessRegistration,
+    ProofOfAddress,
+    OwnerIdentity,
+    Other,
+}
+
+#[derive(DeriveIden)]
+enum NotificationKind {
+    #[sea_orm(iden = "notification_kind")]
+    Type,
+    MerchantVerification,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
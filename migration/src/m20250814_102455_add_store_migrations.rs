@@ -0,0 +1,147 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(Store::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Store::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Store::MerchantId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_store_merchant_id")
+                            .from(Store::Table, Store::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(ColumnDef::new(Store::Name).string().not_null())
+                    .col(ColumnDef::new(Store::Address).string().not_null())
+                    .col(ColumnDef::new(Store::Latitude).double())
+                    .col(ColumnDef::new(Store::Longitude).double())
+                    // Weekly schedule, a list of days with their opening and closing times
+                    .col(
+                        ColumnDef::new(Store::OpeningHours)
+                            .json_binary()
+                            .not_null()
+                            .default(Expr::cust("'[]'::jsonb")),
+                    )
+                    .col(ColumnDef::new(Store::Cover).string())
+                    .col(
+                        ColumnDef::new(Store::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_store_merchant_id_name")
+                    .table(Store::Table)
+                    .col(Store::MerchantId)
+                    .col(Store::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Payments can be taken at one of the merchant's stores
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .add_column(ColumnDef::new(Payment::StoreId).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_payment_store_id")
+                            .from_tbl(Payment::Table)
+                            .from_col(Payment::StoreId)
+                            .to_tbl(Store::Table)
+                            .to_col(Store::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_payment_store_id")
+                    .table(Payment::Table)
+                    .col(Payment::StoreId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .table(Payment::Table)
+                    .name("fk_payment_store_id")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Payment::Table)
+                    .drop_column(Payment::StoreId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Store::Table).if_exists().to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Store {
+    Table,
+    Id,
+    MerchantId,
+    Name,
+    Address,
+    Latitude,
+    Longitude,
+    OpeningHours,
+    Cover,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Payment {
+    Table,
+    StoreId,
+}
@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Merchants get their own location and can be taken out of discovery
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Merchant::Table)
+                    .add_column(ColumnDef::new(Merchant::Latitude).double())
+                    .add_column(ColumnDef::new(Merchant::Longitude).double())
+                    .add_column(
+                        ColumnDef::new(Merchant::IsActive)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Trigram search on names and great circle distance on coordinates
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE EXTENSION IF NOT EXISTS pg_trgm;
+                CREATE EXTENSION IF NOT EXISTS cube;
+                CREATE EXTENSION IF NOT EXISTS earthdistance;
+
+                CREATE INDEX idx_merchant_display_name_trgm
+                    ON merchant USING gin (display_name gin_trgm_ops);
+
+                CREATE INDEX idx_merchant_discovery
+                    ON merchant (category, display_name, id)
+                    WHERE is_verified AND is_active;
+
+                CREATE INDEX idx_merchant_location
+                    ON merchant USING gist (ll_to_earth(latitude, longitude))
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
+
+                CREATE INDEX idx_store_location
+                    ON store USING gist (ll_to_earth(latitude, longitude))
+                    WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Extensions are left installed, other schemas may depend on them
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP INDEX IF EXISTS idx_store_location;
+                DROP INDEX IF EXISTS idx_merchant_location;
+                DROP INDEX IF EXISTS idx_merchant_discovery;
+                DROP INDEX IF EXISTS idx_merchant_display_name_trgm;
+                "#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Merchant::Table)
+                    .drop_column(Merchant::IsActive)
+                    .drop_column(Merchant::Longitude)
+                    .drop_column(Merchant::Latitude)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Latitude,
+    Longitude,
+    IsActive,
+}
@@ -0,0 +1,259 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(Category::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Category::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // Sub categories point to their parent, only one level deep
+                    .col(ColumnDef::new(Category::ParentId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_parent_id")
+                            .from(Category::Table, Category::ParentId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .col(
+                        ColumnDef::new(Category::Slug)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Category::Icon).string())
+                    .col(
+                        ColumnDef::new(Category::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Category::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(CategoryTranslation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CategoryTranslation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslation::CategoryId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_translation_category_id")
+                            .from(CategoryTranslation::Table, CategoryTranslation::CategoryId)
+                            .to(Category::Table, Category::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslation::Locale)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CategoryTranslation::Name)
+                            .string()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_category_translation_category_id_locale")
+                    .table(CategoryTranslation::Table)
+                    .col(CategoryTranslation::CategoryId)
+                    .col(CategoryTranslation::Locale)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Seed the enum values as top level categories and move merchants over
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Merchant::Table)
+                    .add_column(ColumnDef::new(Merchant::CategoryId).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk_merchant_category_id")
+                            .from_tbl(Merchant::Table)
+                            .from_col(Merchant::CategoryId)
+                            .to_tbl(Category::Table)
+                            .to_col(Category::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO category (slug, position) VALUES
+                    ('restaurant', 0), ('groceries', 1), ('other', 2);
+
+                INSERT INTO category_translation (category_id, locale, name)
+                SELECT id, 'en', CASE slug
+                    WHEN 'restaurant' THEN 'Restaurant'
+                    WHEN 'groceries' THEN 'Groceries'
+                    ELSE 'Other'
+                END
+                FROM category;
+
+                UPDATE merchant m SET category_id = c.id
+                FROM category c
+                WHERE c.slug = CASE m.category
+                    WHEN 'grocerries' THEN 'groceries'
+                    ELSE m.category::text
+                END;
+                "#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Merchant::Table)
+                    .modify_column(ColumnDef::new(Merchant::CategoryId).integer().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. Drop the enum, discovery is now indexed by category id
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP INDEX IF EXISTS idx_merchant_discovery;
+                ALTER TABLE merchant DROP COLUMN category;
+                DROP TYPE merchant_category;
+
+                CREATE INDEX idx_merchant_discovery
+                    ON merchant (category_id, display_name, id)
+                    WHERE is_verified AND is_active;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sub categories collapse into their parent, anything unknown becomes other
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP INDEX IF EXISTS idx_merchant_discovery;
+
+                CREATE TYPE merchant_category AS ENUM ('restaurant', 'grocerries', 'other');
+                ALTER TABLE merchant ADD COLUMN category merchant_category;
+
+                UPDATE merchant m SET category = CASE coalesce(p.slug, c.slug)
+                    WHEN 'restaurant' THEN 'restaurant'::merchant_category
+                    WHEN 'groceries' THEN 'grocerries'::merchant_category
+                    ELSE 'other'::merchant_category
+                END
+                FROM category c
+                LEFT JOIN category p ON p.id = c.parent_id
+                WHERE c.id = m.category_id;
+
+                ALTER TABLE merchant ALTER COLUMN category SET NOT NULL;
+
+                CREATE INDEX idx_merchant_discovery
+                    ON merchant (category, display_name, id)
+                    WHERE is_verified AND is_active;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .table(Merchant::Table)
+                    .name("fk_merchant_category_id")
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Merchant::Table)
+                    .drop_column(Merchant::CategoryId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CategoryTranslation::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Category::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Category {
+    Table,
+    Id,
+    ParentId,
+    Slug,
+    Icon,
+    Position,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum CategoryTranslation {
+    Table,
+    Id,
+    CategoryId,
+    Locale,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    CategoryId,
+}
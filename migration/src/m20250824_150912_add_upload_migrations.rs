@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. Create Enums
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(UploadPurpose::Type)
+                    .values([
+                        UploadPurpose::VerificationDocument,
+                        UploadPurpose::UserDocument,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. Create Tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(Upload::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Upload::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Upload::UserId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_upload_user_id")
+                            .from(Upload::Table, Upload::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    // Set for files stored under the merchant's folder
+                    .col(ColumnDef::new(Upload::MerchantId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_upload_merchant_id")
+                            .from(Upload::Table, Upload::MerchantId)
+                            .to(Merchant::Table, Merchant::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(Upload::Purpose)
+                            .custom(UploadPurpose::Type)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Upload::Key).string().not_null().unique_key())
+                    .col(ColumnDef::new(Upload::ContentType).string().not_null())
+                    // Declared by the client and signed into the upload URL
+                    .col(ColumnDef::new(Upload::Size).big_integer().not_null())
+                    .col(ColumnDef::new(Upload::ExpiresAt).date_time().not_null())
+                    .col(ColumnDef::new(Upload::ConfirmedAt).date_time())
+                    // Set once the file is attached to what it was uploaded for
+                    .col(ColumnDef::new(Upload::ConsumedAt).date_time())
+                    .col(
+                        ColumnDef::new(Upload::CreatedAt)
+                            .date_time()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_upload_user_id")
+                    .table(Upload::Table)
+                    .col(Upload::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Upload::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(UploadPurpose::Type)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Upload {
+    Table,
+    Id,
+    UserId,
+    MerchantId,
+    Purpose,
+    Key,
+    ContentType,
+    Size,
+    ExpiresAt,
+    ConfirmedAt,
+    ConsumedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum UploadPurpose {
+    #[sea_orm(iden = "upload_purpose")]
+    Type,
+    VerificationDocument,
+    UserDocument,
+}
+
+#[derive(DeriveIden)]
+enum Merchant {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    Id,
+}
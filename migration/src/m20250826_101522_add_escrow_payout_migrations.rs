@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Payout saved but not confirmed on-chain yet
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(EscrowStatus::Type)
+                    .add_value(EscrowStatus::Releasing)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(EscrowStatus::Type)
+                    .add_value(EscrowStatus::Refunding)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Legs and reference key of the payout, the reference finds the payout if it landed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Escrow::Table)
+                    .add_column(ColumnDef::new(Escrow::PayoutLegs).json_binary())
+                    .add_column(
+                        ColumnDef::new(Escrow::PayoutReference)
+                            .string()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Escrow::Table)
+                    .drop_column(Escrow::PayoutLegs)
+                    .drop_column(Escrow::PayoutReference)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Postgres can't drop enum values, releasing and refunding stay on escrow_status
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Escrow {
+    Table,
+    PayoutLegs,
+    PayoutReference,
+}
+
+#[derive(DeriveIden)]
+enum EscrowStatus {
+    #[sea_orm(iden = "escrow_status")]
+    Type,
+    Releasing,
+    Refunding,
+}
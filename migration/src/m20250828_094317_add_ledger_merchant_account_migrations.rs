@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The type is recreated instead of altered, a value added with ADD VALUE can't be used
+        // before the migration transaction commits
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                ALTER TYPE ledger_account_type RENAME TO ledger_account_type_old;
+                CREATE TYPE ledger_account_type AS ENUM
+                    ('user', 'wallet', 'treasury', 'escrow', 'adjustment', 'merchant');
+                ALTER TABLE ledger_account ALTER COLUMN account_type TYPE ledger_account_type
+                    USING account_type::text::ledger_account_type;
+                DROP TYPE ledger_account_type_old;
+                "#,
+            )
+            .await?;
+
+        // Merchant payouts move off the user accounts, rewards and adjustments stay there
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO ledger_account (account_type, reference)
+                SELECT DISTINCT 'merchant'::ledger_account_type, a.reference
+                FROM ledger_account a
+                JOIN ledger_entry e ON e.ledger_account_id = a.id
+                JOIN ledger_transaction t ON t.id = e.ledger_transaction_id
+                WHERE a.account_type = 'user' AND t.kind IN ('transfer', 'escrow_release')
+                ON CONFLICT DO NOTHING;
+
+                UPDATE ledger_entry e SET ledger_account_id = m.id
+                FROM ledger_account a, ledger_transaction t, ledger_account m
+                WHERE a.id = e.ledger_account_id
+                    AND t.id = e.ledger_transaction_id
+                    AND a.account_type = 'user'
+                    AND t.kind IN ('transfer', 'escrow_release')
+                    AND m.account_type = 'merchant'
+                    AND m.reference = a.reference;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                INSERT INTO ledger_account (account_type, reference)
+                SELECT 'user', reference FROM ledger_account WHERE account_type = 'merchant'
+                ON CONFLICT DO NOTHING;
+
+                UPDATE ledger_entry e SET ledger_account_id = u.id
+                FROM ledger_account m, ledger_account u
+                WHERE m.id = e.ledger_account_id
+                    AND m.account_type = 'merchant'
+                    AND u.account_type = 'user'
+                    AND u.reference = m.reference;
+
+                DELETE FROM ledger_account WHERE account_type = 'merchant';
+
+                ALTER TYPE ledger_account_type RENAME TO ledger_account_type_old;
+                CREATE TYPE ledger_account_type AS ENUM
+                    ('user', 'wallet', 'treasury', 'escrow', 'adjustment');
+                ALTER TABLE ledger_account ALTER COLUMN account_type TYPE ledger_account_type
+                    USING account_type::text::ledger_account_type;
+                DROP TYPE ledger_account_type_old;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Signature whose transaction couldn't be fetched, the rest of the scan carries on
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(ReconciliationIssueKind::Type)
+                    .add_value(ReconciliationIssueKind::LookupFailed)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres can't drop enum values, lookup_failed stays on reconciliation_issue_kind
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReconciliationIssueKind {
+    #[sea_orm(iden = "reconciliation_issue_kind")]
+    Type,
+    LookupFailed,
+}
@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::{extension::postgres::Type, *};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Invitations are delivered to the invitee as a notification
+        manager
+            .alter_type(
+                Type::alter()
+                    .name(NotificationKind::Type)
+                    .add_value(NotificationKind::MerchantInvitation)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres can't drop enum values, merchant_invitation stays on notification_kind
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum NotificationKind {
+    #[sea_orm(iden = "notification_kind")]
+    Type,
+    MerchantInvitation,
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every row carries the time of the refresh that produced it, in UTC like created_at
+        manager
+            .get_connection()
+            .execute_unprepared(&merchant_daily_rollup(
+                ", now() AT TIME ZONE 'UTC' AS refreshed_at",
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&merchant_daily_rollup(""))
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn merchant_daily_rollup(refreshed_at: &str) -> String {
+    format!(
+        r#"
+        DROP MATERIALIZED VIEW IF EXISTS merchant_daily_rollup;
+
+        CREATE MATERIALIZED VIEW merchant_daily_rollup AS
+        SELECT
+            p.user_id,
+            date_trunc('day', t.created_at) AS day,
+            count(*) AS transfers,
+            count(*) FILTER (WHERE t.status = 'pending') AS pending,
+            count(*) FILTER (WHERE t.status = 'completed') AS completed,
+            count(*) FILTER (WHERE t.status = 'rejected') AS rejected,
+            coalesce(sum(t.amount) FILTER (WHERE t.status = 'completed'), 0)::bigint AS revenue
+            {}
+        FROM transfer t
+        JOIN payment p ON p.id = t.payment_id
+        WHERE p.livemode
+        GROUP BY p.user_id, date_trunc('day', t.created_at);
+
+        CREATE UNIQUE INDEX idx_merchant_daily_rollup_user_id_day
+            ON merchant_daily_rollup (user_id, day);
+        "#,
+        refreshed_at
+    )
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // A wallet shared by several users stays with the oldest one, the others sign in as before
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE "user" u SET wallet_address = NULL
+                WHERE EXISTS (
+                    SELECT 1 FROM "user" o
+                    WHERE o.wallet_address = u.wallet_address AND o.id < u.id
+                );
+                "#,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_user_wallet_address")
+                    .table(User::Table)
+                    .col(User::WalletAddress)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_user_wallet_address")
+                    .table(User::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum User {
+    Table,
+    WalletAddress,
+}
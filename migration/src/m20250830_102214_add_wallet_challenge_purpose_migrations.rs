@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Settlement wallet nonces become wallet challenges owned by the merchant
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE TYPE wallet_challenge_purpose AS ENUM
+                    ('sign_in', 'link_wallet', 'settlement_wallet');
+                ALTER TABLE wallet_challenge
+                    ADD COLUMN purpose wallet_challenge_purpose,
+                    ADD COLUMN merchant_id integer
+                        CONSTRAINT fk_wallet_challenge_merchant_id
+                        REFERENCES merchant (id) ON DELETE CASCADE;
+
+                UPDATE wallet_challenge SET purpose = CASE
+                    WHEN user_id IS NULL THEN 'sign_in'::wallet_challenge_purpose
+                    ELSE 'link_wallet'::wallet_challenge_purpose
+                END;
+
+                INSERT INTO wallet_challenge
+                    (purpose, merchant_id, address, nonce, expires_at, created_at)
+                SELECT 'settlement_wallet', merchant_id, address, nonce, expires_at, created_at
+                FROM wallet_nonce
+                ON CONFLICT (nonce) DO NOTHING;
+
+                ALTER TABLE wallet_challenge ALTER COLUMN purpose SET NOT NULL;
+                CREATE INDEX idx_wallet_challenge_purpose_address
+                    ON wallet_challenge (purpose, address);
+                DROP TABLE wallet_nonce;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE TABLE wallet_nonce (
+                    id serial PRIMARY KEY,
+                    merchant_id integer NOT NULL
+                        CONSTRAINT fk_wallet_nonce_merchant_id
+                        REFERENCES merchant (id) ON DELETE CASCADE,
+                    address varchar NOT NULL,
+                    nonce varchar NOT NULL,
+                    expires_at timestamp NOT NULL,
+                    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE INDEX idx_wallet_nonce_merchant_id_address
+                    ON wallet_nonce (merchant_id, address);
+
+                INSERT INTO wallet_nonce (merchant_id, address, nonce, expires_at, created_at)
+                SELECT merchant_id, address, nonce, expires_at, created_at
+                FROM wallet_challenge
+                WHERE purpose = 'settlement_wallet';
+                DELETE FROM wallet_challenge WHERE purpose = 'settlement_wallet';
+
+                DROP INDEX idx_wallet_challenge_purpose_address;
+                ALTER TABLE wallet_challenge DROP COLUMN merchant_id, DROP COLUMN purpose;
+                DROP TYPE wallet_challenge_purpose;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Entity the notification is about, the invitation to accept or the verification case
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notification::Table)
+                    .add_column(ColumnDef::new(Notification::ReferenceId).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Invitations are accepted by id, pending ones are found from the token in their
+        // notification and the token is removed from the body
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE notification n SET reference_id = m.id
+                FROM merchant_member m
+                WHERE n.kind = 'merchant_invitation'
+                    AND m.invite_token_hash = encode(
+                        sha256(convert_to(substring(n.body from 'token: (\w+)$'), 'UTF8')),
+                        'base64'
+                    );
+
+                UPDATE notification
+                SET body = regexp_replace(body, ', accept it with this token: \w+$', '')
+                WHERE kind = 'merchant_invitation';
+                "#,
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerchantMember::Table)
+                    .drop_column(MerchantMember::InviteTokenHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Invitations pending at rollback have no token left and have to be sent again
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(MerchantMember::Table)
+                    .add_column(
+                        ColumnDef::new(MerchantMember::InviteTokenHash)
+                            .string()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Notification::Table)
+                    .drop_column(Notification::ReferenceId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Notification {
+    Table,
+    ReferenceId,
+}
+
+#[derive(DeriveIden)]
+enum MerchantMember {
+    Table,
+    InviteTokenHash,
+}
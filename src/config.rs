@@ -1,6 +1,13 @@
 use std::{env, sync::OnceLock};
 
-use crate::error::{Error, Result};
+use crate::{
+    constants::{
+        DEFAULT_LOCAL_STORAGE_DIR, DEFAULT_STORAGE_BACKEND, PRIVY_BASE_URL, SOLANA_TEST_RPC_URL,
+        TEST_USDC_MINT,
+    },
+    error::{Error, Result},
+    services::storage::StorageBackend,
+};
 
 #[allow(non_snake_case)]
 pub struct Config {
@@ -11,13 +18,24 @@ pub struct Config {
     pub AWS_ACCESS_KEY_ID: String,
     pub AWS_SECRET_ACCESS_KEY: String,
     pub AWS_BUCKET_REGION: String,
+    pub STORAGE_BACKEND: String,
+    pub LOCAL_STORAGE_DIR: String,
+    pub PUBLIC_URL: String,
     pub RPC_URL: String,
+    pub TEST_RPC_URL: String,
+    pub TEST_USDC_MINT: String,
     pub FEE_FAUCET_SECRET: String,
     pub FEE_FAUCET_PRIVATE_KEY: String,
+    pub ESCROW_SECRET: String,
+    pub ESCROW_PRIVATE_KEY: String,
     pub GOOGLE_OAUTH_CLIENT_ID: String,
     pub GOOGLE_OAUTH_CLIENT_SECRET: String,
     pub PRIVY_APP_ID: String,
     pub PRIVY_APP_SECRET: String,
+    pub PRIVY_BASE_URL: String,
+    pub PRIVY_VERIFICATION_KEY: String,
+    pub PRIVY_WEBHOOK_SECRET: String,
+    pub TWO_FACTOR_SECRET: String,
 }
 
 pub fn config() -> &'static Config {
@@ -33,21 +51,47 @@ pub fn config() -> &'static Config {
 
 impl Config {
     pub fn load_env() -> Result<Config> {
+        let storage_backend = get_var("SERVICE_STORAGE_BACKEND")
+            .unwrap_or_else(|_| DEFAULT_STORAGE_BACKEND.to_string());
+        // The bucket is only needed with the s3 storage backend
+        let s3 = matches!(storage_backend.parse(), Ok(StorageBackend::S3));
+        let aws_var = |key| match get_var(key) {
+            Err(_) if !s3 => Ok(String::new()),
+            value => value,
+        };
+
         let config = Config {
             PORT: get_var("PORT")?,
             DB_URL: get_var("SERVICE_DB_URL")?,
             ACCESS_SECRET_KEY: get_var("SERVICE_ACCESS_SECRET_KEY")?,
-            AWS_BUCKET_NAME: get_var("SERVICE_AWS_BUCKET_NAME")?,
-            AWS_ACCESS_KEY_ID: get_var("SERVICE_AWS_ACCESS_KEY_ID")?,
-            AWS_SECRET_ACCESS_KEY: get_var("SERVICE_AWS_SECRET_ACCESS_KEY")?,
-            AWS_BUCKET_REGION: get_var("SERVICE_AWS_BUCKET_REGION")?,
+            AWS_BUCKET_NAME: aws_var("SERVICE_AWS_BUCKET_NAME")?,
+            AWS_ACCESS_KEY_ID: aws_var("SERVICE_AWS_ACCESS_KEY_ID")?,
+            AWS_SECRET_ACCESS_KEY: aws_var("SERVICE_AWS_SECRET_ACCESS_KEY")?,
+            AWS_BUCKET_REGION: aws_var("SERVICE_AWS_BUCKET_REGION")?,
+            STORAGE_BACKEND: storage_backend,
+            LOCAL_STORAGE_DIR: get_var("SERVICE_LOCAL_STORAGE_DIR")
+                .unwrap_or_else(|_| DEFAULT_LOCAL_STORAGE_DIR.to_string()),
+            PUBLIC_URL: get_var("SERVICE_PUBLIC_URL").unwrap_or_else(|_| {
+                format!("http://localhost:{}", env::var("PORT").unwrap_or_default())
+            }),
             RPC_URL: get_var("SERVICE_RPC_URL")?,
+            TEST_RPC_URL: get_var("SERVICE_TEST_RPC_URL")
+                .unwrap_or_else(|_| SOLANA_TEST_RPC_URL.to_string()),
+            TEST_USDC_MINT: get_var("SERVICE_TEST_USDC_MINT")
+                .unwrap_or_else(|_| TEST_USDC_MINT.to_string()),
             FEE_FAUCET_SECRET: get_var("SERVICE_FEE_FAUCET_SECRET")?,
             FEE_FAUCET_PRIVATE_KEY: get_var("SERVICE_FEE_FAUCET_PRIVATE_KEY")?,
+            ESCROW_SECRET: get_var("SERVICE_ESCROW_SECRET")?,
+            ESCROW_PRIVATE_KEY: get_var("SERVICE_ESCROW_PRIVATE_KEY")?,
             GOOGLE_OAUTH_CLIENT_ID: get_var("SERVICE_GOOGLE_CLIENT_ID")?,
             GOOGLE_OAUTH_CLIENT_SECRET: get_var("SERVICE_GOOGLE_CLIENT_SECRET")?,
             PRIVY_APP_ID: get_var("SERVICE_PRIVY_APP_ID")?,
             PRIVY_APP_SECRET: get_var("SERVICE_PRIVY_APP_SECRET")?,
+            PRIVY_BASE_URL: get_var("SERVICE_PRIVY_BASE_URL")
+                .unwrap_or_else(|_| PRIVY_BASE_URL.to_string()),
+            PRIVY_VERIFICATION_KEY: get_var("SERVICE_PRIVY_VERIFICATION_KEY")?,
+            PRIVY_WEBHOOK_SECRET: get_var("SERVICE_PRIVY_WEBHOOK_SECRET")?,
+            TWO_FACTOR_SECRET: get_var("SERVICE_TWO_FACTOR_SECRET")?,
         };
 
         Ok(config)
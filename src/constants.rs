@@ -29,7 +29,111 @@ pub fn validate_password(password: &String) -> Result<(), ValidationError> {
 
 pub const USDC_MINT: &'static str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 pub const BASE_USDC: u64 = 100_00_00;
+pub const BASIS_POINTS: u64 = 10_000;
+pub const MAX_PAYMENT_SPLITS: usize = 5;
 pub const TREASURY_PUBKEY: &'static str = "7SMfVRrJw75vPzHCQ3ckUCT9igMRre8VHmodTbaVv4R";
 
-pub const GOOGLE_OAUTH_BASE_URL: &'static str = "https://www.googleapis.com";
+pub const DEFAULT_PAGE_SIZE: u64 = 20;
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+pub const ESCROW_RELEASE_AFTER_DAYS: i64 = 7;
+pub const ESCROW_RELEASE_JOB_INTERVAL_SECS: u64 = 300;
+pub const ESCROW_PAYOUT_RETRY_AFTER_SECS: i64 = 300;
+
+// Pending transfers older than this can't land anymore, their blockhash has expired
+pub const PROMOTION_RESERVATION_SECS: i64 = 300;
+
+pub const GOOGLE_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+pub const GOOGLE_ISSUERS: [&str; 2] = ["accounts.google.com", "https://accounts.google.com"];
+pub const GOOGLE_JWKS_CACHE_SECS: u64 = 3600;
+pub const GOOGLE_JWKS_MIN_REFRESH_SECS: u64 = 60;
 pub const PRIVY_BASE_URL: &'static str = "https://auth.privy.io/api";
+// Either "s3" or "local"
+pub const DEFAULT_STORAGE_BACKEND: &str = "s3";
+pub const DEFAULT_LOCAL_STORAGE_DIR: &str = "storage";
+pub const PRIVY_ISSUER: &str = "privy.io";
+pub const PRIVY_WEBHOOK_TOLERANCE_SECS: i64 = 300;
+pub const PRIVY_SYNC_INTERVAL_SECS: u64 = 900;
+pub const PRIVY_SYNC_BATCH_SIZE: u64 = 100;
+pub const PRIVY_SYNC_RETRY_AFTER_SECS: i64 = 86400;
+
+pub const STATEMENT_SYNC_MAX_ROWS: u64 = 500;
+pub const STATEMENT_MAX_RANGE_DAYS: i64 = 366;
+pub const STATEMENT_LINK_EXPIRY_SECS: u64 = 3600;
+
+pub const ANALYTICS_ROLLUP_REFRESH_SECS: u64 = 600;
+pub const ANALYTICS_MAX_RANGE_DAYS: i64 = 366;
+
+pub const RECONCILIATION_JOB_INTERVAL_SECS: u64 = 3600;
+pub const RECONCILIATION_LOOKBACK_HOURS: i64 = 24;
+pub const RECONCILIATION_MAX_SIGNATURE_PAGES: usize = 5;
+pub const RECONCILIATION_RPC_CONCURRENCY: usize = 8;
+
+pub const WALLET_NONCE_EXPIRY_SECS: i64 = 300;
+
+pub const TOTP_ISSUER: &str = "Zunopay";
+pub const TOTP_STEP_SECS: u64 = 30;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+pub const TWO_FACTOR_CHALLENGE_EXPIRY_SECS: i64 = 300;
+pub const TWO_FACTOR_MAX_ATTEMPTS: i32 = 5;
+pub const TWO_FACTOR_LOCKOUT_SECS: i64 = 900;
+pub const STEP_UP_TOKEN_EXPIRY_SECS: i64 = 300;
+pub const STEP_UP_HEADER: &str = "x-step-up-token";
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+pub const API_KEY_LIVE_PREFIX: &str = "zk_live_";
+pub const API_KEY_TEST_PREFIX: &str = "zk_test_";
+pub const API_KEY_LAST_USED_RESOLUTION_SECS: i64 = 60;
+pub const SOLANA_TEST_RPC_URL: &str = "https://api.devnet.solana.com";
+// Circle's devnet USDC
+pub const TEST_USDC_MINT: &str = "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU";
+
+pub const MERCHANT_INVITATION_EXPIRY_SECS: i64 = 604800;
+
+pub const VERIFICATION_DOCUMENT_LINK_EXPIRY_SECS: u64 = 900;
+pub const VERIFICATION_MAX_DOCUMENTS: usize = 10;
+// Whole USDC, unverified merchants are limited until their verification is approved
+pub const UNVERIFIED_MERCHANT_MAX_TRANSFER_AMOUNT: u64 = 500;
+pub const UNVERIFIED_MERCHANT_DAILY_VOLUME: u64 = 2_000;
+
+// Meters around the searched coordinates
+pub const DISCOVERY_DEFAULT_RADIUS_METERS: f64 = 10_000.0;
+pub const DISCOVERY_MAX_RADIUS_METERS: f64 = 100_000.0;
+
+// Category names fall back to this locale when a translation is missing
+pub const DEFAULT_LOCALE: &str = "en";
+
+pub const SLUG_MAX_LENGTH: usize = 60;
+// Inserts retried when a concurrent sign up takes the merchant slug first
+pub const MERCHANT_SLUG_ATTEMPTS: u32 = 3;
+// Words dropped from generated slugs so merchant pages can't look like our own routes
+pub const RESERVED_SLUG_WORDS: &[&str] = &[
+    "admin", "api", "auth", "help", "login", "logout", "official", "settings", "signup", "support",
+    "www", "zuno", "zunopay",
+];
+
+pub const IMAGE_ALLOWED_MIME_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp"];
+pub const IMAGE_MAX_DIMENSION: u32 = 8_000;
+pub const IMAGE_JPEG_QUALITY: u8 = 85;
+// Images decoded at once, a decode at the max dimension holds about 200MB
+pub const IMAGE_PROCESSING_CONCURRENCY: usize = 2;
+// Per file, the whole request is also capped by the 2MB default body limit
+pub const IMAGE_MAX_SIZE_BYTES: usize = 2 * 1024 * 1024;
+
+// Where the local storage backend serves its files
+pub const LOCAL_STORAGE_ROUTE: &str = "/storage";
+// KYB documents, statements and user documents live under it, the bucket must not make it public
+pub const PRIVATE_STORAGE_PREFIX: &str = "private";
+
+// Direct uploads through pre-signed URLs
+pub const UPLOAD_URL_EXPIRY_SECS: u64 = 900;
+pub const UPLOAD_DOWNLOAD_LINK_EXPIRY_SECS: u64 = 900;
+pub const UPLOAD_MAX_SIZE_BYTES: i64 = 25 * 1024 * 1024;
+// Allowed content types and the extension their keys get
+pub const UPLOAD_CONTENT_TYPES: &[(&str, &str)] = &[
+    ("application/pdf", "pdf"),
+    ("image/jpeg", "jpg"),
+    ("image/png", "png"),
+];
+pub const UPLOAD_CLEANUP_JOB_INTERVAL_SECS: u64 = 3600;
+pub const UPLOAD_CLEANUP_BATCH_SIZE: u64 = 100;
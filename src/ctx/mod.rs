@@ -1,4 +1,7 @@
 pub mod mw_require_auth;
+pub mod mw_require_permission;
+pub mod mw_require_scope;
+pub mod mw_require_step_up;
 pub mod mw_resolve_ctx;
 pub mod mw_resolve_google_ctx;
 
@@ -9,9 +12,57 @@ use axum::{
 };
 use serde::Serialize;
 
+use crate::{
+    db::entity::sea_orm_active_enums::{MerchantMemberRole, UserRoleType},
+    services::{
+        api_key::ApiKeyScope,
+        auth::dto::authorization_dto::GoogleClaims,
+        role::{Permission, member_role_permissions, role_permissions},
+    },
+};
+
 #[derive(Debug, Clone)]
 pub struct Ctx {
     pub user_id: i32,
+
+    // Set when the request is authenticated with a merchant API key instead of a user session
+    pub api_key: Option<ApiKeyCtx>,
+
+    // Roles on top of customer, which every user is
+    pub roles: Vec<UserRoleType>,
+
+    // Set for staff, the merchant they work for and their role there
+    pub member: Option<MemberCtx>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyCtx {
+    pub id: i32,
+    pub livemode: bool,
+    pub scopes: Vec<ApiKeyScope>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MemberCtx {
+    pub merchant_id: i32,
+    pub role: MerchantMemberRole,
+}
+
+impl Ctx {
+    // User sessions always work with live data
+    pub fn livemode(&self) -> bool {
+        self.api_key.as_ref().is_none_or(|api_key| api_key.livemode)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.roles
+            .iter()
+            .any(|role| role_permissions(*role).contains(&permission))
+            || self
+                .member
+                .as_ref()
+                .is_some_and(|member| member_role_permissions(member.role).contains(&permission))
+    }
 }
 
 type CtxResult = core::result::Result<Ctx, Error>;
@@ -24,11 +75,18 @@ where
     type Rejection = Error;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> CtxResult {
-        parts
+        let ctx = parts
             .extensions
             .get::<CtxResult>()
             .ok_or(Error::FailedCtxErrorNotInRequestExtension)?
-            .clone()
+            .clone()?;
+
+        // API keys only reach routes that opt in through `mw_require_scope`
+        if ctx.api_key.is_some() && parts.extensions.get::<ApiKeyScope>().is_none() {
+            return Err(Error::PermissionDenied);
+        }
+
+        Ok(ctx)
     }
 }
 
@@ -40,7 +98,7 @@ where
 
 #[derive(Debug, Clone)]
 pub struct GoogleCtx {
-    pub email: String,
+    pub claims: GoogleClaims,
 }
 
 impl<S> FromRequestParts<S> for GoogleCtx
@@ -0,0 +1,26 @@
+use crate::{
+    ctx::CtxResult,
+    error::{Error, Result},
+    services::role::Permission,
+};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+// Route level check of a permission granted by one of the user's roles
+pub async fn mw_require_permission(
+    State(permission): State<Permission>,
+    ctx: CtxResult,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let ctx = ctx?;
+
+    if !ctx.has_permission(permission) {
+        return Err(Error::PermissionDenied);
+    }
+
+    Ok(next.run(request).await)
+}
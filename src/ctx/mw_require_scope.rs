@@ -0,0 +1,36 @@
+use crate::{
+    ctx::CtxResult,
+    error::{Error, Result},
+    services::api_key::ApiKeyScope,
+};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+/**
+ * Requires authentication like `mw_require_auth`, but also lets API keys with the scope through.
+ * The scope is recorded on the request so `Ctx` accepts the API key in the handler.
+ */
+pub async fn mw_require_scope(
+    State(scope): State<ApiKeyScope>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response> {
+    let ctx = request
+        .extensions()
+        .get::<CtxResult>()
+        .ok_or(Error::FailedCtxErrorNotInRequestExtension)?
+        .clone()?;
+
+    if ctx
+        .api_key
+        .is_some_and(|api_key| !api_key.scopes.contains(&scope))
+    {
+        return Err(Error::PermissionDenied);
+    }
+
+    request.extensions_mut().insert(scope);
+    Ok(next.run(request).await)
+}
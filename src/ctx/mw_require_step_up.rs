@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{
+    constants::STEP_UP_HEADER,
+    ctx::CtxResult,
+    error::{Error, Result},
+    services::{AppState, two_factor::TwoFactorService, user::UserService},
+};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+// Sensitive actions need a fresh step-up token from users with two factor on
+pub async fn mw_require_step_up(
+    State(state): State<Arc<AppState>>,
+    ctx: CtxResult,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let ctx = ctx?;
+
+    let user = UserService::find_one(state, ctx.user_id).await?;
+    if user.totp_enabled_at.is_some() {
+        let step_up_token = request
+            .headers()
+            .get(STEP_UP_HEADER)
+            .and_then(|val| val.to_str().ok())
+            .ok_or(Error::StepUpRequired)?;
+
+        TwoFactorService::verify_step_up_token(user.id, step_up_token)
+            .map_err(|_| Error::StepUpRequired)?;
+    }
+
+    Ok(next.run(request).await)
+}
@@ -1,9 +1,13 @@
 use crate::{
     config,
-    constants::AUTH_PREFIX,
+    constants::{API_KEY_HEADER, AUTH_PREFIX},
     ctx::{Ctx, CtxResult},
+    db::entity::sea_orm_active_enums::UserRoleType,
     error::{Error, Result},
-    services::{AppState, auth::dto::authorization_dto::Claims, user::UserService},
+    services::{
+        AppState, api_key::ApiKeyService, auth::dto::authorization_dto::Claims,
+        merchant_member::MerchantMemberService, role::RoleService, user::UserService,
+    },
 };
 use axum::{
     extract::{Request, State},
@@ -29,6 +33,35 @@ pub async fn mw_resolve_ctx(
 }
 
 async fn _mw_resolve_ctx(state: Arc<AppState>, headers: HeaderMap) -> CtxResult {
+    // Server to server integrations authenticate with a merchant API key
+    let (user_id, api_key) = match headers.get(API_KEY_HEADER) {
+        Some(api_key) => {
+            let api_key = api_key
+                .to_str()
+                .map_err(|_| Error::ServiceError("Failed to parse API key".to_string()))?;
+            let (user_id, api_key) = ApiKeyService::authenticate(state.clone(), api_key).await?;
+            (user_id, Some(api_key))
+        }
+        None => (resolve_auth_token(state.clone(), &headers).await?, None),
+    };
+
+    let roles = RoleService::find_roles(state.clone(), user_id).await?;
+    let member = if roles.contains(&UserRoleType::MerchantStaff) {
+        MerchantMemberService::find_member_ctx(state, user_id).await?
+    } else {
+        None
+    };
+
+    Ok(Ctx {
+        user_id,
+        api_key,
+        roles,
+        member,
+    })
+}
+
+// User id of the bearer auth token
+async fn resolve_auth_token(state: Arc<AppState>, headers: &HeaderMap) -> Result<i32> {
     let token_str = headers
         .get(AUTHORIZATION)
         .ok_or(Error::MissingAuthToken)?
@@ -47,9 +80,5 @@ async fn _mw_resolve_ctx(state: Arc<AppState>, headers: HeaderMap) -> CtxResult
     )?;
 
     let user = UserService::find_one(state, auth_token.claims.user.user_id).await?;
-
-    //todo: verify user role
-    let ctx = Ctx { user_id: user.id };
-
-    Ok(ctx)
+    Ok(user.id)
 }
@@ -2,10 +2,9 @@ use std::{str::FromStr, sync::Arc};
 
 use crate::{
     config,
-    constants::GOOGLE_OAUTH_BASE_URL,
     ctx::GoogleCtx,
     error::{Error, Result},
-    services::{AppState, auth::dto::authorization_dto::GoogleUser},
+    services::{AppState, auth::AuthService},
 };
 use axum::{
     Json,
@@ -40,28 +39,10 @@ pub async fn mw_resolve_google_ctx(
         return Err(Error::MissingAuthToken);
     }
 
-    let payload = fetch_google_user_info(token).await?;
-    let google_ctx = GoogleCtx {
-        email: payload.email,
-    };
+    // The frontend sends the Google ID token, verified locally against Google's keys
+    let claims = AuthService::verify_google_id_token(state, token).await?;
+    let google_ctx = GoogleCtx { claims };
     request.extensions_mut().insert(google_ctx);
 
     Ok(next.run(request).await)
 }
-
-async fn fetch_google_user_info(access_token: &str) -> Result<GoogleUser> {
-    let client = Client::new();
-    let response = client
-        .get(format!("{}/oauth2/v3/userinfo", GOOGLE_OAUTH_BASE_URL))
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|_| Error::MiddlewareError("Failed to fetch google user"))?;
-
-    let google_user = response
-        .json::<GoogleUser>()
-        .await
-        .map_err(|_| Error::MiddlewareError("Failed to parse google user"))?;
-
-    Ok(google_user)
-}
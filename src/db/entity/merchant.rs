@@ -1,10 +1,9 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
-use super::sea_orm_active_enums::MerchantCategory;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "merchant")]
 pub struct Model {
     #[sea_orm(primary_key)]
@@ -14,16 +13,43 @@ pub struct Model {
     pub slug: String,
     pub cover: Option<String>,
     pub address: String,
-    pub category: MerchantCategory,
     pub s3_bucket_slug: String,
     pub business_registration_number: Option<String>,
     pub is_verified: bool,
     #[sea_orm(unique)]
     pub user_id: i32,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub latitude: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub longitude: Option<f64>,
+    pub is_active: bool,
+    pub category_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::category::Entity",
+        from = "Column::CategoryId",
+        to = "super::category::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    Category,
+    #[sea_orm(has_many = "super::merchant_member::Entity")]
+    MerchantMember,
+    #[sea_orm(has_many = "super::merchant_slug_history::Entity")]
+    MerchantSlugHistory,
+    #[sea_orm(has_many = "super::payment::Entity")]
+    Payment,
+    #[sea_orm(has_many = "super::promotion::Entity")]
+    Promotion,
+    #[sea_orm(has_many = "super::settlement_wallet::Entity")]
+    SettlementWallet,
+    #[sea_orm(has_many = "super::store::Entity")]
+    Store,
+    #[sea_orm(has_many = "super::upload::Entity")]
+    Upload,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -32,6 +58,58 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     User,
+    #[sea_orm(has_one = "super::verification_case::Entity")]
+    VerificationCase,
+    #[sea_orm(has_many = "super::wallet_challenge::Entity")]
+    WalletChallenge,
+}
+
+impl Related<super::category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl Related<super::merchant_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MerchantMember.def()
+    }
+}
+
+impl Related<super::merchant_slug_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MerchantSlugHistory.def()
+    }
+}
+
+impl Related<super::payment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payment.def()
+    }
+}
+
+impl Related<super::promotion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Promotion.def()
+    }
+}
+
+impl Related<super::settlement_wallet::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SettlementWallet.def()
+    }
+}
+
+impl Related<super::store::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Store.def()
+    }
+}
+
+impl Related<super::upload::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Upload.def()
+    }
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -40,4 +118,16 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::verification_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VerificationCase.def()
+    }
+}
+
+impl Related<super::wallet_challenge::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WalletChallenge.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -2,9 +2,35 @@
 
 pub mod prelude;
 
+pub mod api_key;
+pub mod category;
+pub mod category_translation;
+pub mod escrow;
+pub mod escrow_event;
+pub mod ledger_account;
+pub mod ledger_entry;
+pub mod ledger_transaction;
 pub mod merchant;
+pub mod merchant_member;
+pub mod merchant_slug_history;
+pub mod notification;
 pub mod payment;
+pub mod payment_split;
+pub mod promotion;
+pub mod promotion_redemption;
+pub mod reconciliation_issue;
+pub mod reconciliation_report;
+pub mod recovery_code;
 pub mod referral_code;
 pub mod sea_orm_active_enums;
+pub mod settlement_wallet;
+pub mod statement_export;
+pub mod store;
 pub mod transfer;
+pub mod upload;
 pub mod user;
+pub mod user_role;
+pub mod verification_case;
+pub mod verification_document;
+pub mod verification_event;
+pub mod wallet_challenge;
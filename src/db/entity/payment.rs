@@ -17,12 +17,43 @@ pub struct Model {
     #[sea_orm(unique)]
     pub public_id: Uuid,
     pub user_id: i32,
+    pub is_escrow: bool,
+    pub livemode: bool,
+    pub merchant_id: Option<i32>,
+    pub created_by_id: Option<i32>,
+    pub store_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::merchant::Entity",
+        from = "Column::MerchantId",
+        to = "super::merchant::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Merchant,
+    #[sea_orm(has_many = "super::payment_split::Entity")]
+    PaymentSplit,
+    #[sea_orm(
+        belongs_to = "super::store::Entity",
+        from = "Column::StoreId",
+        to = "super::store::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Store,
     #[sea_orm(has_many = "super::transfer::Entity")]
     Transfer,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::CreatedById",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    User2,
     #[sea_orm(
         belongs_to = "super::user::Entity",
         from = "Column::UserId",
@@ -30,18 +61,30 @@ pub enum Relation {
         on_update = "NoAction",
         on_delete = "Cascade"
     )]
-    User,
+    User1,
 }
 
-impl Related<super::transfer::Entity> for Entity {
+impl Related<super::merchant::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Transfer.def()
+        Relation::Merchant.def()
+    }
+}
+
+impl Related<super::payment_split::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentSplit.def()
     }
 }
 
-impl Related<super::user::Entity> for Entity {
+impl Related<super::store::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::User.def()
+        Relation::Store.def()
+    }
+}
+
+impl Related<super::transfer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Transfer.def()
     }
 }
 
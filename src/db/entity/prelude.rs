@@ -1,7 +1,33 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
+pub use super::api_key::Entity as ApiKey;
+pub use super::category::Entity as Category;
+pub use super::category_translation::Entity as CategoryTranslation;
+pub use super::escrow::Entity as Escrow;
+pub use super::escrow_event::Entity as EscrowEvent;
+pub use super::ledger_account::Entity as LedgerAccount;
+pub use super::ledger_entry::Entity as LedgerEntry;
+pub use super::ledger_transaction::Entity as LedgerTransaction;
 pub use super::merchant::Entity as Merchant;
+pub use super::merchant_member::Entity as MerchantMember;
+pub use super::merchant_slug_history::Entity as MerchantSlugHistory;
+pub use super::notification::Entity as Notification;
 pub use super::payment::Entity as Payment;
+pub use super::payment_split::Entity as PaymentSplit;
+pub use super::promotion::Entity as Promotion;
+pub use super::promotion_redemption::Entity as PromotionRedemption;
+pub use super::reconciliation_issue::Entity as ReconciliationIssue;
+pub use super::reconciliation_report::Entity as ReconciliationReport;
+pub use super::recovery_code::Entity as RecoveryCode;
 pub use super::referral_code::Entity as ReferralCode;
+pub use super::settlement_wallet::Entity as SettlementWallet;
+pub use super::statement_export::Entity as StatementExport;
+pub use super::store::Entity as Store;
 pub use super::transfer::Entity as Transfer;
+pub use super::upload::Entity as Upload;
 pub use super::user::Entity as User;
+pub use super::user_role::Entity as UserRole;
+pub use super::verification_case::Entity as VerificationCase;
+pub use super::verification_document::Entity as VerificationDocument;
+pub use super::verification_event::Entity as VerificationEvent;
+pub use super::wallet_challenge::Entity as WalletChallenge;
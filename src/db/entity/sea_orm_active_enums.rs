@@ -2,24 +2,96 @@
 
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
-use strum_macros::EnumString;
 
-#[derive(
-    Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, EnumString,
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "api_key_mode")]
+pub enum ApiKeyMode {
+    #[sea_orm(string_value = "test")]
+    Test,
+    #[sea_orm(string_value = "live")]
+    Live,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "discount_type")]
+pub enum DiscountType {
+    #[sea_orm(string_value = "percentage")]
+    Percentage,
+    #[sea_orm(string_value = "fixed")]
+    Fixed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "escrow_status")]
+pub enum EscrowStatus {
+    #[sea_orm(string_value = "held")]
+    Held,
+    #[sea_orm(string_value = "disputed")]
+    Disputed,
+    #[sea_orm(string_value = "released")]
+    Released,
+    #[sea_orm(string_value = "refunded")]
+    Refunded,
+    #[sea_orm(string_value = "releasing")]
+    Releasing,
+    #[sea_orm(string_value = "refunding")]
+    Refunding,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "ledger_account_type"
 )]
-#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "merchant_category")]
-pub enum MerchantCategory {
-    #[strum(serialize = "restaurant", ascii_case_insensitive)]
-    #[sea_orm(string_value = "restaurant")]
-    Restaurant,
-
-    #[strum(serialize = "grocerries", ascii_case_insensitive)]
-    #[sea_orm(string_value = "grocerries")]
-    Grocerries,
-
-    #[strum(serialize = "other", ascii_case_insensitive)]
-    #[sea_orm(string_value = "other")]
-    Other,
+pub enum LedgerAccountType {
+    #[sea_orm(string_value = "user")]
+    User,
+    #[sea_orm(string_value = "wallet")]
+    Wallet,
+    #[sea_orm(string_value = "treasury")]
+    Treasury,
+    #[sea_orm(string_value = "escrow")]
+    Escrow,
+    #[sea_orm(string_value = "adjustment")]
+    Adjustment,
+    #[sea_orm(string_value = "merchant")]
+    Merchant,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "ledger_transaction_kind"
+)]
+pub enum LedgerTransactionKind {
+    #[sea_orm(string_value = "transfer")]
+    Transfer,
+    #[sea_orm(string_value = "escrow_release")]
+    EscrowRelease,
+    #[sea_orm(string_value = "escrow_refund")]
+    EscrowRefund,
+    #[sea_orm(string_value = "referral_reward")]
+    ReferralReward,
+    #[sea_orm(string_value = "adjustment")]
+    Adjustment,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "merchant_member_role"
+)]
+pub enum MerchantMemberRole {
+    #[sea_orm(string_value = "cashier")]
+    Cashier,
+    #[sea_orm(string_value = "manager")]
+    Manager,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "notification_kind")]
+pub enum NotificationKind {
+    #[sea_orm(string_value = "merchant_verification")]
+    MerchantVerification,
+    #[sea_orm(string_value = "merchant_invitation")]
+    MerchantInvitation,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "payment_category")]
@@ -28,6 +100,48 @@ pub enum PaymentCategory {
     OneTime,
 }
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "reconciliation_issue_kind"
+)]
+pub enum ReconciliationIssueKind {
+    #[sea_orm(string_value = "unmatched_receipt")]
+    UnmatchedReceipt,
+    #[sea_orm(string_value = "missing_on_chain")]
+    MissingOnChain,
+    #[sea_orm(string_value = "amount_mismatch")]
+    AmountMismatch,
+    #[sea_orm(string_value = "lookup_failed")]
+    LookupFailed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "split_type")]
+pub enum SplitType {
+    #[sea_orm(string_value = "percentage")]
+    Percentage,
+    #[sea_orm(string_value = "fixed")]
+    Fixed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "statement_format")]
+pub enum StatementFormat {
+    #[sea_orm(string_value = "csv")]
+    Csv,
+    #[sea_orm(string_value = "pdf")]
+    Pdf,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "statement_status")]
+pub enum StatementStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
 #[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "transfer_status")]
 pub enum TransferStatus {
     #[sea_orm(string_value = "pending")]
@@ -37,3 +151,69 @@ pub enum TransferStatus {
     #[sea_orm(string_value = "rejected")]
     Rejected,
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "upload_purpose")]
+pub enum UploadPurpose {
+    #[sea_orm(string_value = "user_document")]
+    UserDocument,
+    #[sea_orm(string_value = "verification_document")]
+    VerificationDocument,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "user_role_type")]
+pub enum UserRoleType {
+    #[sea_orm(string_value = "merchant_owner")]
+    MerchantOwner,
+    #[sea_orm(string_value = "merchant_staff")]
+    MerchantStaff,
+    #[sea_orm(string_value = "admin")]
+    Admin,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "verification_document_kind"
+)]
+pub enum VerificationDocumentKind {
+    #[sea_orm(string_value = "business_registration")]
+    BusinessRegistration,
+    #[sea_orm(string_value = "other")]
+    Other,
+    #[sea_orm(string_value = "owner_identity")]
+    OwnerIdentity,
+    #[sea_orm(string_value = "proof_of_address")]
+    ProofOfAddress,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "verification_status"
+)]
+pub enum VerificationStatus {
+    #[sea_orm(string_value = "approved")]
+    Approved,
+    #[sea_orm(string_value = "in_review")]
+    InReview,
+    #[sea_orm(string_value = "needs_info")]
+    NeedsInfo,
+    #[sea_orm(string_value = "rejected")]
+    Rejected,
+    #[sea_orm(string_value = "submitted")]
+    Submitted,
+}
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(
+    rs_type = "String",
+    db_type = "Enum",
+    enum_name = "wallet_challenge_purpose"
+)]
+pub enum WalletChallengePurpose {
+    #[sea_orm(string_value = "link_wallet")]
+    LinkWallet,
+    #[sea_orm(string_value = "settlement_wallet")]
+    SettlementWallet,
+    #[sea_orm(string_value = "sign_in")]
+    SignIn,
+}
@@ -16,10 +16,19 @@ pub struct Model {
     pub signature: Option<String>,
     pub status: TransferStatus,
     pub created_at: DateTime,
+    pub amount: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_one = "super::escrow::Entity")]
+    Escrow,
+    #[sea_orm(has_many = "super::ledger_transaction::Entity")]
+    LedgerTransaction,
+    #[sea_orm(has_one = "super::promotion_redemption::Entity")]
+    PromotionRedemption,
+    #[sea_orm(has_many = "super::reconciliation_issue::Entity")]
+    ReconciliationIssue,
     #[sea_orm(
         belongs_to = "super::payment::Entity",
         from = "Column::PaymentId",
@@ -30,6 +39,30 @@ pub enum Relation {
     Payment,
 }
 
+impl Related<super::escrow::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Escrow.def()
+    }
+}
+
+impl Related<super::ledger_transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LedgerTransaction.def()
+    }
+}
+
+impl Related<super::promotion_redemption::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PromotionRedemption.def()
+    }
+}
+
+impl Related<super::reconciliation_issue::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReconciliationIssue.def()
+    }
+}
+
 impl Related<super::payment::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Payment.def()
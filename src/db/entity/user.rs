@@ -9,21 +9,61 @@ pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     #[sea_orm(unique)]
-    pub email: String,
+    pub email: Option<String>,
     pub password: String,
+    #[sea_orm(unique)]
     pub wallet_address: Option<String>,
     pub created_at: DateTime,
     pub email_verified_at: Option<DateTime>,
     #[sea_orm(unique)]
     pub s3_bucket_slug: String,
+    #[sea_orm(unique)]
+    pub google_sub: Option<String>,
+    #[sea_orm(unique)]
+    pub privy_id: Option<String>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<DateTime>,
+    pub totp_last_step: Option<i64>,
+    pub two_factor_failed_attempts: i32,
+    pub two_factor_locked_until: Option<DateTime>,
+    pub avatar: Option<String>,
+    pub privy_synced_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::api_key::Entity")]
+    ApiKey,
+    #[sea_orm(has_many = "super::escrow_event::Entity")]
+    EscrowEvent,
     #[sea_orm(has_one = "super::merchant::Entity")]
     Merchant,
-    #[sea_orm(has_many = "super::payment::Entity")]
-    Payment,
+    #[sea_orm(has_many = "super::notification::Entity")]
+    Notification,
+    #[sea_orm(has_many = "super::recovery_code::Entity")]
+    RecoveryCode,
+    #[sea_orm(has_many = "super::statement_export::Entity")]
+    StatementExport,
+    #[sea_orm(has_many = "super::upload::Entity")]
+    Upload,
+    #[sea_orm(has_many = "super::verification_case::Entity")]
+    VerificationCase,
+    #[sea_orm(has_many = "super::verification_event::Entity")]
+    VerificationEvent,
+    #[sea_orm(has_many = "super::wallet_challenge::Entity")]
+    WalletChallenge,
+}
+
+impl Related<super::api_key::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiKey.def()
+    }
+}
+
+impl Related<super::escrow_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EscrowEvent.def()
+    }
 }
 
 impl Related<super::merchant::Entity> for Entity {
@@ -32,9 +72,45 @@ impl Related<super::merchant::Entity> for Entity {
     }
 }
 
-impl Related<super::payment::Entity> for Entity {
+impl Related<super::notification::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notification.def()
+    }
+}
+
+impl Related<super::recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCode.def()
+    }
+}
+
+impl Related<super::statement_export::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StatementExport.def()
+    }
+}
+
+impl Related<super::upload::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Upload.def()
+    }
+}
+
+impl Related<super::verification_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VerificationCase.def()
+    }
+}
+
+impl Related<super::verification_event::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::VerificationEvent.def()
+    }
+}
+
+impl Related<super::wallet_challenge::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Payment.def()
+        Relation::WalletChallenge.def()
     }
 }
 
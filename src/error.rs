@@ -12,8 +12,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, AsRefStr, Clone)]
 pub enum Error {
     EnvMissing(&'static str),
+    EnvInvalid(&'static str),
     FailedCtxErrorNotInRequestExtension,
     MissingAuthToken,
+    PermissionDenied,
+    StepUpRequired,
     JwtError(jsonwebtoken::errors::Error),
     DatabaseError(Arc<sea_orm::error::DbErr>),
     ServiceError(String),
@@ -1,5 +0,0 @@
-macro_rules! dto_from_multipart {
-    () => {
-        todo!()
-    };
-}
@@ -1 +0,0 @@
-pub mod dto_from_multipart;
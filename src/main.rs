@@ -5,7 +5,6 @@ mod constants;
 mod ctx;
 mod db;
 mod error;
-mod macros;
 mod routes;
 mod services;
 
@@ -47,7 +46,6 @@ async fn main() -> Result<()> {
 
     color_eyre::install().unwrap();
 
-    //todo: add X-API-KEY header when start using it
     //CORS layer
     let cors =
         CorsLayer::new()
@@ -60,6 +58,8 @@ async fn main() -> Result<()> {
                 ACCEPT_ENCODING, // To get supported response compression encoding
                 ACCESS_CONTROL_ALLOW_HEADERS,
                 HeaderName::from_static("x-requested-with"),
+                HeaderName::from_static(constants::API_KEY_HEADER),
+                HeaderName::from_static(constants::STEP_UP_HEADER),
             ])
             .allow_methods([
                 Method::GET,
@@ -0,0 +1,59 @@
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::services::category::category_handler;
+use crate::services::escrow::escrow_handler::{find_disputed_escrows, resolve};
+use crate::services::ledger::ledger_handler::{
+    check, find_balances, post_adjustment, post_referral_reward,
+};
+use crate::services::reconciliation::reconciliation_handler::{find_all, find_one, run};
+use crate::services::role::{Permission, role_handler};
+use crate::services::user::user_handler::update_merchant_status;
+use crate::services::verification::verification_handler;
+use crate::{error::Result, services::AppState};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{delete, get, patch, post},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/category",
+            get(category_handler::find_all).post(category_handler::create),
+        )
+        .route(
+            "/category/{id}",
+            patch(category_handler::update).delete(category_handler::remove),
+        )
+        .route("/escrow", get(find_disputed_escrows))
+        .route("/escrow/{id}/resolve", patch(resolve))
+        .route("/ledger/balances", get(find_balances))
+        .route("/ledger/check", get(check))
+        .route("/ledger/adjustment", post(post_adjustment))
+        .route("/ledger/referral-reward", post(post_referral_reward))
+        .route("/reconciliation", get(find_all))
+        .route("/reconciliation/run", post(run))
+        .route("/reconciliation/{id}", get(find_one))
+        .route("/merchant/{id}/active", patch(update_merchant_status))
+        .route(
+            "/user/{id}/role",
+            get(role_handler::find_all).post(role_handler::grant),
+        )
+        .route("/user/{id}/role/{role}", delete(role_handler::revoke))
+        .route("/verification", get(verification_handler::find_queue))
+        .route("/verification/{id}", get(verification_handler::find_one))
+        .route(
+            "/verification/{id}/review",
+            patch(verification_handler::start_review),
+        )
+        .route(
+            "/verification/{id}/decision",
+            patch(verification_handler::decide),
+        )
+        .layer(middleware::from_fn_with_state(
+            Permission::ManagePlatform,
+            mw_require_permission,
+        ))
+        .with_state(app_state)
+}
@@ -4,7 +4,10 @@ use crate::{
         AppState,
         auth::{
             AuthService,
-            auth_handler::{login, login_with_google, register},
+            auth_handler::{
+                create_wallet_challenge, login, login_with_google, login_with_privy,
+                login_with_two_factor, login_with_wallet, privy_webhook, register,
+            },
         },
     },
 };
@@ -23,5 +26,10 @@ pub fn routes(app_state: Arc<AppState>) -> Router {
         ))
         .route("/register", post(register))
         .route("/login", patch(login))
+        .route("/login/two-factor", patch(login_with_two_factor))
+        .route("/wallet/nonce", post(create_wallet_challenge))
+        .route("/wallet/verify", post(login_with_wallet))
+        .route("/privy", post(login_with_privy))
+        .route("/privy/webhook", post(privy_webhook))
         .with_state(app_state)
 }
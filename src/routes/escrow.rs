@@ -0,0 +1,45 @@
+use crate::ctx::mw_require_auth::mw_require_auth;
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::ctx::mw_require_step_up::mw_require_step_up;
+use crate::services::escrow::escrow_handler::{
+    confirm, dispute, find_buyer_escrows, find_merchant_escrows, refund,
+};
+use crate::services::role::Permission;
+use crate::{error::Result, services::AppState};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{get, patch},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/buyer", get(find_buyer_escrows))
+        .route(
+            "/merchant",
+            get(find_merchant_escrows).route_layer(middleware::from_fn_with_state(
+                Permission::ViewPayments,
+                mw_require_permission,
+            )),
+        )
+        .route("/{id}/confirm", patch(confirm))
+        .route("/{id}/dispute", patch(dispute))
+        .route(
+            "/{id}/refund",
+            patch(refund)
+                .route_layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    mw_require_step_up,
+                ))
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::RefundPayments,
+                    mw_require_permission,
+                )),
+        )
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            mw_require_auth,
+        ))
+        .with_state(app_state)
+}
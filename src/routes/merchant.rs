@@ -0,0 +1,72 @@
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::ctx::mw_require_step_up::mw_require_step_up;
+use crate::services::analytics::analytics_handler::find_merchant_analytics;
+use crate::services::api_key::api_key_handler;
+use crate::services::merchant_member::merchant_member_handler;
+use crate::services::role::Permission;
+use crate::services::settlement_wallet::settlement_wallet_handler::{
+    create, create_nonce, find_all, remove, set_default,
+};
+use crate::services::store::store_handler;
+use crate::services::user::user_handler::update_merchant_slug;
+use crate::services::verification::verification_handler;
+use crate::{error::Result, services::AppState};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{delete, get, patch, post},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    // Changing where payouts go needs a step-up token
+    let step_up = middleware::from_fn_with_state(app_state.clone(), mw_require_step_up);
+
+    Router::new()
+        .route("/analytics", get(find_merchant_analytics))
+        .route(
+            "/api-key",
+            get(api_key_handler::find_all)
+                .merge(post(api_key_handler::create).route_layer(step_up.clone())),
+        )
+        .route("/api-key/{id}", delete(api_key_handler::revoke))
+        .route(
+            "/member",
+            get(merchant_member_handler::find_all).post(merchant_member_handler::invite),
+        )
+        .route(
+            "/member/{id}",
+            patch(merchant_member_handler::update_role).delete(merchant_member_handler::remove),
+        )
+        .route("/slug", patch(update_merchant_slug))
+        .route(
+            "/store",
+            get(store_handler::find_all).post(store_handler::create),
+        )
+        .route(
+            "/store/{id}",
+            patch(store_handler::update).delete(store_handler::remove),
+        )
+        .route(
+            "/verification",
+            get(verification_handler::find_own).post(verification_handler::submit),
+        )
+        .route("/settlement-wallet/nonce", post(create_nonce))
+        .route(
+            "/settlement-wallet",
+            get(find_all).merge(post(create).route_layer(step_up.clone())),
+        )
+        .route(
+            "/settlement-wallet/{id}",
+            delete(remove).route_layer(step_up.clone()),
+        )
+        .route(
+            "/settlement-wallet/{id}/default",
+            patch(set_default).route_layer(step_up),
+        )
+        .layer(middleware::from_fn_with_state(
+            Permission::ManageMerchant,
+            mw_require_permission,
+        ))
+        .with_state(app_state)
+}
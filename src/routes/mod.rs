@@ -1,23 +1,70 @@
+pub mod admin;
 pub mod app;
 pub mod auth;
+pub mod escrow;
+pub mod merchant;
 pub mod payment;
+pub mod promotion;
+pub mod statement;
+pub mod upload;
 pub mod user;
 
 use crate::{
+    config::config,
+    constants::LOCAL_STORAGE_ROUTE,
     ctx::{mw_require_auth::mw_require_auth, mw_resolve_ctx::mw_resolve_ctx},
     error::Result,
-    services::AppState,
+    services::{
+        AppState,
+        analytics::AnalyticsService,
+        auth::AuthService,
+        escrow::EscrowService,
+        reconciliation::ReconciliationService,
+        storage::{StorageBackend, local::mw_check_signature},
+        upload::UploadService,
+    },
 };
 use axum::{Extension, Router, middleware};
 use std::sync::Arc;
 use tower_cookies::CookieManagerLayer;
+use tower_http::services::ServeDir;
 
 pub async fn routes() -> Result<Router> {
     let app_state = Arc::new(AppState::new().await?);
-    let router = Router::new()
+
+    // Background jobs
+    tokio::spawn(EscrowService::run_release_job(app_state.clone()));
+    tokio::spawn(AnalyticsService::run_rollup_job(app_state.clone()));
+    tokio::spawn(ReconciliationService::run_reconciliation_job(
+        app_state.clone(),
+    ));
+    tokio::spawn(AuthService::run_privy_sync_job(app_state.clone()));
+    tokio::spawn(UploadService::run_cleanup_job(app_state.clone()));
+
+    let mut router = Router::new()
         .nest("/payment", payment::routes(app_state.clone()))
         .nest("/auth", auth::routes(app_state.clone()))
-        .merge(app::routes())
+        .nest("/user", user::routes(app_state.clone()))
+        .nest("/escrow", escrow::routes(app_state.clone()))
+        .nest("/merchant", merchant::routes(app_state.clone()))
+        .nest("/promotion", promotion::routes(app_state.clone()))
+        .nest("/statement", statement::routes(app_state.clone()))
+        .nest("/upload", upload::routes(app_state.clone()))
+        .nest("/admin", admin::routes(app_state.clone()))
+        .merge(app::routes());
+
+    // Uploaded files are public, same as objects in the S3 bucket, private ones need a signed link
+    if StorageBackend::from_config()? == StorageBackend::Local {
+        let secret = Arc::new(config().ACCESS_SECRET_KEY.clone());
+        router = router.nest_service(
+            LOCAL_STORAGE_ROUTE,
+            Router::new()
+                .fallback_service(ServeDir::new(&config().LOCAL_STORAGE_DIR))
+                .layer(middleware::from_fn_with_state(secret, mw_check_signature)),
+        );
+    }
+
+    let router = router
         .layer(middleware::from_fn_with_state(app_state, mw_resolve_ctx))
         .layer(CookieManagerLayer::new());
 
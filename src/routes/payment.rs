@@ -1,7 +1,10 @@
-use crate::ctx::mw_require_auth::mw_require_auth;
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::ctx::mw_require_scope::mw_require_scope;
+use crate::services::api_key::ApiKeyScope;
 use crate::services::payment::payment_handler::{
-    create, create_transfer, find_one, submit_transfer,
+    create, create_transfer, find_all, find_one, submit_transfer,
 };
+use crate::services::role::Permission;
 use crate::{error::Result, services::AppState};
 use axum::middleware;
 use axum::{
@@ -12,11 +15,31 @@ use std::sync::Arc;
 
 pub fn routes(app_state: Arc<AppState>) -> Router {
     Router::new()
-        .route("/create", post(create))
-        .layer(middleware::from_fn_with_state(
-            app_state.clone(),
-            mw_require_auth,
-        ))
+        // Merchant backends can use these with an API key
+        .route(
+            "/",
+            get(find_all)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::ViewPayments,
+                    mw_require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(
+                    ApiKeyScope::PaymentsRead,
+                    mw_require_scope,
+                )),
+        )
+        .route(
+            "/create",
+            post(create)
+                .route_layer(middleware::from_fn_with_state(
+                    Permission::ManagePayments,
+                    mw_require_permission,
+                ))
+                .route_layer(middleware::from_fn_with_state(
+                    ApiKeyScope::PaymentsWrite,
+                    mw_require_scope,
+                )),
+        )
         .route("/get/{id}", get(find_one))
         .route("/create-transfer", post(create_transfer))
         .route("/submit-transfer", post(submit_transfer))
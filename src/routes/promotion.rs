@@ -0,0 +1,22 @@
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::services::promotion::promotion_handler::{create, deactivate, find_all};
+use crate::services::role::Permission;
+use crate::{error::Result, services::AppState};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{get, patch, post},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/create", post(create))
+        .route("/get", get(find_all))
+        .route("/{id}/deactivate", patch(deactivate))
+        .layer(middleware::from_fn_with_state(
+            Permission::ManageMerchant,
+            mw_require_permission,
+        ))
+        .with_state(app_state)
+}
@@ -0,0 +1,21 @@
+use crate::ctx::mw_require_permission::mw_require_permission;
+use crate::services::role::Permission;
+use crate::services::statement::statement_handler::{export, find_one};
+use crate::{error::Result, services::AppState};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/export", post(export))
+        .route("/get/{id}", get(find_one))
+        .layer(middleware::from_fn_with_state(
+            Permission::ManageMerchant,
+            mw_require_permission,
+        ))
+        .with_state(app_state)
+}
@@ -0,0 +1,21 @@
+use crate::ctx::mw_require_auth::mw_require_auth;
+use crate::services::AppState;
+use crate::services::upload::upload_handler::{confirm, create, find_download_url};
+use axum::middleware;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+pub fn routes(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", post(create))
+        .route("/{id}/confirm", post(confirm))
+        .route("/{id}/download", get(find_download_url))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            mw_require_auth,
+        ))
+        .with_state(app_state)
+}
@@ -1,25 +1,50 @@
 use crate::ctx::mw_require_auth::mw_require_auth;
+use crate::services::auth::auth_handler::{create_link_wallet_challenge, link_wallet};
+use crate::services::category::category_handler;
+use crate::services::merchant_member::merchant_member_handler::accept;
+use crate::services::notification::notification_handler;
 use crate::services::payment::payment_handler::find_one;
+use crate::services::two_factor::two_factor_handler::{
+    confirm, create_step_up_token, disable, regenerate_recovery_codes, setup,
+};
 use crate::services::user::user_handler::{
-    create_merchant_profile, find_all_merchants, find_me, find_merchant,
+    create_merchant_profile, find_me, find_merchant, search_merchants, update_avatar,
 };
 use crate::{error::Result, services::AppState};
 use axum::middleware;
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{get, patch, post, put},
 };
 use std::sync::Arc;
 
 pub fn routes(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/get/me", get(find_me))
+        .route("/avatar", put(update_avatar))
+        .route("/wallet/link/nonce", post(create_link_wallet_challenge))
+        .route("/wallet/link", post(link_wallet))
+        .route("/two-factor/setup", post(setup))
+        .route("/two-factor/confirm", post(confirm))
+        .route("/two-factor/disable", post(disable))
+        .route(
+            "/two-factor/recovery-codes",
+            post(regenerate_recovery_codes),
+        )
+        .route("/two-factor/step-up", post(create_step_up_token))
+        .route("/merchant-invitation/{id}/accept", post(accept))
+        .route("/notification", get(notification_handler::find_all))
+        .route(
+            "/notification/{id}/read",
+            patch(notification_handler::mark_read),
+        )
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             mw_require_auth,
         ))
+        .route("/category", get(category_handler::find_all))
         .route("/get-merchant/{slug}", get(find_merchant))
-        .route("/get-merchant", get(find_all_merchants))
+        .route("/get-merchant", get(search_merchants))
         .route("/merchant-profile", post(create_merchant_profile))
         .with_state(app_state)
 }
@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        analytics::{
+            AnalyticsService,
+            dto::{analytics_dto::AnalyticsDto, analytics_query_dto::AnalyticsQueryDto},
+        },
+        error::Result,
+    },
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+pub async fn find_merchant_analytics(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Query(analytics_query_dto): Query<AnalyticsQueryDto>,
+) -> Result<Json<AnalyticsDto>> {
+    let analytics =
+        AnalyticsService::find_merchant_analytics(state, ctx, analytics_query_dto).await?;
+    Ok(Json(analytics.into()))
+}
@@ -0,0 +1,121 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+
+use crate::services::analytics::{MerchantAnalytics, RevenueBucket, StoreTotals};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevenueBucketDto {
+    pub period_start: NaiveDateTime,
+
+    // Token base units
+    pub revenue: u64,
+
+    pub completed_transfers: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransfersByStatusDto {
+    pub pending: u64,
+
+    pub completed: u64,
+
+    pub rejected: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreAnalyticsDto {
+    // Empty for payments not taken at a store
+    pub store_id: Option<i32>,
+
+    pub store_name: Option<String>,
+
+    pub transfers: u64,
+
+    pub completed_transfers: u64,
+
+    // Token base units
+    pub revenue: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsDto {
+    pub from: NaiveDate,
+
+    pub to: NaiveDate,
+
+    pub revenue: Vec<RevenueBucketDto>,
+
+    pub total_revenue: u64,
+
+    pub transfers_by_status: TransfersByStatusDto,
+
+    // Completed transfers over created transfers
+    pub conversion_rate: f64,
+
+    pub average_ticket: u64,
+
+    pub unique_payers: u64,
+
+    pub repeat_payers: u64,
+
+    pub repeat_payer_rate: f64,
+
+    pub stores: Vec<StoreAnalyticsDto>,
+}
+
+impl From<RevenueBucket> for RevenueBucketDto {
+    fn from(value: RevenueBucket) -> Self {
+        RevenueBucketDto {
+            period_start: value.bucket,
+            revenue: value.revenue as u64, // sum of completed transfer amounts
+            completed_transfers: value.completed as u64,
+        }
+    }
+}
+
+impl From<StoreTotals> for StoreAnalyticsDto {
+    fn from(value: StoreTotals) -> Self {
+        StoreAnalyticsDto {
+            store_id: value.store_id,
+            store_name: value.store_name,
+            transfers: value.transfers as u64,
+            completed_transfers: value.completed as u64,
+            revenue: value.revenue as u64,
+        }
+    }
+}
+
+impl From<MerchantAnalytics> for AnalyticsDto {
+    fn from(value: MerchantAnalytics) -> Self {
+        let ratio = |num: i64, den: i64| {
+            if den == 0 {
+                0.0
+            } else {
+                num as f64 / den as f64
+            }
+        };
+
+        let total = &value.totals;
+        AnalyticsDto {
+            from: value.from,
+            to: value.to,
+            revenue: value.buckets.into_iter().map(|val| val.into()).collect(),
+            total_revenue: total.revenue as u64,
+            transfers_by_status: TransfersByStatusDto {
+                pending: total.pending as u64,
+                completed: total.completed as u64,
+                rejected: total.rejected as u64,
+            },
+            conversion_rate: ratio(total.completed, total.transfers),
+            average_ticket: total.revenue.checked_div(total.completed).unwrap_or(0) as u64,
+            unique_payers: value.payers.unique_payers as u64,
+            repeat_payers: value.payers.repeat_payers as u64,
+            repeat_payer_rate: ratio(value.payers.repeat_payers, value.payers.unique_payers),
+            stores: value.stores.into_iter().map(|val| val.into()).collect(),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    // Field name for postgres `date_trunc`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsQueryDto {
+    pub from: NaiveDate,
+
+    // Exclusive
+    pub to: NaiveDate,
+
+    #[serde(default)]
+    pub granularity: Granularity,
+}
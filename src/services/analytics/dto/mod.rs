@@ -0,0 +1,2 @@
+pub mod analytics_dto;
+pub mod analytics_query_dto;
@@ -0,0 +1,234 @@
+pub mod analytics_handler;
+pub mod dto;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    constants::{ANALYTICS_MAX_RANGE_DAYS, ANALYTICS_ROLLUP_REFRESH_SECS},
+    ctx::Ctx,
+    services::{
+        AppState,
+        analytics::dto::analytics_query_dto::AnalyticsQueryDto,
+        error::{Result, ServiceError},
+        user::UserService,
+    },
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use sea_orm::{ConnectionTrait, DbBackend, FromQueryResult, Statement, Value};
+use tokio::time::interval;
+
+/**
+ * Per day transfer aggregates of a merchant's live payments. Days that ended before the last
+ * refresh of the `merchant_daily_rollup` view come from it, later ones are aggregated live.
+ * Status changes on those earlier days show up with the next refresh.
+ * Binds: $1 user id, $2 from, $3 to.
+ */
+const DAILY_AGGREGATES: &str = r#"
+    WITH rollup AS (
+        SELECT coalesce(
+            (SELECT date_trunc('day', refreshed_at) FROM merchant_daily_rollup LIMIT 1),
+            $2
+        ) AS until
+    ),
+    daily AS (
+        SELECT day, transfers, pending, completed, rejected, revenue
+        FROM merchant_daily_rollup
+        WHERE user_id = $1 AND day >= $2 AND day < least($3, (SELECT until FROM rollup))
+        UNION ALL
+        SELECT
+            date_trunc('day', t.created_at) AS day,
+            count(*) AS transfers,
+            count(*) FILTER (WHERE t.status = 'pending') AS pending,
+            count(*) FILTER (WHERE t.status = 'completed') AS completed,
+            count(*) FILTER (WHERE t.status = 'rejected') AS rejected,
+            coalesce(sum(t.amount) FILTER (WHERE t.status = 'completed'), 0)::bigint AS revenue
+        FROM transfer t
+        JOIN payment p ON p.id = t.payment_id
+        WHERE p.user_id = $1 AND p.livemode
+            AND t.created_at >= greatest($2, (SELECT until FROM rollup)) AND t.created_at < $3
+        GROUP BY date_trunc('day', t.created_at)
+    )
+"#;
+
+#[derive(Debug, FromQueryResult)]
+pub struct RevenueBucket {
+    pub bucket: NaiveDateTime,
+    pub revenue: i64,
+    pub completed: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct TransferTotals {
+    pub transfers: i64,
+    pub pending: i64,
+    pub completed: i64,
+    pub rejected: i64,
+    pub revenue: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct PayerStats {
+    pub unique_payers: i64,
+    pub repeat_payers: i64,
+}
+
+// Payments taken without a store are grouped under an empty store
+#[derive(Debug, FromQueryResult)]
+pub struct StoreTotals {
+    pub store_id: Option<i32>,
+    pub store_name: Option<String>,
+    pub transfers: i64,
+    pub completed: i64,
+    pub revenue: i64,
+}
+
+pub struct MerchantAnalytics {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub buckets: Vec<RevenueBucket>,
+    pub totals: TransferTotals,
+    pub payers: PayerStats,
+    pub stores: Vec<StoreTotals>,
+}
+
+pub struct AnalyticsService;
+
+impl AnalyticsService {
+    pub async fn find_merchant_analytics(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        analytics_query_dto: AnalyticsQueryDto,
+    ) -> Result<MerchantAnalytics> {
+        // Only merchants have analytics
+        UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let (from, to) = (analytics_query_dto.from, analytics_query_dto.to);
+        if to <= from {
+            return Err(ServiceError::DtoError(
+                "Time range must end after it starts".to_string(),
+            ));
+        }
+        if (to - from).num_days() > ANALYTICS_MAX_RANGE_DAYS {
+            return Err(ServiceError::DtoError(format!(
+                "Time range can't exceed {} days",
+                ANALYTICS_MAX_RANGE_DAYS
+            )));
+        }
+
+        let values: Vec<Value> = vec![
+            ctx.user_id.into(),
+            from.and_time(NaiveTime::MIN).into(),
+            to.and_time(NaiveTime::MIN).into(),
+        ];
+
+        let mut bucket_values = values.clone();
+        bucket_values.push(analytics_query_dto.granularity.as_str().into());
+        let buckets = RevenueBucket::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                "{} SELECT date_trunc($4::text, day) AS bucket, \
+                 coalesce(sum(revenue), 0)::bigint AS revenue, \
+                 coalesce(sum(completed), 0)::bigint AS completed \
+                 FROM daily GROUP BY 1 ORDER BY 1",
+                DAILY_AGGREGATES
+            ),
+            bucket_values,
+        ))
+        .all(state.db())
+        .await?;
+
+        let totals = TransferTotals::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                "{} SELECT coalesce(sum(transfers), 0)::bigint AS transfers, \
+                 coalesce(sum(pending), 0)::bigint AS pending, \
+                 coalesce(sum(completed), 0)::bigint AS completed, \
+                 coalesce(sum(rejected), 0)::bigint AS rejected, \
+                 coalesce(sum(revenue), 0)::bigint AS revenue \
+                 FROM daily",
+                DAILY_AGGREGATES
+            ),
+            values.clone(),
+        ))
+        .one(state.db())
+        .await?
+        .ok_or(ServiceError::Database(
+            "Missing analytics totals".to_string(),
+        ))?;
+
+        // Distinct wallets can't be summed across days, so these are always computed live
+        let payers = PayerStats::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                count(*) AS unique_payers,
+                count(*) FILTER (WHERE payments > 1) AS repeat_payers
+            FROM (
+                SELECT t.sender_wallet_address, count(*) AS payments
+                FROM transfer t
+                JOIN payment p ON p.id = t.payment_id
+                WHERE p.user_id = $1 AND p.livemode AND t.status = 'completed'
+                    AND t.created_at >= $2 AND t.created_at < $3
+                GROUP BY t.sender_wallet_address
+            ) payers
+            "#,
+            values.clone(),
+        ))
+        .one(state.db())
+        .await?
+        .ok_or(ServiceError::Database(
+            "Missing analytics payers".to_string(),
+        ))?;
+
+        // The rollup isn't kept per store, so the breakdown is computed live as well
+        let stores = StoreTotals::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                s.id AS store_id,
+                s.name AS store_name,
+                count(*) AS transfers,
+                count(*) FILTER (WHERE t.status = 'completed') AS completed,
+                coalesce(sum(t.amount) FILTER (WHERE t.status = 'completed'), 0)::bigint AS revenue
+            FROM transfer t
+            JOIN payment p ON p.id = t.payment_id
+            LEFT JOIN store s ON s.id = p.store_id
+            WHERE p.user_id = $1 AND p.livemode
+                AND t.created_at >= $2 AND t.created_at < $3
+            GROUP BY s.id, s.name
+            ORDER BY revenue DESC
+            "#,
+            values,
+        ))
+        .all(state.db())
+        .await?;
+
+        Ok(MerchantAnalytics {
+            from,
+            to,
+            buckets,
+            totals,
+            payers,
+            stores,
+        })
+    }
+
+    pub async fn refresh_rollup(state: Arc<AppState>) -> Result<()> {
+        state
+            .db()
+            .execute_unprepared("REFRESH MATERIALIZED VIEW CONCURRENTLY merchant_daily_rollup")
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn run_rollup_job(state: Arc<AppState>) {
+        let mut ticker = interval(Duration::from_secs(ANALYTICS_ROLLUP_REFRESH_SECS));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = Self::refresh_rollup(state.clone()).await {
+                tracing::error!("Analytics rollup refresh failed: {:?}", e);
+            }
+        }
+    }
+}
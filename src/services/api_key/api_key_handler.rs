@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        api_key::{
+            ApiKeyService,
+            dto::{
+                api_key_dto::{ApiKeyDto, CreatedApiKeyDto},
+                create_api_key_dto::CreateApiKeyDto,
+            },
+        },
+        error::Result,
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use validator::Validate;
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_api_key_dto): Json<CreateApiKeyDto>,
+) -> Result<Json<CreatedApiKeyDto>> {
+    create_api_key_dto.validate()?;
+
+    let api_key = ApiKeyService::create(state, ctx, create_api_key_dto).await?;
+    Ok(Json(api_key.into()))
+}
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<ApiKeyDto>>> {
+    let api_keys = ApiKeyService::find_all(state, ctx).await?;
+    Ok(Json(api_keys.into_iter().map(|val| val.into()).collect()))
+}
+
+pub async fn revoke(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<ApiKeyDto>> {
+    let api_key = ApiKeyService::revoke(state, ctx, id).await?;
+    Ok(Json(api_key.into()))
+}
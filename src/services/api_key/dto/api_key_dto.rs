@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{api_key::Model as ApiKeyModel, sea_orm_active_enums::ApiKeyMode};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyDto {
+    pub id: i32,
+
+    pub name: String,
+
+    pub prefix: String,
+
+    pub mode: ApiKeyMode,
+
+    pub scopes: Vec<String>,
+
+    pub last_used_at: Option<NaiveDateTime>,
+
+    pub revoked_at: Option<NaiveDateTime>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<ApiKeyModel> for ApiKeyDto {
+    fn from(value: ApiKeyModel) -> Self {
+        ApiKeyDto {
+            id: value.id,
+            name: value.name,
+            prefix: value.prefix,
+            mode: value.mode,
+            scopes: value.scopes,
+            last_used_at: value.last_used_at,
+            revoked_at: value.revoked_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+// Only returned on creation, the key is stored hashed
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKeyDto {
+    #[serde(flatten)]
+    pub api_key: ApiKeyDto,
+
+    pub key: String,
+}
+
+impl From<(ApiKeyModel, String)> for CreatedApiKeyDto {
+    fn from(value: (ApiKeyModel, String)) -> Self {
+        let (api_key, key) = value;
+        CreatedApiKeyDto {
+            api_key: api_key.into(),
+            key,
+        }
+    }
+}
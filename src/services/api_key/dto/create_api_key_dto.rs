@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{db::entity::sea_orm_active_enums::ApiKeyMode, services::api_key::ApiKeyScope};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyDto {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+
+    #[validate(length(min = 1))]
+    pub scopes: Vec<ApiKeyScope>,
+
+    pub mode: ApiKeyMode,
+}
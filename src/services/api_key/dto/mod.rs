@@ -0,0 +1,2 @@
+pub mod api_key_dto;
+pub mod create_api_key_dto;
@@ -0,0 +1,203 @@
+pub mod api_key_handler;
+pub mod dto;
+
+use std::{str::FromStr, sync::Arc};
+
+use crate::{
+    constants::{API_KEY_LAST_USED_RESOLUTION_SECS, API_KEY_LIVE_PREFIX, API_KEY_TEST_PREFIX},
+    ctx::{ApiKeyCtx, Ctx},
+    db::entity::{
+        api_key::{self, Model as ApiKeyModel},
+        prelude::ApiKey,
+        sea_orm_active_enums::ApiKeyMode,
+    },
+    services::{
+        AppState,
+        api_key::dto::create_api_key_dto::CreateApiKeyDto,
+        error::{EntityId, Result, ServiceError},
+        user::UserService,
+    },
+};
+use base64::{Engine, engine::general_purpose};
+use chrono::Utc;
+use rand::{Rng, distr::Alphanumeric};
+use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use strum_macros::{AsRefStr, EnumString};
+
+// What a key may do, routes opt in to API keys with the scope they need
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, AsRefStr, EnumString)]
+pub enum ApiKeyScope {
+    #[serde(rename = "payments:read")]
+    #[strum(serialize = "payments:read")]
+    PaymentsRead,
+    #[serde(rename = "payments:write")]
+    #[strum(serialize = "payments:write")]
+    PaymentsWrite,
+}
+
+pub struct ApiKeyService;
+
+impl ApiKeyService {
+    const API_KEY: &'static str = "ApiKey";
+
+    // Returns the key itself only here, afterwards merchants only see its prefix
+    pub async fn create(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_api_key_dto: CreateApiKeyDto,
+    ) -> Result<(ApiKeyModel, String)> {
+        UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let mut scopes = create_api_key_dto
+            .scopes
+            .iter()
+            .map(|scope| scope.as_ref().to_string())
+            .collect::<Vec<_>>();
+        scopes.sort();
+        scopes.dedup();
+
+        let key = generate_key(&create_api_key_dto.mode);
+        let data = api_key::ActiveModel {
+            user_id: Set(ctx.user_id),
+            name: Set(create_api_key_dto.name.trim().to_string()),
+            prefix: Set(key_prefix(&key)),
+            key_hash: Set(hash_key(&key)),
+            mode: Set(create_api_key_dto.mode),
+            scopes: Set(scopes),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        let api_key = ApiKey::insert(data).exec_with_returning(state.db()).await?;
+
+        Ok((api_key, key))
+    }
+
+    pub async fn find_all(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<ApiKeyModel>> {
+        let api_keys = ApiKey::find()
+            .filter(api_key::Column::UserId.eq(ctx.user_id))
+            .order_by_desc(api_key::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(api_keys)
+    }
+
+    pub async fn revoke(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<ApiKeyModel> {
+        let api_key = ApiKey::find_by_id(id)
+            .filter(api_key::Column::UserId.eq(ctx.user_id))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::API_KEY,
+                id: EntityId::Int(id),
+            })?;
+
+        if api_key.revoked_at.is_some() {
+            return Ok(api_key);
+        }
+
+        let mut data: api_key::ActiveModel = api_key.into();
+        data.revoked_at = Set(Some(Utc::now().naive_utc()));
+        let api_key = ApiKey::update(data).exec(state.db()).await?;
+
+        Ok(api_key)
+    }
+
+    // Resolves the `X-API-KEY` header to the merchant's user id, last use is only written when stale
+    pub async fn authenticate(state: Arc<AppState>, key: &str) -> Result<(i32, ApiKeyCtx)> {
+        let api_key = ApiKey::find()
+            .filter(api_key::Column::KeyHash.eq(hash_key(key.trim())))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::PermissionDenied)?;
+
+        let now = Utc::now().naive_utc();
+        let is_stale = api_key.last_used_at.is_none_or(|last_used_at| {
+            now - last_used_at > chrono::Duration::seconds(API_KEY_LAST_USED_RESOLUTION_SECS)
+        });
+        if is_stale {
+            ApiKey::update_many()
+                .col_expr(
+                    api_key::Column::LastUsedAt,
+                    sea_orm::prelude::Expr::value(now),
+                )
+                .filter(api_key::Column::Id.eq(api_key.id))
+                .exec(state.db())
+                .await?;
+        }
+
+        // Scopes are checked on write, unknown ones can only come from a removed scope
+        let scopes = api_key
+            .scopes
+            .iter()
+            .filter_map(|scope| ApiKeyScope::from_str(scope).ok())
+            .collect();
+
+        Ok((
+            api_key.user_id,
+            ApiKeyCtx {
+                id: api_key.id,
+                livemode: api_key.mode == ApiKeyMode::Live,
+                scopes,
+            },
+        ))
+    }
+}
+
+// `zk_live_` or `zk_test_` followed by 40 random characters
+fn generate_key(mode: &ApiKeyMode) -> String {
+    let prefix = match mode {
+        ApiKeyMode::Live => API_KEY_LIVE_PREFIX,
+        ApiKeyMode::Test => API_KEY_TEST_PREFIX,
+    };
+    let secret = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect::<String>();
+
+    format!("{}{}", prefix, secret)
+}
+
+// Mode prefix and the first characters of the secret, enough to recognize a key
+fn key_prefix(key: &str) -> String {
+    key.chars().take(API_KEY_LIVE_PREFIX.len() + 6).collect()
+}
+
+// Keys are long random strings, so a plain hash is enough to look them up
+fn hash_key(key: &str) -> String {
+    general_purpose::STANDARD.encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::sea_orm_active_enums::ApiKeyMode,
+        services::api_key::{ApiKeyScope, generate_key, hash_key, key_prefix},
+    };
+    use anyhow::Result;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_api_key_format() -> Result<()> {
+        let live = generate_key(&ApiKeyMode::Live);
+        let test = generate_key(&ApiKeyMode::Test);
+        assert!(live.starts_with("zk_live_") && live.len() == 48);
+        assert!(test.starts_with("zk_test_"));
+        assert_ne!(hash_key(&live), hash_key(&test));
+
+        assert_eq!(key_prefix(&live), live[..14]);
+        assert_eq!(
+            serde_json::from_str::<ApiKeyScope>("\"payments:write\"")?,
+            ApiKeyScope::PaymentsWrite
+        );
+        assert_eq!(
+            ApiKeyScope::from_str("payments:read")?,
+            ApiKeyScope::PaymentsRead
+        );
+        Ok(())
+    }
+}
@@ -1,26 +1,31 @@
 use std::sync::Arc;
 
 use crate::{
-    ctx::GoogleCtx,
+    ctx::{Ctx, GoogleCtx},
     services::{
         AppState,
         auth::{
             AuthService,
             dto::{
-                authorization_dto::AuthorizationDto, login_dto::LoginDto, register_dto::RegisterDto,
+                authorization_dto::{AuthorizationDto, LoginResponseDto},
+                login_dto::{LoginDto, TwoFactorLoginDto},
+                register_dto::RegisterDto,
+                wallet_auth_dto::{CreateWalletChallengeDto, VerifyWalletDto, WalletChallengeDto},
+                wallet_dto::PrivyLoginDto,
             },
         },
         error::Result,
+        user::dto::user_dto::UserDto,
     },
 };
-use axum::{Json, extract::State};
+use axum::{Json, body::Bytes, extract::State, http::HeaderMap};
 use validator::Validate;
 
 pub async fn login_with_google(
     State(state): State<Arc<AppState>>,
     ctx: GoogleCtx,
-) -> Result<Json<AuthorizationDto>> {
-    let result = AuthService::login_with_google(state, ctx.email).await?;
+) -> Result<Json<LoginResponseDto>> {
+    let result = AuthService::login_with_google(state, ctx.claims).await?;
     Ok(Json(result))
 }
 
@@ -37,7 +42,69 @@ pub async fn register(
 pub async fn login(
     State(state): State<Arc<AppState>>,
     Json(body): Json<LoginDto>,
-) -> Result<Json<AuthorizationDto>> {
+) -> Result<Json<LoginResponseDto>> {
     let result = AuthService::login(state, body).await?;
     Ok(Json(result))
 }
+
+pub async fn login_with_two_factor(
+    State(state): State<Arc<AppState>>,
+    Json(two_factor_login_dto): Json<TwoFactorLoginDto>,
+) -> Result<Json<AuthorizationDto>> {
+    let result = AuthService::login_with_two_factor(state, two_factor_login_dto).await?;
+    Ok(Json(result))
+}
+
+pub async fn create_wallet_challenge(
+    State(state): State<Arc<AppState>>,
+    Json(create_wallet_challenge_dto): Json<CreateWalletChallengeDto>,
+) -> Result<Json<WalletChallengeDto>> {
+    let challenge =
+        AuthService::create_wallet_challenge(state, None, create_wallet_challenge_dto).await?;
+    Ok(Json(challenge.into()))
+}
+
+pub async fn login_with_wallet(
+    State(state): State<Arc<AppState>>,
+    Json(verify_wallet_dto): Json<VerifyWalletDto>,
+) -> Result<Json<LoginResponseDto>> {
+    let result = AuthService::login_with_wallet(state, verify_wallet_dto).await?;
+    Ok(Json(result))
+}
+
+pub async fn create_link_wallet_challenge(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_wallet_challenge_dto): Json<CreateWalletChallengeDto>,
+) -> Result<Json<WalletChallengeDto>> {
+    let challenge =
+        AuthService::create_wallet_challenge(state, Some(ctx.user_id), create_wallet_challenge_dto)
+            .await?;
+    Ok(Json(challenge.into()))
+}
+
+pub async fn link_wallet(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(verify_wallet_dto): Json<VerifyWalletDto>,
+) -> Result<Json<UserDto>> {
+    let user = AuthService::link_wallet(state, ctx, verify_wallet_dto).await?;
+    Ok(Json(user.into()))
+}
+
+pub async fn login_with_privy(
+    State(state): State<Arc<AppState>>,
+    Json(privy_login_dto): Json<PrivyLoginDto>,
+) -> Result<Json<LoginResponseDto>> {
+    let result = AuthService::login_with_privy(state, privy_login_dto).await?;
+    Ok(Json(result))
+}
+
+// Raw body is needed since the signature covers the exact bytes Privy sent
+pub async fn privy_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<()> {
+    AuthService::handle_privy_webhook(state, &headers, &body).await
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
@@ -6,11 +7,30 @@ pub struct AuthorizationDto {
     // pub refresh_token: String (TODO)
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorChallengeDto {
+    // Exchanged with a TOTP or recovery code for the auth token
+    pub challenge_token: String,
+
+    pub two_factor_required: bool,
+
+    pub expires_at: NaiveDateTime,
+}
+
+// Sign in either returns the auth token right away or asks for the second factor
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum LoginResponseDto {
+    Authorized(AuthorizationDto),
+    TwoFactorRequired(TwoFactorChallengeDto),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BasicUserPayload {
     pub user_id: i32,
-    pub email: String,
+    pub email: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,10 +42,23 @@ pub struct Claims {
     pub exp: usize,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GoogleUser {
+// Purpose bound token (two factor login challenge, step-up), the audience keeps it from being
+// accepted as an auth token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurposeClaims {
+    pub sub: String,
+    pub aud: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+// Claims of a Google ID token, `iss`, `aud` and `exp` are checked on decode
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleClaims {
     pub sub: String,
     pub email: String,
-    pub name: String,
-    pub picture: String,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub picture: Option<String>,
 }
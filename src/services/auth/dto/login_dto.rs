@@ -7,3 +7,12 @@ pub struct LoginDto {
     pub email: String,
     pub password: String,
 }
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorLoginDto {
+    pub challenge_token: String,
+
+    // TOTP code or an unused recovery code
+    pub code: String,
+}
@@ -1,4 +1,5 @@
 pub mod authorization_dto;
 pub mod login_dto;
 pub mod register_dto;
+pub mod wallet_auth_dto;
 pub mod wallet_dto;
@@ -0,0 +1,44 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::db::entity::wallet_challenge::Model as WalletChallengeModel;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWalletChallengeDto {
+    pub address: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletChallengeDto {
+    pub address: String,
+
+    pub nonce: String,
+
+    // Message to sign with the wallet
+    pub message: String,
+
+    pub expires_at: NaiveDateTime,
+}
+
+impl From<(WalletChallengeModel, String)> for WalletChallengeDto {
+    fn from(value: (WalletChallengeModel, String)) -> Self {
+        let (challenge, message) = value;
+        WalletChallengeDto {
+            address: challenge.address,
+            nonce: challenge.nonce,
+            message,
+            expires_at: challenge.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyWalletDto {
+    pub address: String,
+
+    // Base58 signature of the challenge message by the wallet
+    pub signature: String,
+}
@@ -1,17 +1,66 @@
-use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PrivyWallet {
     pub id: Option<String>,
     pub address: String,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrivyLinkedAccount {
+    #[serde(rename = "type")]
+    pub account_type: String,
+
+    pub address: Option<String>,
+
+    pub chain_type: Option<String>,
+
+    pub wallet_client_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct PrivyUser {
     pub id: String,
-    pub created_at: DateTime<Utc>,
+
     pub wallet: Option<PrivyWallet>,
+
+    #[serde(default)]
+    pub linked_accounts: Vec<PrivyLinkedAccount>,
+}
+
+impl PrivyUser {
+    // Embedded Solana wallet created by Privy for the user
+    pub fn solana_wallet(&self) -> Option<String> {
+        self.linked_accounts
+            .iter()
+            .find(|account| {
+                account.account_type == "wallet"
+                    && account.chain_type.as_deref() == Some("solana")
+                    && account.wallet_client_type.as_deref() == Some("privy")
+            })
+            .and_then(|account| account.address.clone())
+            .or_else(|| self.wallet.as_ref().map(|wallet| wallet.address.clone()))
+    }
+
+    pub fn email(&self) -> Option<String> {
+        self.linked_accounts
+            .iter()
+            .find(|account| account.account_type == "email")
+            .and_then(|account| account.address.clone())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrivyWebhookEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+
+    pub user: Option<PrivyUser>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivyLoginDto {
+    pub access_token: String,
 }
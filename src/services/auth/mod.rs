@@ -1,57 +1,102 @@
 pub mod auth_handler;
 pub mod dto;
+pub mod google;
+pub mod privy;
 
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::config;
 use crate::config::config;
-use crate::constants::PRIVY_BASE_URL;
+use crate::constants::{
+    PRIVY_SYNC_BATCH_SIZE, PRIVY_SYNC_INTERVAL_SECS, PRIVY_SYNC_RETRY_AFTER_SECS,
+};
+use crate::ctx::Ctx;
 use crate::db::entity::{
     prelude::User,
+    sea_orm_active_enums::WalletChallengePurpose,
     user::{self, Column, Model as UserModel},
+    wallet_challenge::Model as WalletChallengeModel,
+};
+use crate::services::auth::dto::authorization_dto::{
+    AuthorizationDto, BasicUserPayload, GoogleClaims, LoginResponseDto, TwoFactorChallengeDto,
 };
-use crate::services::auth::dto::authorization_dto::{AuthorizationDto, BasicUserPayload};
-use crate::services::auth::dto::login_dto::LoginDto;
-use crate::services::auth::dto::wallet_dto::{PrivyUser, PrivyWallet};
+use crate::services::auth::dto::login_dto::{LoginDto, TwoFactorLoginDto};
+use crate::services::auth::dto::wallet_auth_dto::{CreateWalletChallengeDto, VerifyWalletDto};
+use crate::services::auth::dto::wallet_dto::{PrivyLoginDto, PrivyUser, PrivyWebhookEvent};
 use crate::services::auth::dto::{authorization_dto::Claims, register_dto::RegisterDto};
 use crate::services::error::{Result, ServiceError};
+use crate::services::two_factor::TwoFactorService;
+use crate::services::wallet_challenge::WalletChallengeService;
 use crate::services::{AppState, append_timestamp, hash_password, verify_password};
 use axum::extract::State;
-use base64::Engine;
-use base64::engine::general_purpose;
+use axum::http::HeaderMap;
 use chrono;
 use jsonwebtoken::{EncodingKey, Header, encode};
-use reqwest::{Body, Client};
 use sea_orm::ActiveValue::Set;
+use sea_orm::prelude::Expr;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, Iden, QueryFilter, QuerySelect, SelectColumns,
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, Iden, ModelTrait,
+    QueryFilter, QueryOrder, QuerySelect, SelectColumns, TransactionTrait,
 };
-use serde_json::json;
+use spl_token::solana_program::pubkey::Pubkey;
+use tokio::time::interval;
 use tokio::try_join;
+use uuid::Uuid;
 use validator::ValidateEmail;
 
 pub struct AuthService;
 
 impl AuthService {
+    pub async fn verify_google_id_token(
+        state: Arc<AppState>,
+        id_token: &str,
+    ) -> Result<GoogleClaims> {
+        state.google.verify(id_token).await
+    }
+
     pub async fn login_with_google(
         state: Arc<AppState>,
-        email: String,
-    ) -> Result<AuthorizationDto> {
+        google_claims: GoogleClaims,
+    ) -> Result<LoginResponseDto> {
+        let GoogleClaims { sub, email, .. } = google_claims;
+
+        // The Google account id is stable, so a changed Google email still finds the same user
         let user = User::find()
-            .filter(Column::Email.eq(&email))
+            .filter(Column::GoogleSub.eq(&sub))
             .one(state.db())
             .await?;
-
         if let Some(val) = user {
-            let auth_token = Self::generate_auth_token(val).await?;
+            return Self::authorize(val).await;
+        }
 
-            Ok(AuthorizationDto { auth_token })
-        } else {
-            Self::register_with_google(state, email).await
+        // Existing accounts with the verified email are linked to the Google account once
+        let user = User::find()
+            .filter(Column::Email.eq(&email))
+            .one(state.db())
+            .await?;
+
+        match user {
+            Some(val) if val.google_sub.is_some() => Err(ServiceError::DtoError(
+                "Email is already linked to another Google account".to_string(),
+            )),
+            Some(val) => {
+                let mut data: user::ActiveModel = val.into();
+                data.google_sub = Set(Some(sub));
+                let user = User::update(data).exec(state.db()).await?;
+
+                Self::authorize(user).await
+            }
+            None => Self::register_with_google(state, sub, email).await,
         }
     }
 
-    async fn register_with_google(state: Arc<AppState>, email: String) -> Result<AuthorizationDto> {
+    async fn register_with_google(
+        state: Arc<AppState>,
+        sub: String,
+        email: String,
+    ) -> Result<LoginResponseDto> {
         let name = email
             .split('@')
             .next()
@@ -61,21 +106,25 @@ impl AuthService {
         let slug = append_timestamp(&name);
         let s3_bucket_slug = Self::get_s3_bucket(slug);
 
-        let privy_wallet = Self::get_privy_user_by_email(&email).await?;
-        let wallet_address = privy_wallet.map(|val| val.address);
+        let privy_user = state.privy.find_user_by_email(&email).await?;
+        let wallet_address = match privy_user.as_ref().and_then(|val| val.solana_wallet()) {
+            Some(address) => Self::find_available_wallet(state.db(), address, None).await?,
+            None => None,
+        };
 
         let data = user::ActiveModel {
-            email: Set(email),
+            email: Set(Some(email)),
             password: Set("".to_string()), // Empty password for oauth users
             s3_bucket_slug: Set(s3_bucket_slug),
             wallet_address: Set(wallet_address),
+            google_sub: Set(Some(sub)),
+            privy_id: Set(privy_user.map(|val| val.id)),
             ..Default::default()
         };
 
         let user = User::insert(data).exec_with_returning(state.db()).await?;
-        let auth_token = Self::generate_auth_token(user).await?;
 
-        Ok(AuthorizationDto { auth_token })
+        Self::authorize(user).await
     }
 
     pub async fn register(state: Arc<AppState>, body: RegisterDto) -> Result<AuthorizationDto> {
@@ -94,7 +143,7 @@ impl AuthService {
 
         let hashed_password = hash_password(body.password)?;
         let data = user::ActiveModel {
-            email: Set(email),
+            email: Set(Some(email)),
             password: Set(hashed_password),
             s3_bucket_slug: Set(s3_bucket_slug),
             ..Default::default()
@@ -107,7 +156,7 @@ impl AuthService {
         Ok(AuthorizationDto { auth_token })
     }
 
-    pub async fn login(state: Arc<AppState>, body: LoginDto) -> Result<AuthorizationDto> {
+    pub async fn login(state: Arc<AppState>, body: LoginDto) -> Result<LoginResponseDto> {
         let user = User::find()
             .filter(Column::Email.eq(body.email))
             .one(state.db())
@@ -116,10 +165,356 @@ impl AuthService {
         let user = user.ok_or(ServiceError::UserNotFound)?;
         verify_password(body.password, user.password.clone())?;
 
+        Self::authorize(user).await
+    }
+
+    // With two factor on, every sign in method only earns a challenge for the second step
+    async fn authorize(user: UserModel) -> Result<LoginResponseDto> {
+        if user.totp_enabled_at.is_some() {
+            let (challenge_token, expires_at) = TwoFactorService::create_challenge_token(user.id)?;
+            return Ok(LoginResponseDto::TwoFactorRequired(TwoFactorChallengeDto {
+                challenge_token,
+                two_factor_required: true,
+                expires_at,
+            }));
+        }
+
+        let auth_token = Self::generate_auth_token(user).await?;
+        Ok(LoginResponseDto::Authorized(AuthorizationDto {
+            auth_token,
+        }))
+    }
+
+    pub async fn login_with_two_factor(
+        state: Arc<AppState>,
+        two_factor_login_dto: TwoFactorLoginDto,
+    ) -> Result<AuthorizationDto> {
+        let user = TwoFactorService::verify_challenge(
+            state,
+            &two_factor_login_dto.challenge_token,
+            &two_factor_login_dto.code,
+        )
+        .await?;
+
         let auth_token = Self::generate_auth_token(user).await?;
         Ok(AuthorizationDto { auth_token })
     }
 
+    /**
+     * Signs in with a Privy access token. Users are found by their Privy id, then by the email of the
+     * Privy account, and created otherwise; the embedded wallet is synced on every sign in.
+     */
+    pub async fn login_with_privy(
+        state: Arc<AppState>,
+        privy_login_dto: PrivyLoginDto,
+    ) -> Result<LoginResponseDto> {
+        let claims = state
+            .privy
+            .verify_access_token(&privy_login_dto.access_token)?;
+        let privy_user = state.privy.find_user(&claims.sub).await?;
+
+        let user = match Self::sync_privy_user(state.clone(), &privy_user).await? {
+            Some(user) => user,
+            None => {
+                let email = privy_user.email();
+                let name = email
+                    .as_deref()
+                    .and_then(|email| email.split('@').next())
+                    .unwrap_or(&privy_user.id)
+                    .to_string();
+                let s3_bucket_slug = Self::get_s3_bucket(append_timestamp(&name));
+
+                let wallet_address = match privy_user.solana_wallet() {
+                    Some(address) => Self::find_available_wallet(state.db(), address, None).await?,
+                    None => None,
+                };
+
+                let data = user::ActiveModel {
+                    email: Set(email),
+                    password: Set("".to_string()), // Empty password for privy users
+                    s3_bucket_slug: Set(s3_bucket_slug),
+                    wallet_address: Set(wallet_address),
+                    privy_id: Set(Some(privy_user.id)),
+                    ..Default::default()
+                };
+                User::insert(data).exec_with_returning(state.db()).await?
+            }
+        };
+
+        Self::authorize(user).await
+    }
+
+    /**
+     * Links the Privy account to its user and fills in the embedded wallet. A wallet the user already
+     * has is never replaced, since it may have been linked with Sign-In With Solana.
+     */
+    pub async fn sync_privy_user(
+        state: Arc<AppState>,
+        privy_user: &PrivyUser,
+    ) -> Result<Option<UserModel>> {
+        let user = User::find()
+            .filter(Column::PrivyId.eq(&privy_user.id))
+            .one(state.db())
+            .await?;
+
+        let user = match (user, privy_user.email()) {
+            (Some(user), _) => user,
+            (None, Some(email)) => {
+                let user = User::find()
+                    .filter(Column::Email.eq(email))
+                    .filter(Column::PrivyId.is_null())
+                    .one(state.db())
+                    .await?;
+                let Some(user) = user else { return Ok(None) };
+
+                let mut data: user::ActiveModel = user.into();
+                data.privy_id = Set(Some(privy_user.id.clone()));
+                User::update(data).exec(state.db()).await?
+            }
+            (None, None) => return Ok(None),
+        };
+
+        if user.wallet_address.is_some() {
+            return Ok(Some(user));
+        }
+        let Some(address) = privy_user.solana_wallet() else {
+            return Ok(Some(user));
+        };
+        let Some(address) = Self::find_available_wallet(state.db(), address, Some(user.id)).await?
+        else {
+            tracing::warn!(
+                "Privy wallet of user {} is linked to another account",
+                user.id
+            );
+            return Ok(Some(user));
+        };
+
+        let mut data: user::ActiveModel = user.into();
+        data.wallet_address = Set(Some(address));
+        let user = User::update(data).exec(state.db()).await?;
+
+        Ok(Some(user))
+    }
+
+    pub async fn handle_privy_webhook(
+        state: Arc<AppState>,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<()> {
+        state.privy.verify_webhook(headers, body)?;
+
+        let event = serde_json::from_slice::<PrivyWebhookEvent>(body)?;
+        tracing::debug!("Privy webhook {}", event.event_type);
+
+        // Every user event carries the full user, so they are all handled the same way
+        if let Some(privy_user) = event.user {
+            Self::sync_privy_user(state, &privy_user).await?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Fills in wallets of users created before their Privy wallet existed, returns the synced count.
+     * Users looked up in the last `PRIVY_SYNC_RETRY_AFTER_SECS` are skipped.
+     */
+    pub async fn sync_privy_wallets(state: Arc<AppState>) -> Result<usize> {
+        let retry_before =
+            chrono::Utc::now().naive_utc() - chrono::Duration::seconds(PRIVY_SYNC_RETRY_AFTER_SECS);
+        let (mut synced, mut last_id) = (0, 0);
+        loop {
+            let users = User::find()
+                .filter(Column::Id.gt(last_id))
+                .filter(Column::WalletAddress.is_null())
+                .filter(
+                    Condition::any()
+                        .add(Column::PrivyId.is_not_null())
+                        .add(Column::Email.is_not_null()),
+                )
+                .filter(
+                    Condition::any()
+                        .add(Column::PrivySyncedAt.is_null())
+                        .add(Column::PrivySyncedAt.lt(retry_before)),
+                )
+                .order_by_asc(Column::Id)
+                .limit(PRIVY_SYNC_BATCH_SIZE)
+                .all(state.db())
+                .await?;
+
+            let Some(last) = users.last() else {
+                return Ok(synced);
+            };
+            last_id = last.id;
+
+            for user in users {
+                let privy_user = match (&user.privy_id, &user.email) {
+                    (Some(privy_id), _) => state.privy.find_user(privy_id).await.map(Some),
+                    (None, Some(email)) => state.privy.find_user_by_email(email).await,
+                    (None, None) => Ok(None),
+                };
+                User::update_many()
+                    .col_expr(
+                        Column::PrivySyncedAt,
+                        Expr::value(chrono::Utc::now().naive_utc()),
+                    )
+                    .filter(Column::Id.eq(user.id))
+                    .exec(state.db())
+                    .await?;
+
+                // One failing lookup shouldn't stop the rest of the batch
+                let privy_user = match privy_user {
+                    Ok(Some(privy_user)) => privy_user,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::warn!("Privy lookup for user {} failed: {:?}", user.id, e);
+                        continue;
+                    }
+                };
+
+                let synced_user = Self::sync_privy_user(state.clone(), &privy_user).await?;
+                if synced_user.is_some_and(|val| val.wallet_address.is_some()) {
+                    synced += 1;
+                }
+            }
+        }
+    }
+
+    pub async fn run_privy_sync_job(state: Arc<AppState>) {
+        let mut ticker = interval(Duration::from_secs(PRIVY_SYNC_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            match Self::sync_privy_wallets(state.clone()).await {
+                Ok(synced) if synced > 0 => tracing::info!("Synced {} Privy wallets", synced),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Privy wallet sync failed: {:?}", e),
+            }
+        }
+    }
+
+    // The address unless it's already linked to a different user
+    async fn find_available_wallet<C: ConnectionTrait>(
+        db: &C,
+        address: String,
+        user_id: Option<i32>,
+    ) -> Result<Option<String>> {
+        let owner = User::find()
+            .filter(Column::WalletAddress.eq(&address))
+            .one(db)
+            .await?;
+
+        match owner {
+            Some(owner) if Some(owner.id) != user_id => Ok(None),
+            _ => Ok(Some(address)),
+        }
+    }
+
+    /**
+     * Issues a single use Sign-In With Solana challenge for the wallet. With `user_id` the challenge
+     * links the wallet to that user instead of signing in.
+     */
+    pub async fn create_wallet_challenge(
+        state: Arc<AppState>,
+        user_id: Option<i32>,
+        create_wallet_challenge_dto: CreateWalletChallengeDto,
+    ) -> Result<(WalletChallengeModel, String)> {
+        let address = Pubkey::from_str(create_wallet_challenge_dto.address.trim())
+            .map_err(|_| ServiceError::DtoError("Invalid wallet address".to_string()))?
+            .to_string();
+
+        let challenge = WalletChallengeService::issue(
+            state.db(),
+            wallet_challenge_purpose(user_id),
+            &address,
+            user_id,
+            None,
+        )
+        .await?;
+
+        let message = wallet_challenge_message(&challenge);
+        Ok((challenge, message))
+    }
+
+    // Signs in with the wallet, creating a user on first sign in
+    pub async fn login_with_wallet(
+        state: Arc<AppState>,
+        verify_wallet_dto: VerifyWalletDto,
+    ) -> Result<LoginResponseDto> {
+        let txn = state.db().begin().await?;
+        let address = Self::verify_wallet_challenge(&txn, None, verify_wallet_dto).await?;
+
+        let user = User::find()
+            .filter(Column::WalletAddress.eq(&address))
+            .one(&txn)
+            .await?;
+
+        let user = match user {
+            Some(user) => user,
+            None => {
+                let s3_bucket_slug = Self::get_s3_bucket(append_timestamp(&address));
+                let data = user::ActiveModel {
+                    password: Set("".to_string()), // Empty password for wallet users
+                    s3_bucket_slug: Set(s3_bucket_slug),
+                    wallet_address: Set(Some(address)),
+                    ..Default::default()
+                };
+                User::insert(data).exec_with_returning(&txn).await?
+            }
+        };
+
+        txn.commit().await?;
+
+        Self::authorize(user).await
+    }
+
+    // Links the wallet to an existing account with the same proof as wallet sign in
+    pub async fn link_wallet(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        verify_wallet_dto: VerifyWalletDto,
+    ) -> Result<UserModel> {
+        let txn = state.db().begin().await?;
+        let address =
+            Self::verify_wallet_challenge(&txn, Some(ctx.user_id), verify_wallet_dto).await?;
+
+        let owner = User::find()
+            .filter(Column::WalletAddress.eq(&address))
+            .one(&txn)
+            .await?;
+        let user = User::find_by_id(ctx.user_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(ServiceError::UserNotFound)?;
+        check_wallet_link(&user, owner.as_ref(), &address)?;
+
+        let mut user: user::ActiveModel = user.into();
+        user.wallet_address = Set(Some(address));
+        let user = User::update(user).exec(&txn).await?;
+
+        txn.commit().await?;
+        Ok(user)
+    }
+
+    // Consumes the challenge and checks its signature, returning the verified wallet address
+    async fn verify_wallet_challenge<C: ConnectionTrait>(
+        db: &C,
+        user_id: Option<i32>,
+        verify_wallet_dto: VerifyWalletDto,
+    ) -> Result<String> {
+        let challenge = WalletChallengeService::verify(
+            db,
+            wallet_challenge_purpose(user_id),
+            verify_wallet_dto.address.trim(),
+            user_id,
+            None,
+            &verify_wallet_dto.signature,
+            wallet_challenge_message,
+        )
+        .await?;
+
+        Ok(challenge.address)
+    }
+
     pub async fn generate_auth_token(user: UserModel) -> Result<String> {
         let now = chrono::Utc::now();
         let claims = Claims {
@@ -156,29 +551,91 @@ impl AuthService {
     fn get_s3_bucket(user_slug: String) -> String {
         return format!("user/{}", user_slug);
     }
+}
+
+// A wallet belongs to one account and an account keeps the wallet it already has
+fn check_wallet_link(user: &UserModel, owner: Option<&UserModel>, address: &str) -> Result<()> {
+    if owner.is_some_and(|owner| owner.id != user.id) {
+        return Err(ServiceError::DtoError(
+            "Wallet is already linked to another account".to_string(),
+        ));
+    }
+    if user
+        .wallet_address
+        .as_ref()
+        .is_some_and(|wallet_address| wallet_address != address)
+    {
+        return Err(ServiceError::DtoError(
+            "Account already has a linked wallet".to_string(),
+        ));
+    }
+
+    Ok(())
+}
 
-    async fn get_privy_user_by_email(email: &String) -> Result<Option<PrivyWallet>> {
-        let client = Client::new();
-        let url = format!("{}/v1/users/email/address", PRIVY_BASE_URL);
+// A signed in user's challenge links the wallet, otherwise it signs in with it
+fn wallet_challenge_purpose(user_id: Option<i32>) -> WalletChallengePurpose {
+    match user_id {
+        Some(_) => WalletChallengePurpose::LinkWallet,
+        None => WalletChallengePurpose::SignIn,
+    }
+}
 
-        let body = json!({"address": email});
+// Sign-In With Solana style message, rebuilt from the stored challenge on verification
+fn wallet_challenge_message(challenge: &WalletChallengeModel) -> String {
+    let statement = match challenge.purpose {
+        WalletChallengePurpose::LinkWallet => "Link this wallet to your Zunopay account.",
+        _ => "Sign in to Zunopay with your Solana account.",
+    };
+
+    format!(
+        "Zunopay wants you to sign in with your Solana account:\n{}\n\n{}\n\nNonce: {}\nIssued At: {}\nExpiration Time: {}",
+        challenge.address,
+        statement,
+        challenge.nonce,
+        challenge.created_at.format("%Y-%m-%dT%H:%M:%SZ"),
+        challenge.expires_at.format("%Y-%m-%dT%H:%M:%SZ")
+    )
+}
 
-        let app_id = &config().PRIVY_APP_ID;
-        let credentials = format!("{}:{}", app_id, config().PRIVY_APP_SECRET);
-        let encoded_credentials = general_purpose::STANDARD.encode(credentials);
+#[cfg(test)]
+mod test {
+    use crate::{db::entity::user::Model as UserModel, services::auth::check_wallet_link};
+    use anyhow::Result;
+
+    fn user(id: i32, wallet_address: Option<&str>) -> UserModel {
+        UserModel {
+            id,
+            email: None,
+            password: String::new(),
+            wallet_address: wallet_address.map(str::to_string),
+            created_at: chrono::Utc::now().naive_utc(),
+            email_verified_at: None,
+            s3_bucket_slug: format!("user-{}", id),
+            google_sub: None,
+            privy_id: None,
+            totp_secret: None,
+            totp_enabled_at: None,
+            totp_last_step: None,
+            two_factor_failed_attempts: 0,
+            two_factor_locked_until: None,
+            avatar: None,
+            privy_synced_at: None,
+        }
+    }
 
-        let response = client
-            .post(url)
-            .json(&body)
-            .header("Authorization", format!("Basic {}", encoded_credentials))
-            .header("privy-app-id", app_id)
-            .header("Content-Type", "application/json")
-            .send()
-            .await?;
+    #[test]
+    fn test_wallet_link() -> Result<()> {
+        let wallet = "4Nd1mYQ5Ba9AhhELXAUWQs9mYUe1nSUy7tBJjw3DWyUZ";
+        let other_wallet = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
 
-        let privy_user = response.json::<PrivyUser>().await?;
+        // First link and linking the same wallet again both pass
+        check_wallet_link(&user(1, None), None, wallet)?;
+        check_wallet_link(&user(1, Some(wallet)), Some(&user(1, Some(wallet))), wallet)?;
 
-        let wallet = privy_user.wallet;
-        Ok(wallet)
+        // Another account's wallet and replacing the linked wallet are rejected
+        assert!(check_wallet_link(&user(1, None), Some(&user(2, Some(wallet))), wallet).is_err());
+        assert!(check_wallet_link(&user(1, Some(other_wallet)), None, wallet).is_err());
+        Ok(())
     }
 }
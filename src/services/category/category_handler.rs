@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::{
+    constants::DEFAULT_LOCALE,
+    services::{
+        AppState,
+        category::{
+            CategoryService,
+            dto::{
+                category_dto::CategoryDto,
+                category_form_dto::{CreateCategoryDto, UpdateCategoryDto},
+                category_query_dto::CategoryQueryDto,
+            },
+            normalize_locale,
+        },
+        error::Result,
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use validator::Validate;
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    Query(category_query_dto): Query<CategoryQueryDto>,
+) -> Result<Json<Vec<CategoryDto>>> {
+    let locale = match category_query_dto.locale {
+        Some(locale) => normalize_locale(&locale)?,
+        None => DEFAULT_LOCALE.to_string(),
+    };
+
+    let categories = CategoryService::find_all(state).await?;
+    Ok(Json(
+        categories
+            .into_iter()
+            .map(|(category, translations)| CategoryDto::new(category, translations, &locale))
+            .collect(),
+    ))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    Json(create_category_dto): Json<CreateCategoryDto>,
+) -> Result<Json<CategoryDto>> {
+    create_category_dto.validate()?;
+
+    let (category, translations) = CategoryService::create(state, create_category_dto).await?;
+    Ok(Json(CategoryDto::new(
+        category,
+        translations,
+        DEFAULT_LOCALE,
+    )))
+}
+
+pub async fn update(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(update_category_dto): Json<UpdateCategoryDto>,
+) -> Result<Json<CategoryDto>> {
+    update_category_dto.validate()?;
+
+    let (category, translations) = CategoryService::update(state, id, update_category_dto).await?;
+    Ok(Json(CategoryDto::new(
+        category,
+        translations,
+        DEFAULT_LOCALE,
+    )))
+}
+
+pub async fn remove(State(state): State<Arc<AppState>>, Path(id): Path<i32>) -> Result<()> {
+    CategoryService::remove(state, id).await?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    db::entity::{
+        category::Model as CategoryModel, category_translation::Model as CategoryTranslationModel,
+    },
+    services::category::resolve_name,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryDto {
+    pub id: i32,
+
+    pub parent_id: Option<i32>,
+
+    pub slug: String,
+
+    pub icon: Option<String>,
+
+    pub position: i32,
+
+    // Name in the requested locale
+    pub name: String,
+
+    // Every translation keyed by locale
+    pub names: BTreeMap<String, String>,
+}
+
+impl CategoryDto {
+    pub fn new(
+        category: CategoryModel,
+        translations: Vec<CategoryTranslationModel>,
+        locale: &str,
+    ) -> Self {
+        let name = resolve_name(&translations, locale)
+            .unwrap_or(&category.slug)
+            .to_string();
+
+        CategoryDto {
+            id: category.id,
+            parent_id: category.parent_id,
+            slug: category.slug,
+            icon: category.icon,
+            position: category.position,
+            name,
+            names: translations
+                .into_iter()
+                .map(|val| (val.locale, val.name))
+                .collect(),
+        }
+    }
+}
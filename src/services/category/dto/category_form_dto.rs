@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCategoryDto {
+    // Only top level categories can be parents
+    pub parent_id: Option<i32>,
+
+    #[validate(length(min = 1, max = 50))]
+    pub slug: String,
+
+    #[validate(length(max = 255))]
+    pub icon: Option<String>,
+
+    pub position: Option<i32>,
+
+    // Display name per locale, must include the default locale
+    pub names: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCategoryDto {
+    pub parent_id: Option<i32>,
+
+    #[validate(length(min = 1, max = 50))]
+    pub slug: Option<String>,
+
+    #[validate(length(max = 255))]
+    pub icon: Option<String>,
+
+    pub position: Option<i32>,
+
+    // Replaces every translation when set
+    pub names: Option<HashMap<String, String>>,
+}
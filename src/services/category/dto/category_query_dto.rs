@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryQueryDto {
+    // e.g. "en" or "fr-CA", names fall back to the language and then the default locale
+    pub locale: Option<String>,
+}
@@ -0,0 +1,3 @@
+pub mod category_dto;
+pub mod category_form_dto;
+pub mod category_query_dto;
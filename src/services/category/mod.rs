@@ -0,0 +1,344 @@
+pub mod category_handler;
+pub mod dto;
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    constants::DEFAULT_LOCALE,
+    db::entity::{
+        category::{self, Model as CategoryModel},
+        category_translation::{self, Model as CategoryTranslationModel},
+        merchant,
+        prelude::{Category, CategoryTranslation, Merchant},
+    },
+    services::{
+        AppState,
+        category::dto::category_form_dto::{CreateCategoryDto, UpdateCategoryDto},
+        error::{EntityId, Result, ServiceError},
+    },
+};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, LoaderTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+
+pub struct CategoryService;
+
+impl CategoryService {
+    const CATEGORY: &'static str = "Category";
+
+    // Flat list, ordered so parents can be grouped with their sub categories client side
+    pub async fn find_all(
+        state: Arc<AppState>,
+    ) -> Result<Vec<(CategoryModel, Vec<CategoryTranslationModel>)>> {
+        let categories = Category::find()
+            .order_by_asc(category::Column::Position)
+            .order_by_asc(category::Column::Id)
+            .all(state.db())
+            .await?;
+        let translations = categories
+            .load_many(CategoryTranslation, state.db())
+            .await?;
+
+        Ok(categories.into_iter().zip(translations).collect())
+    }
+
+    pub async fn create(
+        state: Arc<AppState>,
+        create_category_dto: CreateCategoryDto,
+    ) -> Result<(CategoryModel, Vec<CategoryTranslationModel>)> {
+        let slug = create_category_dto.slug.trim().to_string();
+        validate_slug(&slug)?;
+        let names = normalize_names(create_category_dto.names)?;
+
+        let txn = state.db().begin().await?;
+        Self::check_slug_available(&txn, &slug, None).await?;
+        if let Some(parent_id) = create_category_dto.parent_id {
+            Self::check_parent(&txn, parent_id, None).await?;
+        }
+
+        let data = category::ActiveModel {
+            parent_id: Set(create_category_dto.parent_id),
+            slug: Set(slug),
+            icon: Set(create_category_dto.icon),
+            position: Set(create_category_dto.position.unwrap_or_default()),
+            ..Default::default()
+        };
+        let category = Category::insert(data).exec_with_returning(&txn).await?;
+        let translations = Self::replace_translations(&txn, category.id, names).await?;
+        txn.commit().await?;
+
+        Ok((category, translations))
+    }
+
+    pub async fn update(
+        state: Arc<AppState>,
+        id: i32,
+        update_category_dto: UpdateCategoryDto,
+    ) -> Result<(CategoryModel, Vec<CategoryTranslationModel>)> {
+        let txn = state.db().begin().await?;
+        let category = Self::find_category(&txn, id).await?;
+
+        let mut data: category::ActiveModel = category.clone().into();
+        if let Some(slug) = update_category_dto.slug {
+            let slug = slug.trim().to_string();
+            validate_slug(&slug)?;
+            Self::check_slug_available(&txn, &slug, Some(id)).await?;
+            data.slug = Set(slug);
+        }
+        if let Some(parent_id) = update_category_dto.parent_id {
+            Self::check_parent(&txn, parent_id, Some(id)).await?;
+            data.parent_id = Set(Some(parent_id));
+        }
+        if let Some(icon) = update_category_dto.icon {
+            data.icon = Set(Some(icon));
+        }
+        if let Some(position) = update_category_dto.position {
+            data.position = Set(position);
+        }
+        let category = Category::update(data).exec(&txn).await?;
+
+        let translations = match update_category_dto.names {
+            Some(names) => {
+                let names = normalize_names(names)?;
+                Self::replace_translations(&txn, id, names).await?
+            }
+            None => category.find_related(CategoryTranslation).all(&txn).await?,
+        };
+        txn.commit().await?;
+
+        Ok((category, translations))
+    }
+
+    // Categories still used by merchants or sub categories have to be emptied first
+    pub async fn remove(state: Arc<AppState>, id: i32) -> Result<()> {
+        let category = Self::find_category(state.db(), id).await?;
+
+        let merchants = Merchant::find()
+            .filter(merchant::Column::CategoryId.eq(id))
+            .count(state.db())
+            .await?;
+        let children = Category::find()
+            .filter(category::Column::ParentId.eq(id))
+            .count(state.db())
+            .await?;
+        if merchants > 0 || children > 0 {
+            return Err(ServiceError::DtoError("Category is still in use".into()));
+        }
+
+        category.delete(state.db()).await?;
+        Ok(())
+    }
+
+    pub(in crate::services) async fn find_category<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<CategoryModel> {
+        let category = Category::find_by_id(id).one(db).await?;
+        category.ok_or(ServiceError::EntityNotFound {
+            entity: Self::CATEGORY,
+            id: EntityId::Int(id),
+        })
+    }
+
+    async fn check_slug_available<C: ConnectionTrait>(
+        db: &C,
+        slug: &str,
+        except_id: Option<i32>,
+    ) -> Result<()> {
+        let mut select = Category::find().filter(category::Column::Slug.eq(slug));
+        if let Some(id) = except_id {
+            select = select.filter(category::Column::Id.ne(id));
+        }
+
+        if select.one(db).await?.is_some() {
+            return Err(ServiceError::DtoError("Category slug is taken".into()));
+        }
+
+        Ok(())
+    }
+
+    // The hierarchy is only two levels deep, a parent has to be a top level category
+    async fn check_parent<C: ConnectionTrait>(
+        db: &C,
+        parent_id: i32,
+        id: Option<i32>,
+    ) -> Result<()> {
+        if Some(parent_id) == id {
+            return Err(ServiceError::DtoError(
+                "Category can't be its own parent".into(),
+            ));
+        }
+
+        let parent = Self::find_category(db, parent_id).await?;
+        if parent.parent_id.is_some() {
+            return Err(ServiceError::DtoError(
+                "Sub categories can't have sub categories".into(),
+            ));
+        }
+
+        if let Some(id) = id {
+            let children = Category::find()
+                .filter(category::Column::ParentId.eq(id))
+                .count(db)
+                .await?;
+            if children > 0 {
+                return Err(ServiceError::DtoError(
+                    "Category with sub categories can't have a parent".into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn replace_translations<C: ConnectionTrait>(
+        db: &C,
+        category_id: i32,
+        names: Vec<(String, String)>,
+    ) -> Result<Vec<CategoryTranslationModel>> {
+        CategoryTranslation::delete_many()
+            .filter(category_translation::Column::CategoryId.eq(category_id))
+            .exec(db)
+            .await?;
+
+        let data = names
+            .into_iter()
+            .map(|(locale, name)| category_translation::ActiveModel {
+                category_id: Set(category_id),
+                locale: Set(locale),
+                name: Set(name),
+                ..Default::default()
+            });
+        CategoryTranslation::insert_many(data).exec(db).await?;
+
+        let translations = CategoryTranslation::find()
+            .filter(category_translation::Column::CategoryId.eq(category_id))
+            .order_by_asc(category_translation::Column::Locale)
+            .all(db)
+            .await?;
+        Ok(translations)
+    }
+}
+
+/**
+ * Picks the translation for `locale`, falling back to its language ("fr-ca" to "fr") and then
+ * to the default locale.
+ */
+pub(in crate::services) fn resolve_name<'a>(
+    translations: &'a [CategoryTranslationModel],
+    locale: &str,
+) -> Option<&'a str> {
+    let language = locale.split('-').next().unwrap_or(locale);
+    [locale, language, DEFAULT_LOCALE]
+        .into_iter()
+        .find_map(|locale| translations.iter().find(|val| val.locale == locale))
+        .map(|val| val.name.as_str())
+}
+
+// Locales are stored lowercase with a dash, e.g. "en" or "pt-br"
+pub(in crate::services) fn normalize_locale(locale: &str) -> Result<String> {
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let mut parts = locale.split('-');
+    let language_valid = parts.next().is_some_and(|val| {
+        (2..=3).contains(&val.len()) && val.chars().all(|c| c.is_ascii_lowercase())
+    });
+    let region_valid = parts
+        .all(|val| (2..=8).contains(&val.len()) && val.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    if !language_valid || !region_valid {
+        return Err(ServiceError::DtoError(format!("Invalid locale {}", locale)));
+    }
+
+    Ok(locale)
+}
+
+fn normalize_names(names: HashMap<String, String>) -> Result<Vec<(String, String)>> {
+    let mut normalized: HashMap<String, String> = HashMap::new();
+    for (locale, name) in names {
+        let locale = normalize_locale(&locale)?;
+        let name = name.trim().to_string();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err(ServiceError::DtoError(
+                "Category name must be between 1 and 100 characters".into(),
+            ));
+        }
+        if normalized.insert(locale.clone(), name).is_some() {
+            return Err(ServiceError::DtoError(format!(
+                "Duplicate name for locale {}",
+                locale
+            )));
+        }
+    }
+
+    if !normalized.contains_key(DEFAULT_LOCALE) {
+        return Err(ServiceError::DtoError(format!(
+            "Category needs a name for the default locale {}",
+            DEFAULT_LOCALE
+        )));
+    }
+
+    let mut names: Vec<(String, String)> = normalized.into_iter().collect();
+    names.sort();
+    Ok(names)
+}
+
+fn validate_slug(slug: &str) -> Result<()> {
+    let valid = !slug.is_empty()
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+    if !valid {
+        return Err(ServiceError::DtoError(
+            "Category slug can only contain lowercase letters, digits and dashes".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::{
+        db::entity::category_translation::Model as CategoryTranslationModel,
+        services::category::{normalize_locale, normalize_names, resolve_name, validate_slug},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_category_names() -> Result<()> {
+        assert_eq!(normalize_locale("pt_BR")?, "pt-br");
+        assert!(normalize_locale("english").is_err());
+        assert!(validate_slug("fast-food").is_ok());
+        assert!(validate_slug("Fast Food").is_err());
+
+        let names = normalize_names(HashMap::from([
+            ("EN".to_string(), " Groceries ".to_string()),
+            ("fr".to_string(), "Épicerie".to_string()),
+        ]))?;
+        assert_eq!(names[0], ("en".to_string(), "Groceries".to_string()));
+        assert!(
+            normalize_names(HashMap::from([("fr".to_string(), "Épicerie".to_string())])).is_err()
+        );
+
+        let translations: Vec<CategoryTranslationModel> = names
+            .into_iter()
+            .enumerate()
+            .map(|(id, (locale, name))| CategoryTranslationModel {
+                id: id as i32,
+                category_id: 1,
+                locale,
+                name,
+            })
+            .collect();
+        assert_eq!(resolve_name(&translations, "fr-ca"), Some("Épicerie"));
+        assert_eq!(resolve_name(&translations, "de"), Some("Groceries"));
+
+        Ok(())
+    }
+}
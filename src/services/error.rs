@@ -35,6 +35,7 @@ pub enum ServiceError {
     EntityNotFound { entity: &'static str, id: EntityId },
     Database(String),
     UserNotFound,
+    PermissionDenied,
     InvalidPassword,
     DtoError(String),
     ParseError(strum::ParseError),
@@ -44,6 +45,7 @@ pub enum ServiceError {
     PasswordHashError(argon2::password_hash::Error),
     ValidationError(validator::ValidationErrors),
     S3Error(String),
+    StorageError(String),
     Web3Error(Web3ErrorType),
     SerializationError(String),
     KeypairError(String),
@@ -118,6 +120,12 @@ impl From<bincode::Error> for ServiceError {
     }
 }
 
+impl From<serde_json::Error> for ServiceError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::SerializationError(value.to_string())
+    }
+}
+
 impl From<solana_client::client_error::ClientError> for ServiceError {
     fn from(value: solana_client::client_error::ClientError) -> Self {
         Self::Web3Error(Web3ErrorType::Custom(value.to_string()))
@@ -0,0 +1,36 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{escrow::Model as EscrowModel, sea_orm_active_enums::EscrowStatus};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EscrowDto {
+    pub id: i32,
+
+    pub transfer_id: i32,
+
+    pub status: EscrowStatus,
+
+    pub amount: u64,
+
+    pub release_after: NaiveDateTime,
+
+    pub signature: Option<String>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<EscrowModel> for EscrowDto {
+    fn from(value: EscrowModel) -> Self {
+        EscrowDto {
+            id: value.id,
+            transfer_id: value.transfer_id,
+            status: value.status,
+            amount: value.amount as u64, // held amount after fee, never negative
+            release_after: value.release_after,
+            signature: value.signature,
+            created_at: value.created_at,
+        }
+    }
+}
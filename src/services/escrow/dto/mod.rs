@@ -0,0 +1,3 @@
+pub mod dispute_escrow_dto;
+pub mod escrow_dto;
+pub mod resolve_escrow_dto;
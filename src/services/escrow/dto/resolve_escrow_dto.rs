@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub enum EscrowOutcome {
+    Release,
+    Refund,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveEscrowDto {
+    pub outcome: EscrowOutcome,
+    pub note: Option<String>,
+}
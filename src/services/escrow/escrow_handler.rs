@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        escrow::{
+            EscrowService,
+            dto::{
+                dispute_escrow_dto::DisputeEscrowDto, escrow_dto::EscrowDto,
+                resolve_escrow_dto::ResolveEscrowDto,
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn find_buyer_escrows(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<EscrowDto>>> {
+    let escrows = EscrowService::find_buyer_escrows(state, ctx).await?;
+    let escrows = escrows
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<EscrowDto>>();
+
+    Ok(Json(escrows))
+}
+
+pub async fn find_merchant_escrows(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<EscrowDto>>> {
+    let escrows = EscrowService::find_merchant_escrows(state, ctx).await?;
+    let escrows = escrows
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<EscrowDto>>();
+
+    Ok(Json(escrows))
+}
+
+pub async fn find_disputed_escrows(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<EscrowDto>>> {
+    let escrows = EscrowService::find_disputed_escrows(state).await?;
+    let escrows = escrows
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<EscrowDto>>();
+
+    Ok(Json(escrows))
+}
+
+pub async fn confirm(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<EscrowDto>> {
+    let escrow = EscrowService::confirm(state, ctx, id).await?;
+    Ok(Json(escrow.into()))
+}
+
+pub async fn dispute(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+    Json(dispute_escrow_dto): Json<DisputeEscrowDto>,
+) -> Result<Json<EscrowDto>> {
+    let escrow = EscrowService::dispute(state, ctx, id, dispute_escrow_dto).await?;
+    Ok(Json(escrow.into()))
+}
+
+pub async fn refund(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<EscrowDto>> {
+    let escrow = EscrowService::refund(state, ctx, id).await?;
+    Ok(Json(escrow.into()))
+}
+
+pub async fn resolve(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+    Json(resolve_escrow_dto): Json<ResolveEscrowDto>,
+) -> Result<Json<EscrowDto>> {
+    let escrow = EscrowService::resolve(state, ctx, id, resolve_escrow_dto).await?;
+    Ok(Json(escrow.into()))
+}
@@ -0,0 +1,535 @@
+pub mod dto;
+pub mod escrow_handler;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    constants::{
+        ESCROW_PAYOUT_RETRY_AFTER_SECS, ESCROW_RELEASE_AFTER_DAYS,
+        ESCROW_RELEASE_JOB_INTERVAL_SECS, USDC_MINT,
+    },
+    ctx::Ctx,
+    db::entity::{
+        escrow::{self, Model as EscrowModel},
+        escrow_event,
+        payment::{self, Model as PaymentModel},
+        prelude::{Escrow, EscrowEvent, Payment, PaymentSplit, Transfer},
+        sea_orm_active_enums::{EscrowStatus, LedgerTransactionKind},
+        transfer::{self, Model as TransferModel},
+    },
+    services::{
+        AppState,
+        error::{EntityId, MathErrorType, Result, ServiceError, Web3ErrorType},
+        escrow::dto::{
+            dispute_escrow_dto::DisputeEscrowDto,
+            resolve_escrow_dto::{EscrowOutcome, ResolveEscrowDto},
+        },
+        ledger::LedgerService,
+        payment::{PaymentService, get_split_legs},
+        user::UserService,
+        web3::{TransferLeg, calculate_transfer_fee},
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use spl_token::solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+use tokio::time::interval;
+
+pub struct EscrowService;
+
+impl EscrowService {
+    const ESCROW: &'static str = "Escrow";
+
+    // Called when an escrow payment's transfer completes, funds (after fee) now sit in the escrow account
+    pub async fn hold<C: ConnectionTrait>(
+        db: &C,
+        payment: &PaymentModel,
+        transfer: &TransferModel,
+    ) -> Result<EscrowModel> {
+        // Transfer amount already has any promotion discount applied
+        let amount = u64::try_from(transfer.amount)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+        let (_, amount_after_fee) = calculate_transfer_fee(amount)?;
+        let amount_after_fee = i64::try_from(amount_after_fee)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        let now = Utc::now().naive_utc();
+        let data = escrow::ActiveModel {
+            transfer_id: Set(transfer.id),
+            status: Set(EscrowStatus::Held),
+            amount: Set(amount_after_fee),
+            release_after: Set(now + chrono::Duration::days(ESCROW_RELEASE_AFTER_DAYS)),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        let escrow = Escrow::insert(data).exec_with_returning(db).await?;
+
+        Self::insert_event(db, escrow.id, None, EscrowStatus::Held, None, None).await?;
+
+        Ok(escrow)
+    }
+
+    pub async fn find_buyer_escrows(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<EscrowModel>> {
+        let user = UserService::find_one(state.clone(), ctx.user_id).await?;
+        let wallet_address = user.wallet_address.ok_or(ServiceError::EntityNotFound {
+            entity: "UserWallet",
+            id: EntityId::Int(user.id),
+        })?;
+
+        let escrows = Escrow::find()
+            .inner_join(Transfer)
+            .filter(transfer::Column::SenderWalletAddress.eq(wallet_address))
+            .order_by_desc(escrow::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(escrows)
+    }
+
+    pub async fn find_merchant_escrows(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<EscrowModel>> {
+        let merchant = UserService::find_ctx_merchant(state.clone(), &ctx).await?;
+        let escrows = Escrow::find()
+            .inner_join(Transfer)
+            .join(JoinType::InnerJoin, transfer::Relation::Payment.def())
+            .filter(payment::Column::MerchantId.eq(merchant.id))
+            .order_by_desc(escrow::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(escrows)
+    }
+
+    pub async fn find_disputed_escrows(state: Arc<AppState>) -> Result<Vec<EscrowModel>> {
+        let escrows = Escrow::find()
+            .filter(escrow::Column::Status.eq(EscrowStatus::Disputed))
+            .order_by_asc(escrow::Column::UpdatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(escrows)
+    }
+
+    // Buyer received the order, release funds to the merchant
+    pub async fn confirm(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<EscrowModel> {
+        Self::check_buyer(state.clone(), &ctx, id).await?;
+
+        Self::transition(
+            state,
+            id,
+            EscrowStatus::Released,
+            Some(ctx.user_id),
+            Some("Confirmed by buyer".to_string()),
+        )
+        .await
+    }
+
+    pub async fn dispute(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+        dispute_escrow_dto: DisputeEscrowDto,
+    ) -> Result<EscrowModel> {
+        let escrow = Self::check_buyer(state.clone(), &ctx, id).await?;
+
+        if escrow.release_after <= Utc::now().naive_utc() {
+            return Err(ServiceError::Custom(
+                "Dispute window has closed".to_string(),
+            ));
+        }
+
+        Self::transition(
+            state,
+            id,
+            EscrowStatus::Disputed,
+            Some(ctx.user_id),
+            dispute_escrow_dto.note,
+        )
+        .await
+    }
+
+    // Merchant gives the funds back to the buyer
+    pub async fn refund(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<EscrowModel> {
+        let merchant = UserService::find_ctx_merchant(state.clone(), &ctx).await?;
+        let (_, _, payment) = Self::find_one(state.clone(), id).await?;
+        if payment.merchant_id != Some(merchant.id) {
+            return Err(ServiceError::PermissionDenied);
+        }
+
+        Self::transition(
+            state,
+            id,
+            EscrowStatus::Refunded,
+            Some(ctx.user_id),
+            Some("Refunded by merchant".to_string()),
+        )
+        .await
+    }
+
+    pub async fn resolve(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+        resolve_escrow_dto: ResolveEscrowDto,
+    ) -> Result<EscrowModel> {
+        let (escrow, _, _) = Self::find_one(state.clone(), id).await?;
+        if escrow.status != EscrowStatus::Disputed {
+            return Err(ServiceError::Custom("Escrow is not disputed".to_string()));
+        }
+
+        let status = match resolve_escrow_dto.outcome {
+            EscrowOutcome::Release => EscrowStatus::Released,
+            EscrowOutcome::Refund => EscrowStatus::Refunded,
+        };
+
+        Self::transition(
+            state,
+            id,
+            status,
+            Some(ctx.user_id),
+            resolve_escrow_dto.note,
+        )
+        .await
+    }
+
+    // Releases held escrows whose dispute window has passed without buyer confirmation
+    pub async fn release_expired(state: Arc<AppState>) -> Result<()> {
+        let escrows = Escrow::find()
+            .filter(escrow::Column::Status.eq(EscrowStatus::Held))
+            .filter(escrow::Column::ReleaseAfter.lte(Utc::now().naive_utc()))
+            .all(state.db())
+            .await?;
+
+        for escrow in escrows {
+            let result = Self::transition(
+                state.clone(),
+                escrow.id,
+                EscrowStatus::Released,
+                None,
+                Some("Released after timeout".to_string()),
+            )
+            .await;
+
+            if let Err(e) = result {
+                tracing::error!("Failed to release escrow {}: {:?}", escrow.id, e);
+            }
+        }
+
+        // Payouts interrupted after they were saved, old enough that a sent transaction has landed or expired
+        let retry_before =
+            Utc::now().naive_utc() - chrono::Duration::seconds(ESCROW_PAYOUT_RETRY_AFTER_SECS);
+        let pending = Escrow::find()
+            .filter(
+                escrow::Column::Status.is_in([EscrowStatus::Releasing, EscrowStatus::Refunding]),
+            )
+            .filter(escrow::Column::UpdatedAt.lte(retry_before))
+            .all(state.db())
+            .await?;
+
+        for escrow in pending {
+            let (escrow, transfer, payment) = Self::find_one(state.clone(), escrow.id).await?;
+            let id = escrow.id;
+            if let Err(e) = Self::pay_out(state.clone(), escrow, transfer, payment).await {
+                tracing::error!("Failed to retry payout of escrow {}: {:?}", id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn run_release_job(state: Arc<AppState>) {
+        let mut ticker = interval(Duration::from_secs(ESCROW_RELEASE_JOB_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = Self::release_expired(state.clone()).await {
+                tracing::error!("Escrow release job failed: {:?}", e);
+            }
+        }
+    }
+
+    async fn find_one(
+        state: Arc<AppState>,
+        id: i32,
+    ) -> Result<(EscrowModel, TransferModel, PaymentModel)> {
+        let not_found = || ServiceError::EntityNotFound {
+            entity: Self::ESCROW,
+            id: EntityId::Int(id),
+        };
+
+        let (escrow, transfer) = Escrow::find_by_id(id)
+            .find_also_related(Transfer)
+            .one(state.db())
+            .await?
+            .ok_or_else(not_found)?;
+        let transfer = transfer.ok_or_else(not_found)?;
+
+        let payment = transfer
+            .find_related(Payment)
+            .one(state.db())
+            .await?
+            .ok_or_else(not_found)?;
+
+        Ok((escrow, transfer, payment))
+    }
+
+    // Buyer is the user whose wallet sent the transfer
+    async fn check_buyer(state: Arc<AppState>, ctx: &Ctx, id: i32) -> Result<EscrowModel> {
+        let (escrow, transfer, _) = Self::find_one(state.clone(), id).await?;
+        let user = UserService::find_one(state, ctx.user_id).await?;
+
+        if user.wallet_address.as_ref() != Some(&transfer.sender_wallet_address) {
+            return Err(ServiceError::PermissionDenied);
+        }
+
+        Ok(escrow)
+    }
+
+    /**
+     * Moves an escrow to `status`. Release and refund first save the payout as releasing or
+     * refunding and commit, so the escrow can't be paid out twice, then send it on-chain.
+     */
+    async fn transition(
+        state: Arc<AppState>,
+        id: i32,
+        status: EscrowStatus,
+        actor_id: Option<i32>,
+        note: Option<String>,
+    ) -> Result<EscrowModel> {
+        let (_, transfer, payment) = Self::find_one(state.clone(), id).await?;
+        let status = get_pending_status(&status).unwrap_or(status);
+
+        let txn = state.db().begin().await?;
+        let escrow = Self::lock(&txn, id).await?;
+
+        if !can_transition(&escrow.status, &status) {
+            return Err(ServiceError::Custom(format!(
+                "Escrow can't move from {:?} to {:?}",
+                escrow.status, status
+            )));
+        }
+
+        let amount = u64::try_from(escrow.amount)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+        let legs = match status {
+            EscrowStatus::Releasing => {
+                let receiver_address =
+                    PaymentService::find_receiver_wallet(state.clone(), &payment).await?;
+                let splits = payment.find_related(PaymentSplit).all(&txn).await?;
+                Some(get_split_legs(amount, &receiver_address, &splits)?)
+            }
+            EscrowStatus::Refunding => Some(vec![TransferLeg {
+                receiver_wallet: transfer.sender_wallet_address.clone(),
+                amount,
+            }]),
+            _ => None,
+        };
+
+        let from_status = escrow.status.clone();
+        let mut escrow: escrow::ActiveModel = escrow.into();
+        escrow.status = Set(status.clone());
+        escrow.updated_at = Set(Utc::now().naive_utc());
+        if let Some(legs) = &legs {
+            let legs = serde_json::to_value(legs)
+                .map_err(|e| ServiceError::SerializationError(e.to_string()))?;
+            escrow.payout_legs = Set(Some(legs));
+            escrow.payout_reference = Set(Some(Keypair::new().pubkey().to_string()));
+        }
+        let escrow = Escrow::update(escrow).exec(&txn).await?;
+
+        Self::insert_event(&txn, escrow.id, Some(from_status), status, actor_id, note).await?;
+        txn.commit().await?;
+
+        match legs {
+            Some(_) => Self::pay_out(state, escrow, transfer, payment).await,
+            None => Ok(escrow),
+        }
+    }
+
+    /**
+     * Sends a saved payout and records its signature. Safe to retry: a payout that already
+     * landed is found by its reference key instead of being sent again.
+     */
+    async fn pay_out(
+        state: Arc<AppState>,
+        escrow: EscrowModel,
+        transfer: TransferModel,
+        payment: PaymentModel,
+    ) -> Result<EscrowModel> {
+        let (legs, reference) = match (&escrow.payout_legs, &escrow.payout_reference) {
+            (Some(legs), Some(reference)) => (
+                serde_json::from_value::<Vec<TransferLeg>>(legs.clone())
+                    .map_err(|e| ServiceError::SerializationError(e.to_string()))?,
+                reference.clone(),
+            ),
+            _ => {
+                return Err(ServiceError::Custom(format!(
+                    "Escrow {} has no saved payout",
+                    escrow.id
+                )));
+            }
+        };
+
+        let landed = match state
+            .web3
+            .clone()
+            .find_reference(reference.clone(), None)
+            .await
+        {
+            Ok(status) if status.err.is_none() => Some(status.signature),
+            Ok(_) | Err(ServiceError::Web3Error(Web3ErrorType::ReferenceError)) => None,
+            Err(e) => return Err(e),
+        };
+        let signature = match landed {
+            Some(signature) => signature,
+            None => {
+                let reference = Pubkey::from_str(&reference)?;
+                state
+                    .web3
+                    .send_from_escrow(&legs, USDC_MINT, reference)
+                    .await?
+            }
+        };
+
+        let txn = state.db().begin().await?;
+        let escrow = Self::lock(&txn, escrow.id).await?;
+        let (status, kind) = match escrow.status {
+            EscrowStatus::Releasing => {
+                (EscrowStatus::Released, LedgerTransactionKind::EscrowRelease)
+            }
+            EscrowStatus::Refunding => {
+                (EscrowStatus::Refunded, LedgerTransactionKind::EscrowRefund)
+            }
+            // Recorded by a concurrent retry
+            _ => return Ok(escrow),
+        };
+
+        LedgerService::post_escrow_settlement(
+            &txn,
+            &escrow,
+            &transfer,
+            &payment,
+            kind,
+            Some(signature.clone()),
+        )
+        .await?;
+
+        let from_status = escrow.status.clone();
+        let mut escrow: escrow::ActiveModel = escrow.into();
+        escrow.status = Set(status.clone());
+        escrow.signature = Set(Some(signature));
+        escrow.updated_at = Set(Utc::now().naive_utc());
+        let escrow = Escrow::update(escrow).exec(&txn).await?;
+
+        Self::insert_event(&txn, escrow.id, Some(from_status), status, None, None).await?;
+
+        txn.commit().await?;
+        Ok(escrow)
+    }
+
+    async fn lock<C: ConnectionTrait>(db: &C, id: i32) -> Result<EscrowModel> {
+        let escrow = Escrow::find_by_id(id).lock_exclusive().one(db).await?;
+
+        escrow.ok_or(ServiceError::EntityNotFound {
+            entity: Self::ESCROW,
+            id: EntityId::Int(id),
+        })
+    }
+
+    async fn insert_event<C: ConnectionTrait>(
+        db: &C,
+        escrow_id: i32,
+        from_status: Option<EscrowStatus>,
+        to_status: EscrowStatus,
+        actor_id: Option<i32>,
+        note: Option<String>,
+    ) -> Result<()> {
+        let data = escrow_event::ActiveModel {
+            escrow_id: Set(escrow_id),
+            from_status: Set(from_status),
+            to_status: Set(to_status),
+            actor_id: Set(actor_id),
+            note: Set(note),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        EscrowEvent::insert(data).exec(db).await?;
+
+        Ok(())
+    }
+}
+
+fn can_transition(from: &EscrowStatus, to: &EscrowStatus) -> bool {
+    matches!(
+        (from, to),
+        (EscrowStatus::Held, EscrowStatus::Disputed)
+            | (EscrowStatus::Held, EscrowStatus::Releasing)
+            | (EscrowStatus::Held, EscrowStatus::Refunding)
+            | (EscrowStatus::Disputed, EscrowStatus::Releasing)
+            | (EscrowStatus::Disputed, EscrowStatus::Refunding)
+            | (EscrowStatus::Releasing, EscrowStatus::Released)
+            | (EscrowStatus::Refunding, EscrowStatus::Refunded)
+    )
+}
+
+// Final states are reached through a pending state while the payout is sent
+fn get_pending_status(status: &EscrowStatus) -> Option<EscrowStatus> {
+    match status {
+        EscrowStatus::Released => Some(EscrowStatus::Releasing),
+        EscrowStatus::Refunded => Some(EscrowStatus::Refunding),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::sea_orm_active_enums::EscrowStatus,
+        services::escrow::{can_transition, get_pending_status},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_escrow_transitions() -> Result<()> {
+        // Release and refund go through their pending state
+        for status in [EscrowStatus::Released, EscrowStatus::Refunded] {
+            let pending = get_pending_status(&status).expect("pending status");
+            assert!(can_transition(&EscrowStatus::Held, &pending));
+            assert!(can_transition(&EscrowStatus::Disputed, &pending));
+            assert!(can_transition(&pending, &status));
+            assert!(!can_transition(&EscrowStatus::Held, &status));
+            assert!(!can_transition(&status, &pending));
+        }
+        assert_eq!(get_pending_status(&EscrowStatus::Disputed), None);
+
+        // A payout in flight can't be disputed or paid out again
+        assert!(can_transition(&EscrowStatus::Held, &EscrowStatus::Disputed));
+        assert!(!can_transition(
+            &EscrowStatus::Disputed,
+            &EscrowStatus::Disputed
+        ));
+        assert!(!can_transition(
+            &EscrowStatus::Releasing,
+            &EscrowStatus::Disputed
+        ));
+        assert!(!can_transition(
+            &EscrowStatus::Releasing,
+            &EscrowStatus::Refunding
+        ));
+        assert!(!can_transition(
+            &EscrowStatus::Refunding,
+            &EscrowStatus::Released
+        ));
+        assert!(!can_transition(
+            &EscrowStatus::Released,
+            &EscrowStatus::Refunding
+        ));
+
+        Ok(())
+    }
+}
@@ -6,16 +6,19 @@ use crate::db::entity::{
     user::Model as UserModel,
 };
 use crate::services::error::MathErrorType;
-use crate::services::web3::get_fee_faucet_pubkey;
+use crate::services::web3::{TransferLeg, Web3Service, get_fee_faucet_pubkey};
 use crate::services::{
     AppState,
     error::{Result, ServiceError, Web3ErrorType},
+    escrow::EscrowService,
+    ledger::LedgerService,
+    promotion::PromotionService,
 };
 use chrono::Utc;
 use sea_orm::ActiveValue::Set;
 use sea_orm::prelude::Expr;
 use sea_orm::sea_query::{ExprTrait, ValueType};
-use sea_orm::{ActiveEnum, ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{ActiveEnum, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
 use serde::Deserialize;
 use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
 use solana_instruction::{AccountMeta, Instruction};
@@ -24,10 +27,12 @@ use solana_signature::Signature;
 use solana_transaction::{Transaction, versioned::VersionedTransaction};
 use solana_transaction_status_client_types::{
     EncodedTransactionWithStatusMeta, UiTransactionEncoding, UiTransactionStatusMeta,
+    UiTransactionTokenBalance, option_serializer::OptionSerializer,
 };
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::instruction::TokenInstruction;
 use spl_token::solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -48,9 +53,9 @@ impl Indexer {
     pub async fn poll_payment(
         state: Arc<AppState>,
         reference: String,
-        receipt: String,
+        legs: Vec<TransferLeg>,
         mint: String,
-        amount: u64,
+        livemode: bool,
     ) -> Result<()> {
         let start_time = Instant::now();
         let timeout = Duration::from_secs(60);
@@ -67,25 +72,23 @@ impl Indexer {
 
             ticker.tick().await;
             let status = state
-                .web3
+                .web3_for(livemode)
                 .clone()
                 .find_reference(reference.clone(), None)
                 .await;
 
             // Todo: Keep loop for 10 minutes, 8 minutes for user and 2 minutes buffer check
             let reference = Pubkey::from_str(&reference)?;
-            let wallet_address = Pubkey::from_str(&receipt)?;
             let mint = Pubkey::from_str(&mint)?;
 
             match status {
                 Ok(status) => {
                     let transfer_status = Self::validate_payment(
-                        &state,
+                        state.web3_for(livemode),
                         &status,
                         &reference,
-                        &wallet_address,
+                        &legs,
                         &mint,
-                        amount,
                     )
                     .await?;
 
@@ -96,33 +99,52 @@ impl Indexer {
             }
         }?;
 
+        let is_completed = status == TransferStatus::Completed;
         let enum_type_name = TransferStatus::enum_type_name().unwrap_or_else(|| "transfer_status");
+
+        let txn = state.db().begin().await?;
         Transfer::update_many()
             .col_expr(transfer::Column::Signature, Expr::value(signature))
             .col_expr(
                 transfer::Column::Status,
                 Expr::value(status).as_enum(enum_type_name),
             )
-            .filter(transfer::Column::ReferenceKey.eq(reference))
-            .exec(state.db())
+            .filter(transfer::Column::ReferenceKey.eq(&reference))
+            .exec(&txn)
             .await?;
+
+        if is_completed {
+            let transfer = Transfer::find()
+                .filter(transfer::Column::ReferenceKey.eq(&reference))
+                .find_also_related(Payment)
+                .one(&txn)
+                .await?;
+
+            if let Some((transfer, Some(payment))) = transfer {
+                LedgerService::post_transfer(&txn, &payment, &transfer).await?;
+                PromotionService::redeem(&txn, transfer.id).await?;
+
+                if payment.is_escrow {
+                    EscrowService::hold(&txn, &payment, &transfer).await?;
+                }
+            }
+        }
+
+        txn.commit().await?;
         //todo: Send websocket event
 
         Ok(())
     }
 
     async fn validate_payment(
-        state: &AppState,
+        web3: &Web3Service,
         status: &RpcConfirmedTransactionStatusWithSignature,
         reference: &Pubkey,
-        receipt: &Pubkey,
+        legs: &[TransferLeg],
         mint: &Pubkey,
-        amount: u64,
     ) -> Result<TransferStatus> {
-        //todo: can check transfer fee instruction, but validation handles by fee faucet signing for now
         let transaction_response =
-            Self::validate_transfer(state, &status.signature, reference, receipt, mint, amount)
-                .await?;
+            Self::validate_transfer(web3, &status.signature, reference, legs, mint).await?;
 
         let transfer_status = if status.err.is_some() {
             TransferStatus::Rejected
@@ -134,14 +156,13 @@ impl Indexer {
     }
 
     async fn validate_transfer(
-        state: &AppState,
+        web3: &Web3Service,
         signature: &String,
         reference: &Pubkey,
-        receipt: &Pubkey,
+        legs: &[TransferLeg],
         mint: &Pubkey,
-        amount: u64,
     ) -> Result<EncodedTransactionWithStatusMeta> {
-        let response = state.web3.rpc_client.get_transaction(
+        let response = web3.rpc_client.get_transaction(
             &Signature::from_str(&signature).map_err(|_| {
                 ServiceError::Web3Error(Web3ErrorType::ValidateTransferError(
                     "Error parsing signature from string".to_string(),
@@ -186,64 +207,135 @@ impl Indexer {
                 Web3ErrorType::ValidateTransferError("Missing meta".to_string()),
             ))?;
 
-        let compiled_ix =
-            transaction
-                .message
-                .instructions()
-                .last()
-                .cloned()
-                .ok_or(ServiceError::Web3Error(
-                    Web3ErrorType::ValidateTransferError("Invalid instruction".to_string()),
-                ))?;
+        let instructions = transaction
+            .message
+            .instructions()
+            .iter()
+            .cloned()
+            .map(|compiled_ix| Self::decompile_instruction(compiled_ix, &transaction.message))
+            .collect::<Result<Vec<Instruction>>>()?;
+
+        // Every leg (treasury fee, split recipients and merchant) has to be transferred in full
+        let mut verified_instructions = vec![false; instructions.len()];
+        for leg in legs {
+            let receipt = Pubkey::from_str(&leg.receiver_wallet)?;
+            let transferred = Self::validate_spl_transfer(
+                &instructions,
+                &mut verified_instructions,
+                &receipt,
+                mint,
+                reference,
+            )?;
+
+            if transferred < leg.amount {
+                return Err(ServiceError::Web3Error(
+                    Web3ErrorType::ValidateTransferError("Amount not transferred".to_string()),
+                ));
+            }
+        }
 
-        let instruction = Self::decompile_instruction(compiled_ix, &transaction.message)?;
-        let (pre_balance, post_balance) = Self::validate_spl_transfer(
-            &instruction,
-            &transaction.message,
-            meta,
-            receipt,
-            mint,
-            reference,
-        )
-        .await?;
-
-        let is_transferred = post_balance
-            .checked_sub(pre_balance)
-            .map(|balance| balance.less_than(amount))
-            .flatten()
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
-
-        if !is_transferred {
-            return Err(ServiceError::Web3Error(
-                Web3ErrorType::ValidateTransferError("Amount not transferred".to_string()),
-            ));
+        // Legs paying the same wallet share one balance change, so it has to cover all of them
+        for (receipt_ata, expected) in Self::get_expected_amounts(legs, mint)? {
+            let received =
+                Self::get_received_amount(&transaction.message, meta, &receipt_ata, mint)?;
+            if received < expected {
+                return Err(ServiceError::Web3Error(
+                    Web3ErrorType::ValidateTransferError("Amount not transferred".to_string()),
+                ));
+            }
         }
 
         Ok(response.transaction)
     }
 
-    async fn validate_spl_transfer(
-        instruction: &Instruction,
-        message: &VersionedMessage,
-        meta: &UiTransactionStatusMeta,
+    /**
+     * Balance change of `token_account` in a confirmed transaction, along with the transaction's
+     * account keys so the caller can look for a payment reference among them.
+     */
+    pub(crate) async fn find_token_balance_change(
+        web3: &Web3Service,
+        signature: &str,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<(i64, Vec<Pubkey>)> {
+        let signature = Signature::from_str(signature).map_err(|_| {
+            ServiceError::Web3Error(Web3ErrorType::ValidateTransferError(
+                "Error parsing signature from string".to_string(),
+            ))
+        })?;
+        // The RPC client blocks, keep it off the runtime so lookups can run side by side
+        let rpc_client = web3.rpc_client.clone();
+        let response = tokio::task::spawn_blocking(move || {
+            rpc_client
+                .get_transaction(&signature, UiTransactionEncoding::Json)
+                .map_err(ServiceError::from)
+        })
+        .await
+        .map_err(|e| ServiceError::Custom(e.to_string()))??;
+        let transaction =
+            response
+                .transaction
+                .transaction
+                .decode()
+                .ok_or(ServiceError::Web3Error(
+                    Web3ErrorType::ValidateTransferError("Not Found".to_string()),
+                ))?;
+        let meta = response
+            .transaction
+            .meta
+            .as_ref()
+            .ok_or(ServiceError::Web3Error(
+                Web3ErrorType::ValidateTransferError("Missing meta".to_string()),
+            ))?;
+
+        let account_keys = transaction.message.static_account_keys().to_vec();
+        let Some(account_index) = account_keys.iter().position(|key| key == token_account) else {
+            return Ok((0, account_keys));
+        };
+
+        let pre_balance = Self::get_token_balance(&meta.pre_token_balances, account_index, mint)?;
+        let post_balance = Self::get_token_balance(&meta.post_token_balances, account_index, mint)?;
+        let change = i128::from(post_balance) - i128::from(pre_balance);
+        let change: i64 = change
+            .try_into()
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        Ok((change, account_keys))
+    }
+
+    /**
+     * Finds the unverified transfer instruction paying `receipt` and returns its amount.
+     * The caller checks the amounts against the token balance changes, so a failed or partial
+     * transfer can't pass on instruction data alone.
+     */
+    fn validate_spl_transfer(
+        instructions: &[Instruction],
+        verified_instructions: &mut [bool],
         receipt: &Pubkey,
         mint: &Pubkey,
         reference: &Pubkey,
-    ) -> Result<(u64, u64)> {
+    ) -> Result<u64> {
         let receipt_ata = get_associated_token_address(receipt, mint);
-        // 1. receipt_ata exists
-        let ata_index = instruction
-            .accounts
+
+        // 1. find the spl transfer instruction (checked or normal) to receipt_ata
+        let (instruction_index, remaining_accounts, amount) = instructions
             .iter()
-            .position(|account| account.pubkey.eq(&receipt_ata))
+            .enumerate()
+            .filter(|(index, _)| !verified_instructions[*index])
+            .find_map(|(index, instruction)| {
+                let (transfer_data, remaining_accounts, amount) =
+                    Self::decode_transfer_instruction_data(instruction).ok()?;
+                transfer_data.destination.eq(&receipt_ata).then_some((
+                    index,
+                    remaining_accounts,
+                    amount,
+                ))
+            })
             .ok_or(ServiceError::Web3Error(
                 Web3ErrorType::ValidateTransferError("Receipt not found".to_string()),
             ))?;
 
-        // 2. check if spl transfer instruction (checked or normal)
-        let (_, remaining_accounts, _) = Self::decode_transfer_instruction_data(instruction)?;
-
-        // 3. reference key exists  (todo: reference could be array)
+        // 2. reference key exists  (todo: reference could be array)
         remaining_accounts
             .iter()
             .find(|account| account.pubkey.eq(reference))
@@ -251,20 +343,69 @@ impl Indexer {
                 Web3ErrorType::ValidateTransferError("Invalid reference".to_string()),
             ))?;
 
-        // 4. amount check
-        let pre_balance = meta
-            .pre_balances
-            .get(ata_index)
-            .or_else(|| Some(&0))
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+        verified_instructions[instruction_index] = true;
+        Ok(amount)
+    }
 
-        let post_balance = meta
-            .post_balances
-            .get(ata_index)
-            .or_else(|| Some(&0))
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+    // Total owed to each receipt token account
+    fn get_expected_amounts(legs: &[TransferLeg], mint: &Pubkey) -> Result<HashMap<Pubkey, u64>> {
+        let mut expected: HashMap<Pubkey, u64> = HashMap::new();
+        for leg in legs {
+            let receipt = Pubkey::from_str(&leg.receiver_wallet)?;
+            let total = expected
+                .entry(get_associated_token_address(&receipt, mint))
+                .or_default();
+            *total = total
+                .checked_add(leg.amount)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+        }
 
-        Ok((pre_balance.to_owned(), post_balance.to_owned()))
+        Ok(expected)
+    }
+
+    // Token balance change of receipt_ata in the transaction
+    fn get_received_amount(
+        message: &VersionedMessage,
+        meta: &UiTransactionStatusMeta,
+        receipt_ata: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<u64> {
+        let ata_index = message
+            .static_account_keys()
+            .iter()
+            .position(|account| account.eq(receipt_ata))
+            .ok_or(ServiceError::Web3Error(
+                Web3ErrorType::ValidateTransferError("Receipt not found".to_string()),
+            ))?;
+
+        let pre_balance = Self::get_token_balance(&meta.pre_token_balances, ata_index, mint)?;
+        let post_balance = Self::get_token_balance(&meta.post_token_balances, ata_index, mint)?;
+        Ok(post_balance.saturating_sub(pre_balance))
+    }
+
+    // Missing token balance means the account didn't exist (or held nothing) at that point
+    fn get_token_balance(
+        token_balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+        account_index: usize,
+        mint: &Pubkey,
+    ) -> Result<u64> {
+        let token_balances = match token_balances {
+            OptionSerializer::Some(token_balances) => token_balances,
+            _ => return Ok(0),
+        };
+
+        let token_balance = token_balances.iter().find(|balance| {
+            usize::from(balance.account_index) == account_index && balance.mint == mint.to_string()
+        });
+
+        match token_balance {
+            Some(balance) => balance.ui_token_amount.amount.parse::<u64>().map_err(|_| {
+                ServiceError::Web3Error(Web3ErrorType::ValidateTransferError(
+                    "Invalid token balance".to_string(),
+                ))
+            }),
+            None => Ok(0),
+        }
     }
 
     fn decode_transfer_instruction_data(
@@ -353,3 +494,42 @@ impl Indexer {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::services::{indexer::Indexer, web3::TransferLeg};
+    use anyhow::Result;
+    use spl_associated_token_account::get_associated_token_address;
+    use spl_token::solana_program::pubkey::Pubkey;
+
+    #[test]
+    fn test_expected_amounts() -> Result<()> {
+        let mint = Pubkey::new_unique();
+        let merchant = Pubkey::new_unique();
+        let venue = Pubkey::new_unique();
+        let legs = vec![
+            TransferLeg {
+                receiver_wallet: merchant.to_string(),
+                amount: 70,
+            },
+            TransferLeg {
+                receiver_wallet: venue.to_string(),
+                amount: 20,
+            },
+            TransferLeg {
+                receiver_wallet: merchant.to_string(),
+                amount: 10,
+            },
+        ];
+
+        let expected = Indexer::get_expected_amounts(&legs, &mint)?;
+        assert_eq!(expected.len(), 2);
+        assert_eq!(
+            expected[&get_associated_token_address(&merchant, &mint)],
+            80
+        );
+        assert_eq!(expected[&get_associated_token_address(&venue, &mint)], 20);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustmentDto {
+    pub user_id: i32,
+
+    // Token base units, negative to debit the user
+    pub amount: i64,
+
+    pub note: String,
+}
@@ -0,0 +1,9 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceQueryDto {
+    // Defaults to now
+    pub at: Option<NaiveDateTime>,
+}
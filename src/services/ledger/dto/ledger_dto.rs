@@ -0,0 +1,98 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::{
+    db::entity::{
+        ledger_transaction::Model as LedgerTransactionModel,
+        sea_orm_active_enums::LedgerTransactionKind,
+    },
+    services::ledger::{AccountBalance, LedgerCheck},
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalanceDto {
+    pub id: i32,
+
+    pub account_type: String,
+
+    pub reference: String,
+
+    pub balance: i64,
+}
+
+impl From<AccountBalance> for AccountBalanceDto {
+    fn from(value: AccountBalance) -> Self {
+        AccountBalanceDto {
+            id: value.id,
+            account_type: value.account_type,
+            reference: value.reference,
+            balance: value.balance,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerTransactionDto {
+    pub id: i32,
+
+    pub kind: LedgerTransactionKind,
+
+    pub transfer_id: Option<i32>,
+
+    pub escrow_id: Option<i32>,
+
+    pub signature: Option<String>,
+
+    pub note: Option<String>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<LedgerTransactionModel> for LedgerTransactionDto {
+    fn from(value: LedgerTransactionModel) -> Self {
+        LedgerTransactionDto {
+            id: value.id,
+            kind: value.kind,
+            transfer_id: value.transfer_id,
+            escrow_id: value.escrow_id,
+            signature: value.signature,
+            note: value.note,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LedgerCheckDto {
+    pub is_consistent: bool,
+
+    // Sum of every entry, must be zero
+    pub total: i64,
+
+    pub unbalanced_transactions: Vec<i32>,
+
+    pub escrow_ledger_balance: i64,
+
+    pub escrow_on_chain_balance: Option<u64>,
+
+    pub treasury_ledger_balance: i64,
+
+    pub treasury_on_chain_balance: Option<u64>,
+}
+
+impl From<LedgerCheck> for LedgerCheckDto {
+    fn from(value: LedgerCheck) -> Self {
+        LedgerCheckDto {
+            is_consistent: value.is_consistent(),
+            total: value.total,
+            unbalanced_transactions: value.unbalanced_transactions,
+            escrow_ledger_balance: value.escrow_ledger_balance,
+            escrow_on_chain_balance: value.escrow_on_chain_balance,
+            treasury_ledger_balance: value.treasury_ledger_balance,
+            treasury_on_chain_balance: value.treasury_on_chain_balance,
+        }
+    }
+}
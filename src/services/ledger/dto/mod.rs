@@ -0,0 +1,4 @@
+pub mod adjustment_dto;
+pub mod balance_query_dto;
+pub mod ledger_dto;
+pub mod referral_reward_dto;
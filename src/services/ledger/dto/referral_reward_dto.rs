@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferralRewardDto {
+    pub user_id: i32,
+
+    // Token base units
+    pub amount: u64,
+
+    pub note: Option<String>,
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::services::{
+    AppState,
+    error::Result,
+    ledger::{
+        LedgerService,
+        dto::{
+            adjustment_dto::AdjustmentDto,
+            balance_query_dto::BalanceQueryDto,
+            ledger_dto::{AccountBalanceDto, LedgerCheckDto, LedgerTransactionDto},
+            referral_reward_dto::ReferralRewardDto,
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+pub async fn find_balances(
+    State(state): State<Arc<AppState>>,
+    Query(balance_query_dto): Query<BalanceQueryDto>,
+) -> Result<Json<Vec<AccountBalanceDto>>> {
+    let balances = LedgerService::find_balances(state, balance_query_dto.at).await?;
+    let balances = balances
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<AccountBalanceDto>>();
+
+    Ok(Json(balances))
+}
+
+pub async fn check(State(state): State<Arc<AppState>>) -> Result<Json<LedgerCheckDto>> {
+    let check = LedgerService::check(state).await?;
+    Ok(Json(check.into()))
+}
+
+pub async fn post_adjustment(
+    State(state): State<Arc<AppState>>,
+    Json(adjustment_dto): Json<AdjustmentDto>,
+) -> Result<Json<LedgerTransactionDto>> {
+    let ledger_transaction = LedgerService::post_adjustment(state, adjustment_dto).await?;
+    Ok(Json(ledger_transaction.into()))
+}
+
+pub async fn post_referral_reward(
+    State(state): State<Arc<AppState>>,
+    Json(referral_reward_dto): Json<ReferralRewardDto>,
+) -> Result<Json<LedgerTransactionDto>> {
+    let ledger_transaction =
+        LedgerService::post_referral_reward(state, referral_reward_dto).await?;
+    Ok(Json(ledger_transaction.into()))
+}
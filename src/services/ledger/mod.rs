@@ -0,0 +1,590 @@
+pub mod dto;
+pub mod ledger_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    constants::{TREASURY_PUBKEY, USDC_MINT},
+    db::entity::{
+        escrow::Model as EscrowModel,
+        ledger_account::{self, Model as LedgerAccountModel},
+        ledger_entry, ledger_transaction,
+        ledger_transaction::Model as LedgerTransactionModel,
+        payment::Model as PaymentModel,
+        payment_split::Model as PaymentSplitModel,
+        prelude::{LedgerAccount, LedgerEntry, LedgerTransaction, PaymentSplit},
+        sea_orm_active_enums::{LedgerAccountType, LedgerTransactionKind},
+        transfer::Model as TransferModel,
+    },
+    services::{
+        AppState,
+        error::{MathErrorType, Result, ServiceError},
+        ledger::dto::{adjustment_dto::AdjustmentDto, referral_reward_dto::ReferralRewardDto},
+        payment::get_split_amounts,
+        user::UserService,
+        web3::{calculate_transfer_fee, get_escrow_pubkey},
+    },
+};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DbBackend, EntityTrait, FromQueryResult,
+    ModelTrait, QueryFilter, Statement, TransactionTrait, sea_query::OnConflict,
+};
+use spl_token::solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+/**
+ * Internal double-entry ledger. Every ledger transaction is a set of entries that sum to zero,
+ * a positive amount increases the account's balance. Payer wallets go negative as money enters
+ * the system, merchants, split recipients, treasury and escrow accounts hold what they are owed.
+ * Merchant accounts hold payouts, user accounts hold rewards and adjustments.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Posting {
+    pub account_type: LedgerAccountType,
+    pub reference: String,
+    pub amount: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+pub struct AccountBalance {
+    pub id: i32,
+    pub account_type: String,
+    pub reference: String,
+    pub balance: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct Total {
+    total: i64,
+}
+
+#[derive(Debug, FromQueryResult)]
+struct UnbalancedTransaction {
+    id: i32,
+}
+
+pub struct LedgerCheck {
+    pub total: i64,
+    pub unbalanced_transactions: Vec<i32>,
+    pub escrow_ledger_balance: i64,
+    pub escrow_on_chain_balance: Option<u64>,
+    pub treasury_ledger_balance: i64,
+    pub treasury_on_chain_balance: Option<u64>,
+}
+
+impl LedgerCheck {
+    /**
+     * Ledger sums to zero, escrow holds exactly what the ledger says it owes and the treasury holds
+     * at least its ledger balance. Rewards are booked against the treasury before they are paid out,
+     * so the treasury can hold more than the ledger says but never less.
+     */
+    pub fn is_consistent(&self) -> bool {
+        self.total == 0
+            && self.unbalanced_transactions.is_empty()
+            && self
+                .escrow_on_chain_balance
+                .is_some_and(|balance| i64::try_from(balance) == Ok(self.escrow_ledger_balance))
+            && self.treasury_on_chain_balance.is_some_and(|balance| {
+                i64::try_from(balance).is_ok_and(|balance| balance >= self.treasury_ledger_balance)
+            })
+    }
+}
+
+pub struct LedgerService;
+
+impl LedgerService {
+    // Completed transfer: payer pays gross, treasury takes the fee, the rest goes to merchant and splits or escrow
+    pub async fn post_transfer<C: ConnectionTrait>(
+        db: &C,
+        payment: &PaymentModel,
+        transfer: &TransferModel,
+    ) -> Result<()> {
+        // Test mode transfers move test cluster tokens and stay out of the books
+        if !payment.livemode {
+            return Ok(());
+        }
+
+        let gross = to_amount(transfer.amount)?;
+        let (fee, net) = calculate_transfer_fee(gross)?;
+
+        let mut postings = vec![
+            posting(
+                LedgerAccountType::Wallet,
+                &transfer.sender_wallet_address,
+                -from_amount(gross)?,
+            ),
+            posting(
+                LedgerAccountType::Treasury,
+                TREASURY_PUBKEY,
+                from_amount(fee)?,
+            ),
+        ];
+
+        if payment.is_escrow {
+            postings.push(posting(
+                LedgerAccountType::Escrow,
+                &get_escrow_pubkey()?.to_string(),
+                from_amount(net)?,
+            ));
+        } else {
+            postings.extend(Self::get_payout_postings(db, payment, net).await?);
+        }
+
+        Self::post(
+            db,
+            LedgerTransactionKind::Transfer,
+            Some(transfer.id),
+            None,
+            transfer.signature.clone(),
+            None,
+            postings,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Escrow paid out on-chain, either to the merchant and splits or back to the payer
+    pub async fn post_escrow_settlement<C: ConnectionTrait>(
+        db: &C,
+        escrow: &EscrowModel,
+        transfer: &TransferModel,
+        payment: &PaymentModel,
+        kind: LedgerTransactionKind,
+        signature: Option<String>,
+    ) -> Result<()> {
+        let amount = to_amount(escrow.amount)?;
+
+        let mut postings = vec![posting(
+            LedgerAccountType::Escrow,
+            &get_escrow_pubkey()?.to_string(),
+            -from_amount(amount)?,
+        )];
+
+        match kind {
+            LedgerTransactionKind::EscrowRelease => {
+                postings.extend(Self::get_payout_postings(db, payment, amount).await?);
+            }
+            LedgerTransactionKind::EscrowRefund => postings.push(posting(
+                LedgerAccountType::Wallet,
+                &transfer.sender_wallet_address,
+                from_amount(amount)?,
+            )),
+            _ => {
+                return Err(ServiceError::Custom(
+                    "Invalid escrow settlement".to_string(),
+                ));
+            }
+        }
+
+        Self::post(
+            db,
+            kind,
+            Some(transfer.id),
+            Some(escrow.id),
+            signature,
+            None,
+            postings,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    // Rewards are owed by the treasury to the user
+    pub async fn post_referral_reward(
+        state: Arc<AppState>,
+        referral_reward_dto: ReferralRewardDto,
+    ) -> Result<LedgerTransactionModel> {
+        let user = UserService::find_one(state.clone(), referral_reward_dto.user_id).await?;
+        let amount = from_amount(referral_reward_dto.amount)?;
+        if amount == 0 {
+            return Err(ServiceError::DtoError(
+                "Amount must be greater than zero".to_string(),
+            ));
+        }
+
+        let txn = state.db().begin().await?;
+        let ledger_transaction = Self::post(
+            &txn,
+            LedgerTransactionKind::ReferralReward,
+            None,
+            None,
+            None,
+            referral_reward_dto.note,
+            vec![
+                posting(LedgerAccountType::Treasury, TREASURY_PUBKEY, -amount),
+                posting(LedgerAccountType::User, &user.id.to_string(), amount),
+            ],
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(ledger_transaction)
+    }
+
+    // Manual correction against the adjustment account, amount can be negative
+    pub async fn post_adjustment(
+        state: Arc<AppState>,
+        adjustment_dto: AdjustmentDto,
+    ) -> Result<LedgerTransactionModel> {
+        let user = UserService::find_one(state.clone(), adjustment_dto.user_id).await?;
+        let amount = adjustment_dto.amount;
+        if amount == 0 {
+            return Err(ServiceError::DtoError(
+                "Amount must not be zero".to_string(),
+            ));
+        }
+        let offset = amount
+            .checked_neg()
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        let txn = state.db().begin().await?;
+        let ledger_transaction = Self::post(
+            &txn,
+            LedgerTransactionKind::Adjustment,
+            None,
+            None,
+            None,
+            Some(adjustment_dto.note),
+            vec![
+                posting(LedgerAccountType::Adjustment, "adjustment", offset),
+                posting(LedgerAccountType::User, &user.id.to_string(), amount),
+            ],
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(ledger_transaction)
+    }
+
+    // Balance of every account including entries up to `at`
+    pub async fn find_balances(
+        state: Arc<AppState>,
+        at: Option<NaiveDateTime>,
+    ) -> Result<Vec<AccountBalance>> {
+        let at = at.unwrap_or_else(|| Utc::now().naive_utc());
+
+        let balances = AccountBalance::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                a.id,
+                a.account_type::text AS account_type,
+                a.reference,
+                coalesce(sum(e.amount), 0)::bigint AS balance
+            FROM ledger_account a
+            LEFT JOIN ledger_entry e ON e.ledger_account_id = a.id AND e.created_at <= $1
+            GROUP BY a.id
+            ORDER BY a.id
+            "#,
+            [at.into()],
+        ))
+        .all(state.db())
+        .await?;
+
+        Ok(balances)
+    }
+
+    // Verifies the ledger is balanced and the escrow account matches its on-chain token balance
+    pub async fn check(state: Arc<AppState>) -> Result<LedgerCheck> {
+        let total = Total::find_by_statement(Statement::from_string(
+            DbBackend::Postgres,
+            "SELECT coalesce(sum(amount), 0)::bigint AS total FROM ledger_entry",
+        ))
+        .one(state.db())
+        .await?
+        .map(|val| val.total)
+        .unwrap_or(0);
+
+        let unbalanced_transactions =
+            UnbalancedTransaction::find_by_statement(Statement::from_string(
+                DbBackend::Postgres,
+                "SELECT ledger_transaction_id AS id FROM ledger_entry \
+                 GROUP BY ledger_transaction_id HAVING sum(amount) <> 0",
+            ))
+            .all(state.db())
+            .await?
+            .into_iter()
+            .map(|val| val.id)
+            .collect();
+
+        let escrow = get_escrow_pubkey()?;
+        let treasury = Pubkey::from_str(TREASURY_PUBKEY)?;
+
+        let escrow_ledger_balance = Self::find_account_balance(
+            state.clone(),
+            LedgerAccountType::Escrow,
+            &escrow.to_string(),
+        )
+        .await?;
+        let treasury_ledger_balance =
+            Self::find_account_balance(state.clone(), LedgerAccountType::Treasury, TREASURY_PUBKEY)
+                .await?;
+
+        // A missing token account means nothing was ever paid to it
+        let on_chain_balance =
+            async |owner: &Pubkey| match state.web3.get_token_balance(owner, USDC_MINT).await {
+                Ok(balance) => Some(balance),
+                Err(e) => {
+                    tracing::error!("Failed to fetch token balance of {}: {:?}", owner, e);
+                    None
+                }
+            };
+
+        Ok(LedgerCheck {
+            total,
+            unbalanced_transactions,
+            escrow_ledger_balance,
+            escrow_on_chain_balance: on_chain_balance(&escrow).await,
+            treasury_ledger_balance,
+            treasury_on_chain_balance: on_chain_balance(&treasury).await,
+        })
+    }
+
+    async fn find_account_balance(
+        state: Arc<AppState>,
+        account_type: LedgerAccountType,
+        reference: &str,
+    ) -> Result<i64> {
+        let account = LedgerAccount::find()
+            .filter(ledger_account::Column::AccountType.eq(account_type))
+            .filter(ledger_account::Column::Reference.eq(reference))
+            .one(state.db())
+            .await?;
+
+        let Some(account) = account else {
+            return Ok(0);
+        };
+
+        let entries = account.find_related(LedgerEntry).all(state.db()).await?;
+        entries.iter().try_fold(0i64, |balance, entry| {
+            balance
+                .checked_add(entry.amount)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))
+        })
+    }
+
+    async fn get_payout_postings<C: ConnectionTrait>(
+        db: &C,
+        payment: &PaymentModel,
+        amount: u64,
+    ) -> Result<Vec<Posting>> {
+        let splits = payment.find_related(PaymentSplit).all(db).await?;
+
+        get_payout_postings(payment.user_id, amount, &splits)
+    }
+
+    async fn post<C: ConnectionTrait>(
+        db: &C,
+        kind: LedgerTransactionKind,
+        transfer_id: Option<i32>,
+        escrow_id: Option<i32>,
+        signature: Option<String>,
+        note: Option<String>,
+        postings: Vec<Posting>,
+    ) -> Result<LedgerTransactionModel> {
+        let total = postings.iter().try_fold(0i64, |total, posting| {
+            total
+                .checked_add(posting.amount)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))
+        })?;
+        if total != 0 {
+            return Err(ServiceError::Custom(format!(
+                "Ledger transaction is unbalanced by {}",
+                total
+            )));
+        }
+
+        let now = Utc::now().naive_utc();
+        let data = ledger_transaction::ActiveModel {
+            kind: Set(kind),
+            transfer_id: Set(transfer_id),
+            escrow_id: Set(escrow_id),
+            signature: Set(signature),
+            note: Set(note),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let ledger_transaction = LedgerTransaction::insert(data)
+            .exec_with_returning(db)
+            .await?;
+
+        for posting in postings.into_iter().filter(|posting| posting.amount != 0) {
+            let account =
+                Self::find_or_create_account(db, posting.account_type, &posting.reference).await?;
+
+            let data = ledger_entry::ActiveModel {
+                ledger_transaction_id: Set(ledger_transaction.id),
+                ledger_account_id: Set(account.id),
+                amount: Set(posting.amount),
+                created_at: Set(now),
+                ..Default::default()
+            };
+            LedgerEntry::insert(data).exec(db).await?;
+        }
+
+        Ok(ledger_transaction)
+    }
+
+    async fn find_or_create_account<C: ConnectionTrait>(
+        db: &C,
+        account_type: LedgerAccountType,
+        reference: &str,
+    ) -> Result<LedgerAccountModel> {
+        let data = ledger_account::ActiveModel {
+            account_type: Set(account_type.clone()),
+            reference: Set(reference.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        LedgerAccount::insert(data)
+            .on_conflict(
+                OnConflict::columns([
+                    ledger_account::Column::AccountType,
+                    ledger_account::Column::Reference,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec(db)
+            .await?;
+
+        let account = LedgerAccount::find()
+            .filter(ledger_account::Column::AccountType.eq(account_type))
+            .filter(ledger_account::Column::Reference.eq(reference))
+            .one(db)
+            .await?
+            .ok_or(ServiceError::Database("Ledger account missing".to_string()))?;
+
+        Ok(account)
+    }
+}
+
+// Split recipients get their share, the merchant gets the remainder
+fn get_payout_postings(
+    user_id: i32,
+    amount: u64,
+    splits: &[PaymentSplitModel],
+) -> Result<Vec<Posting>> {
+    let (legs, remaining) = get_split_amounts(amount, splits)?;
+
+    let mut postings = legs
+        .iter()
+        .map(|leg| {
+            Ok(posting(
+                LedgerAccountType::Wallet,
+                &leg.receiver_wallet,
+                from_amount(leg.amount)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    postings.push(posting(
+        LedgerAccountType::Merchant,
+        &user_id.to_string(),
+        from_amount(remaining)?,
+    ));
+
+    Ok(postings)
+}
+
+fn posting(account_type: LedgerAccountType, reference: &str, amount: i64) -> Posting {
+    Posting {
+        account_type,
+        reference: reference.to_string(),
+        amount,
+    }
+}
+
+fn to_amount(amount: i64) -> Result<u64> {
+    u64::try_from(amount).map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))
+}
+
+fn from_amount(amount: u64) -> Result<i64> {
+    i64::try_from(amount).map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::{BASE_USDC, TREASURY_PUBKEY},
+        db::entity::{
+            payment_split::Model as PaymentSplitModel,
+            sea_orm_active_enums::{LedgerAccountType, SplitType},
+        },
+        services::{
+            ledger::{LedgerCheck, get_payout_postings, posting},
+            web3::calculate_transfer_fee,
+        },
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_ledger_balances() -> Result<()> {
+        let gross = 100 * BASE_USDC;
+        let (fee, net) = calculate_transfer_fee(gross)?;
+        let splits = vec![PaymentSplitModel {
+            id: 1,
+            payment_id: 1,
+            wallet_address: "venue".to_string(),
+            split_type: SplitType::Fixed,
+            value: 9,
+        }];
+
+        let mut transfer = vec![
+            posting(LedgerAccountType::Wallet, "payer", -i64::try_from(gross)?),
+            posting(
+                LedgerAccountType::Treasury,
+                TREASURY_PUBKEY,
+                i64::try_from(fee)?,
+            ),
+        ];
+        transfer.extend(get_payout_postings(7, net, &splits)?);
+        let reward = vec![
+            posting(LedgerAccountType::Treasury, TREASURY_PUBKEY, -100),
+            posting(LedgerAccountType::User, "7", 100),
+        ];
+
+        assert_eq!(transfer.iter().map(|val| val.amount).sum::<i64>(), 0);
+        assert_eq!(reward.iter().map(|val| val.amount).sum::<i64>(), 0);
+        let postings = [transfer, reward].concat();
+        let balance = |account_type: LedgerAccountType, reference: &str| {
+            postings
+                .iter()
+                .filter(|val| val.account_type == account_type && val.reference == reference)
+                .map(|val| val.amount)
+                .sum::<i64>()
+        };
+
+        // Payouts and rewards of the same user land on different accounts
+        let merchant = i64::try_from(net - 9 * BASE_USDC)?;
+        let treasury = i64::try_from(fee)? - 100;
+        assert_eq!(balance(LedgerAccountType::Merchant, "7"), merchant);
+        assert_eq!(balance(LedgerAccountType::User, "7"), 100);
+        assert_eq!(
+            balance(LedgerAccountType::Wallet, "venue"),
+            i64::try_from(9 * BASE_USDC)?
+        );
+        assert_eq!(
+            balance(LedgerAccountType::Treasury, TREASURY_PUBKEY),
+            treasury
+        );
+
+        let check = |treasury_on_chain_balance| LedgerCheck {
+            total: postings.iter().map(|val| val.amount).sum(),
+            unbalanced_transactions: vec![],
+            escrow_ledger_balance: 0,
+            escrow_on_chain_balance: Some(0),
+            treasury_ledger_balance: treasury,
+            treasury_on_chain_balance,
+        };
+        // The unpaid reward is still in the treasury wallet
+        assert!(check(Some(fee)).is_consistent());
+        assert!(check(Some(u64::try_from(treasury)?)).is_consistent());
+        assert!(!check(Some(u64::try_from(treasury)? - 1)).is_consistent());
+        assert!(!check(None).is_consistent());
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use serde::Serialize;
+
+use crate::services::{
+    get_public_url,
+    media::{ImageVariant, is_single_file},
+};
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageVariantsDto {
+    pub thumbnail: String,
+
+    pub card: String,
+
+    pub full: String,
+}
+
+impl ImageVariantsDto {
+    // Every variant of a single file image points to that file
+    pub fn from_key(key: &str) -> Self {
+        let is_single_file = is_single_file(key);
+        let url = |variant: ImageVariant| {
+            if is_single_file {
+                get_public_url(key)
+            } else {
+                get_public_url(&variant.key(key))
+            }
+        };
+
+        ImageVariantsDto {
+            thumbnail: url(ImageVariant::Thumbnail),
+            card: url(ImageVariant::Card),
+            full: url(ImageVariant::Full),
+        }
+    }
+}
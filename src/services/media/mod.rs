@@ -0,0 +1,210 @@
+pub mod dto;
+
+use std::io::Cursor;
+
+use crate::{
+    constants::{
+        IMAGE_ALLOWED_MIME_TYPES, IMAGE_JPEG_QUALITY, IMAGE_MAX_DIMENSION,
+        IMAGE_PROCESSING_CONCURRENCY,
+    },
+    services::{
+        error::{Result, ServiceError},
+        storage::Storage,
+    },
+};
+use axum::body::Bytes;
+use image::{
+    DynamicImage, ImageDecoder, ImageReader, Limits, codecs::jpeg::JpegEncoder,
+    imageops::FilterType,
+};
+use strum::IntoEnumIterator;
+use strum_macros::{AsRefStr, EnumIter};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+static IMAGE_PROCESSING: Semaphore = Semaphore::const_new(IMAGE_PROCESSING_CONCURRENCY);
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ImageVariant {
+    Thumbnail,
+    Card,
+    Full,
+}
+
+impl ImageVariant {
+    // Thumbnails and cards are cropped to fill, full images keep their aspect ratio
+    fn resize(&self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageVariant::Thumbnail => image.resize_to_fill(160, 160, FilterType::Lanczos3),
+            ImageVariant::Card => image.resize_to_fill(640, 360, FilterType::Lanczos3),
+            ImageVariant::Full if image.width() > 1920 || image.height() > 1920 => {
+                image.resize(1920, 1920, FilterType::Lanczos3)
+            }
+            ImageVariant::Full => image.clone(),
+        }
+    }
+
+    // Must match how `Storage::upload_file_as` names files
+    pub fn key(&self, image_key: &str) -> String {
+        format!("{}/{}.jpg", image_key, self.as_ref())
+    }
+}
+
+// Every `ImageVariant` of an image, encoded and ready to upload
+pub struct ProcessedImage(Vec<(ImageVariant, Vec<u8>)>);
+
+pub struct MediaService;
+
+impl MediaService {
+    /**
+     * Validates and re-encodes an uploaded image into every `ImageVariant`, which also drops
+     * EXIF and other metadata. Returns the key the variants are stored under, see
+     * `ImageVariantsDto::from_key`.
+     */
+    pub async fn upload_image(
+        storage: &dyn Storage,
+        s3_folder: &str,
+        file: Bytes,
+    ) -> Result<String> {
+        let image = Self::process_image(file).await?;
+        Self::upload_variants(storage, s3_folder, image).await
+    }
+
+    pub async fn process_image(file: Bytes) -> Result<ProcessedImage> {
+        let _permit = IMAGE_PROCESSING
+            .acquire()
+            .await
+            .map_err(|e| ServiceError::Custom(e.to_string()))?;
+        let variants = tokio::task::spawn_blocking(move || process_image(&file))
+            .await
+            .map_err(|e| ServiceError::Custom(e.to_string()))??;
+
+        Ok(ProcessedImage(variants))
+    }
+
+    // Variants uploaded before a failure are deleted again
+    pub async fn upload_variants(
+        storage: &dyn Storage,
+        s3_folder: &str,
+        image: ProcessedImage,
+    ) -> Result<String> {
+        let key = format!("{}/{}", s3_folder.trim_end_matches('/'), Uuid::new_v4());
+        for (variant, bytes) in image.0 {
+            let uploaded = storage
+                .upload_file_as(
+                    &key,
+                    &Bytes::from(bytes),
+                    Some(variant.as_ref().to_string()),
+                    "image/jpeg",
+                    "jpg",
+                )
+                .await;
+            if let Err(e) = uploaded {
+                Self::delete_image(storage, &key).await;
+                return Err(e);
+            }
+        }
+
+        Ok(key)
+    }
+
+    // Best effort, a replaced image that fails to delete is only logged
+    pub async fn delete_image(storage: &dyn Storage, key: &str) {
+        let keys = if is_single_file(key) {
+            vec![key.to_string()]
+        } else {
+            ImageVariant::iter()
+                .map(|variant| variant.key(key))
+                .collect()
+        };
+        for key in keys {
+            if let Err(e) = storage.delete_object(&key).await {
+                tracing::warn!("Failed to delete image {}: {:?}", key, e);
+            }
+        }
+    }
+}
+
+// Images uploaded before the pipeline are a single file rather than a folder of variants
+fn is_single_file(key: &str) -> bool {
+    key.rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'))
+}
+
+fn process_image(file: &[u8]) -> Result<Vec<(ImageVariant, Vec<u8>)>> {
+    let invalid = |message: &str| ServiceError::DtoError(message.to_string());
+
+    let mime_type = infer::get(file).map(|kind| kind.mime_type());
+    if !mime_type.is_some_and(|mime_type| IMAGE_ALLOWED_MIME_TYPES.contains(&mime_type)) {
+        return Err(invalid("Image must be a JPEG, PNG or WebP"));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(IMAGE_MAX_DIMENSION);
+    limits.max_image_height = Some(IMAGE_MAX_DIMENSION);
+
+    let mut reader = ImageReader::new(Cursor::new(file))
+        .with_guessed_format()
+        .map_err(|_| invalid("Image is invalid"))?;
+    reader.limits(limits);
+
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|_| invalid("Image is invalid or too large"))?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|_| invalid("Image is invalid"))?;
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|_| invalid("Image is invalid or too large"))?;
+    // Metadata is dropped on re-encode, so the EXIF rotation is applied to the pixels first
+    image.apply_orientation(orientation);
+
+    ImageVariant::iter()
+        .map(|variant| {
+            let mut bytes = Vec::new();
+            let encoder = JpegEncoder::new_with_quality(&mut bytes, IMAGE_JPEG_QUALITY);
+            DynamicImage::ImageRgb8(variant.resize(&image).to_rgb8())
+                .write_with_encoder(encoder)
+                .map_err(|e| ServiceError::Custom(e.to_string()))?;
+            Ok((variant, bytes))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use crate::services::media::{ImageVariant, process_image};
+    use anyhow::Result;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    #[test]
+    fn test_process_image() -> Result<()> {
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(1000, 500))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+        let variants = process_image(&png)?;
+        let sizes: Vec<(ImageVariant, (u32, u32))> = variants
+            .iter()
+            .map(|(variant, bytes)| {
+                let image = image::load_from_memory(bytes)?;
+                Ok((*variant, (image.width(), image.height())))
+            })
+            .collect::<Result<_>>()?;
+        assert_eq!(
+            sizes,
+            vec![
+                (ImageVariant::Thumbnail, (160, 160)),
+                (ImageVariant::Card, (640, 360)),
+                (ImageVariant::Full, (1000, 500)),
+            ]
+        );
+
+        assert!(process_image(b"%PDF-1.4 not an image").is_err());
+        Ok(())
+    }
+}
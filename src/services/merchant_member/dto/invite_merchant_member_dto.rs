@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::db::entity::sea_orm_active_enums::MerchantMemberRole;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteMerchantMemberDto {
+    #[validate(email)]
+    pub email: String,
+
+    pub role: MerchantMemberRole,
+}
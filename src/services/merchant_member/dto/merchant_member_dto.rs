@@ -0,0 +1,38 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{
+    merchant_member::Model as MerchantMemberModel, sea_orm_active_enums::MerchantMemberRole,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchantMemberDto {
+    pub id: i32,
+
+    pub merchant_id: i32,
+
+    pub email: String,
+
+    pub user_id: Option<i32>,
+
+    pub role: MerchantMemberRole,
+
+    pub accepted_at: Option<NaiveDateTime>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<MerchantMemberModel> for MerchantMemberDto {
+    fn from(value: MerchantMemberModel) -> Self {
+        MerchantMemberDto {
+            id: value.id,
+            merchant_id: value.merchant_id,
+            email: value.email,
+            user_id: value.user_id,
+            role: value.role,
+            accepted_at: value.accepted_at,
+            created_at: value.created_at,
+        }
+    }
+}
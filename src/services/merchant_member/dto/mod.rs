@@ -0,0 +1,3 @@
+pub mod invite_merchant_member_dto;
+pub mod merchant_member_dto;
+pub mod update_merchant_member_dto;
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+use crate::db::entity::sea_orm_active_enums::MerchantMemberRole;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMerchantMemberDto {
+    pub role: MerchantMemberRole,
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        merchant_member::{
+            MerchantMemberService,
+            dto::{
+                invite_merchant_member_dto::InviteMerchantMemberDto,
+                merchant_member_dto::MerchantMemberDto,
+                update_merchant_member_dto::UpdateMerchantMemberDto,
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use validator::Validate;
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<MerchantMemberDto>>> {
+    let members = MerchantMemberService::find_all(state, ctx).await?;
+    Ok(Json(members.into_iter().map(|val| val.into()).collect()))
+}
+
+pub async fn invite(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(invite_merchant_member_dto): Json<InviteMerchantMemberDto>,
+) -> Result<Json<MerchantMemberDto>> {
+    invite_merchant_member_dto.validate()?;
+
+    let member = MerchantMemberService::invite(state, ctx, invite_merchant_member_dto).await?;
+    Ok(Json(member.into()))
+}
+
+pub async fn accept(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<MerchantMemberDto>> {
+    let member = MerchantMemberService::accept(state, ctx, id).await?;
+    Ok(Json(member.into()))
+}
+
+pub async fn update_role(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+    Json(update_merchant_member_dto): Json<UpdateMerchantMemberDto>,
+) -> Result<Json<MerchantMemberDto>> {
+    let member =
+        MerchantMemberService::update_role(state, ctx, id, update_merchant_member_dto).await?;
+    Ok(Json(member.into()))
+}
+
+pub async fn remove(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<()> {
+    MerchantMemberService::remove(state, ctx, id).await?;
+    Ok(())
+}
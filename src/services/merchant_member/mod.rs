@@ -0,0 +1,271 @@
+pub mod dto;
+pub mod merchant_member_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    constants::MERCHANT_INVITATION_EXPIRY_SECS,
+    ctx::{Ctx, MemberCtx},
+    db::entity::{
+        merchant_member::{self, Model as MerchantMemberModel},
+        prelude::{MerchantMember, User},
+        sea_orm_active_enums::{NotificationKind, UserRoleType},
+        user,
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        merchant_member::dto::{
+            invite_merchant_member_dto::InviteMerchantMemberDto,
+            update_merchant_member_dto::UpdateMerchantMemberDto,
+        },
+        notification::NotificationService,
+        role::RoleService,
+        user::UserService,
+    },
+};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder, TransactionTrait,
+    sea_query::{Expr, ExprTrait, Func},
+};
+
+pub struct MerchantMemberService;
+
+impl MerchantMemberService {
+    const MERCHANT_MEMBER: &'static str = "MerchantMember";
+
+    pub async fn find_all(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<MerchantMemberModel>> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let members = MerchantMember::find()
+            .filter(merchant_member::Column::MerchantId.eq(merchant.id))
+            .order_by_asc(merchant_member::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(members)
+    }
+
+    // The invitee learns about the invitation from a notification to the account with the email
+    pub async fn invite(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        invite_merchant_member_dto: InviteMerchantMemberDto,
+    ) -> Result<MerchantMemberModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let owner = UserService::find_one(state.clone(), ctx.user_id).await?;
+        let email = normalize_email(&invite_merchant_member_dto.email);
+        if owner
+            .email
+            .is_some_and(|owner_email| normalize_email(&owner_email) == email)
+        {
+            return Err(ServiceError::DtoError(
+                "Merchant owners can't invite themselves".to_string(),
+            ));
+        }
+
+        let existing = MerchantMember::find()
+            .filter(merchant_member::Column::MerchantId.eq(merchant.id))
+            .filter(merchant_member::Column::Email.eq(&email))
+            .one(state.db())
+            .await?;
+        if existing.is_some() {
+            return Err(ServiceError::Custom(
+                "This email is already invited to the merchant".to_string(),
+            ));
+        }
+
+        let invitee = User::find()
+            .filter(Expr::expr(Func::lower(Expr::col(user::Column::Email))).eq(&email))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::DtoError(
+                "No account uses this email, the invitee has to sign up first".to_string(),
+            ))?;
+
+        let data = merchant_member::ActiveModel {
+            merchant_id: Set(merchant.id),
+            email: Set(email),
+            role: Set(invite_merchant_member_dto.role),
+            invited_by_id: Set(Some(ctx.user_id)),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        let txn = state.db().begin().await?;
+        let member = MerchantMember::insert(data)
+            .exec_with_returning(&txn)
+            .await?;
+        NotificationService::notify(
+            &txn,
+            invitee.id,
+            NotificationKind::MerchantInvitation,
+            format!("Invitation from {}", merchant.display_name),
+            format!("You're invited to join {} as staff", merchant.display_name),
+            Some(member.id),
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(member)
+    }
+
+    // Only the user with the invited email can accept, members get the merchant staff role
+    pub async fn accept(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<MerchantMemberModel> {
+        let member = MerchantMember::find_by_id(id)
+            .filter(merchant_member::Column::AcceptedAt.is_null())
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::PermissionDenied)?;
+
+        let now = Utc::now().naive_utc();
+        let user = UserService::find_one(state.clone(), ctx.user_id).await?;
+        check_invitation(&member, user.email.as_deref(), now)?;
+
+        // Owners and existing members can't work for another merchant
+        if UserService::find_user_merchant(state.clone(), ctx.user_id)
+            .await
+            .is_ok()
+            || Self::find_member_ctx(state.clone(), ctx.user_id)
+                .await?
+                .is_some()
+        {
+            return Err(ServiceError::Custom(
+                "User already belongs to a merchant".to_string(),
+            ));
+        }
+
+        let invited_by_id = member.invited_by_id;
+        let mut data: merchant_member::ActiveModel = member.into();
+        data.user_id = Set(Some(ctx.user_id));
+        data.accepted_at = Set(Some(now));
+
+        let txn = state.db().begin().await?;
+        let member = MerchantMember::update(data).exec(&txn).await?;
+        RoleService::insert_role(
+            &txn,
+            ctx.user_id,
+            UserRoleType::MerchantStaff,
+            invited_by_id,
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(member)
+    }
+
+    pub async fn update_role(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+        update_merchant_member_dto: UpdateMerchantMemberDto,
+    ) -> Result<MerchantMemberModel> {
+        let member = Self::find_one(state.clone(), ctx, id).await?;
+
+        let mut data: merchant_member::ActiveModel = member.into();
+        data.role = Set(update_merchant_member_dto.role);
+        let member = MerchantMember::update(data).exec(state.db()).await?;
+
+        Ok(member)
+    }
+
+    // Removing an accepted member also takes away their staff role
+    pub async fn remove(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<()> {
+        let member = Self::find_one(state.clone(), ctx, id).await?;
+        let user_id = member.user_id;
+
+        let txn = state.db().begin().await?;
+        member.delete(&txn).await?;
+        if let Some(user_id) = user_id {
+            RoleService::delete_role(&txn, user_id, UserRoleType::MerchantStaff).await?;
+        }
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    // Merchant the user works for, resolved into `Ctx` for staff
+    pub async fn find_member_ctx(state: Arc<AppState>, user_id: i32) -> Result<Option<MemberCtx>> {
+        let member = MerchantMember::find()
+            .filter(merchant_member::Column::UserId.eq(user_id))
+            .filter(merchant_member::Column::AcceptedAt.is_not_null())
+            .one(state.db())
+            .await?;
+
+        Ok(member.map(|member| MemberCtx {
+            merchant_id: member.merchant_id,
+            role: member.role,
+        }))
+    }
+
+    // Member of the merchant owned by the user
+    async fn find_one(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<MerchantMemberModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let member = MerchantMember::find_by_id(id)
+            .filter(merchant_member::Column::MerchantId.eq(merchant.id))
+            .one(state.db())
+            .await?;
+
+        member.ok_or(ServiceError::EntityNotFound {
+            entity: Self::MERCHANT_MEMBER,
+            id: EntityId::Int(id),
+        })
+    }
+}
+
+fn check_invitation(
+    member: &MerchantMemberModel,
+    email: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<()> {
+    if now - member.created_at > chrono::Duration::seconds(MERCHANT_INVITATION_EXPIRY_SECS) {
+        return Err(ServiceError::Custom("Invitation has expired".to_string()));
+    }
+    if email.is_none_or(|email| normalize_email(email) != member.email) {
+        return Err(ServiceError::PermissionDenied);
+    }
+
+    Ok(())
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::MERCHANT_INVITATION_EXPIRY_SECS,
+        db::entity::{
+            merchant_member::Model as MerchantMemberModel, sea_orm_active_enums::MerchantMemberRole,
+        },
+        services::merchant_member::check_invitation,
+    };
+    use anyhow::Result;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_accept_invitation() -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let member = MerchantMemberModel {
+            id: 1,
+            merchant_id: 1,
+            email: "cashier@example.com".to_string(),
+            user_id: None,
+            role: MerchantMemberRole::Cashier,
+            invited_by_id: Some(1),
+            accepted_at: None,
+            created_at: now,
+        };
+
+        // Emails are compared case and whitespace insensitive
+        check_invitation(&member, Some(" Cashier@Example.com"), now)?;
+        assert!(check_invitation(&member, Some("owner@example.com"), now).is_err());
+        assert!(check_invitation(&member, None, now).is_err());
+
+        let expired = now + Duration::seconds(MERCHANT_INVITATION_EXPIRY_SECS + 1);
+        assert!(check_invitation(&member, Some("cashier@example.com"), expired).is_err());
+        Ok(())
+    }
+}
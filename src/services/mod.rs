@@ -1,21 +1,43 @@
+pub mod analytics;
+pub mod api_key;
 pub mod app;
 pub mod auth;
+pub mod category;
 pub mod error;
+pub mod escrow;
 mod indexer;
+pub mod ledger;
+pub mod media;
+pub mod merchant_member;
+pub mod notification;
+pub mod pagination;
 pub mod payment;
-pub mod s3;
+pub mod promotion;
+pub mod reconciliation;
+pub mod role;
+pub mod settlement_wallet;
+pub mod slug;
+pub mod statement;
+pub mod storage;
+pub mod store;
+pub mod two_factor;
+pub mod upload;
 pub mod user;
+pub mod verification;
+pub mod wallet_challenge;
 pub mod web3;
 
 use std::sync::Arc;
 
 use crate::{
     config::config,
+    constants::{GOOGLE_JWKS_URL, USDC_MINT},
     db,
     services::{
+        auth::{google::GoogleTokenVerifier, privy::PrivyClient},
         error::{Result, ServiceError},
         indexer::Indexer,
-        s3::S3Service,
+        storage::{Storage, new_storage},
         web3::Web3Service,
     },
 };
@@ -38,26 +60,65 @@ use solana_signer::Signer;
 #[derive(Clone)]
 pub struct AppState {
     db: DatabaseConnection,
-    s3: Arc<S3Service>,
+    storage: Arc<dyn Storage>,
     web3: Arc<Web3Service>,
+    web3_test: Arc<Web3Service>,
+    test_usdc_mint: String,
+    google: Arc<GoogleTokenVerifier>,
+    privy: Arc<PrivyClient>,
 }
 
 impl AppState {
     pub async fn new() -> crate::error::Result<Self> {
         let db = db::connect_database().await?;
 
-        let s3 = S3Service::new().await;
-        let s3 = Arc::new(s3);
-
-        let web3 = Arc::new(Web3Service::new()?);
-
-        Ok(AppState { db, s3, web3 })
+        let storage = new_storage().await?;
+
+        let web3 = Arc::new(Web3Service::new(&config().RPC_URL)?);
+        let web3_test = Arc::new(Web3Service::new(&config().TEST_RPC_URL)?);
+
+        let google = Arc::new(GoogleTokenVerifier::new(
+            GOOGLE_JWKS_URL.to_string(),
+            config().GOOGLE_OAUTH_CLIENT_ID.clone(),
+        ));
+
+        let privy = Arc::new(PrivyClient::new(
+            config().PRIVY_BASE_URL.clone(),
+            config().PRIVY_APP_ID.clone(),
+            config().PRIVY_APP_SECRET.clone(),
+            config().PRIVY_VERIFICATION_KEY.clone(),
+            config().PRIVY_WEBHOOK_SECRET.clone(),
+        ));
+
+        Ok(AppState {
+            db,
+            storage,
+            web3,
+            web3_test,
+            test_usdc_mint: config().TEST_USDC_MINT.clone(),
+            google,
+            privy,
+        })
     }
 
     // Access db on in services
     pub(in crate::services) fn db(self: &Self) -> &DatabaseConnection {
         &self.db
     }
+
+    // Test mode payments are settled on the test cluster
+    pub(in crate::services) fn web3_for(&self, livemode: bool) -> &Arc<Web3Service> {
+        by_mode(livemode, &self.web3, &self.web3_test)
+    }
+
+    // The test cluster has its own USDC mint
+    pub(in crate::services) fn usdc_mint_for(&self, livemode: bool) -> &str {
+        by_mode(livemode, USDC_MINT, &self.test_usdc_mint)
+    }
+}
+
+fn by_mode<T>(livemode: bool, live: T, test: T) -> T {
+    if livemode { live } else { test }
 }
 
 pub fn hash_password(password: String) -> Result<String> {
@@ -85,14 +146,9 @@ pub fn append_timestamp(value: &String) -> String {
     appended_value
 }
 
-pub fn get_public_url(key: &String) -> String {
-    let url = format!(
-        "https://{}.s3.amazonaws.com/{}",
-        config().AWS_BUCKET_NAME,
-        key
-    );
-
-    url
+// Delegates to the configured storage backend
+pub fn get_public_url(key: &str) -> String {
+    storage::public_url(key)
 }
 
 pub fn create_wallet() -> Result<(String, String, String)> {
@@ -183,8 +239,12 @@ fn decode_keypair(private_key: &String, secret: &String) -> Result<Keypair> {
 mod test {
     use std::str::FromStr;
 
-    use crate::services::{create_wallet, decode_keypair};
+    use crate::{
+        constants::{SOLANA_TEST_RPC_URL, TEST_USDC_MINT, USDC_MINT},
+        services::{by_mode, create_wallet, decode_keypair},
+    };
     use anyhow::Result;
+    use solana_client::rpc_client::RpcClient;
     use solana_signer::Signer;
     use spl_token::solana_program::pubkey::Pubkey;
 
@@ -200,4 +260,23 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_mode_selection() -> Result<()> {
+        let rpc_client = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
+        let test_rpc_client = RpcClient::new(SOLANA_TEST_RPC_URL.to_string());
+
+        // Live mode settles mainnet USDC, test mode devnet USDC on the test cluster
+        assert_eq!(
+            by_mode(false, &rpc_client, &test_rpc_client).url(),
+            SOLANA_TEST_RPC_URL
+        );
+        assert_eq!(
+            by_mode(true, &rpc_client, &test_rpc_client).url(),
+            rpc_client.url()
+        );
+        assert_eq!(by_mode(true, USDC_MINT, TEST_USDC_MINT), USDC_MINT);
+        assert_eq!(by_mode(false, USDC_MINT, TEST_USDC_MINT), TEST_USDC_MINT);
+        Ok(())
+    }
 }
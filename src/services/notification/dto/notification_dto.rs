@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{
+    notification::Model as NotificationModel, sea_orm_active_enums::NotificationKind,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDto {
+    pub id: i32,
+
+    pub kind: NotificationKind,
+
+    pub title: String,
+
+    pub body: String,
+
+    // Invitation or verification case the notification is about
+    pub reference_id: Option<i32>,
+
+    pub read_at: Option<NaiveDateTime>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<NotificationModel> for NotificationDto {
+    fn from(value: NotificationModel) -> Self {
+        NotificationDto {
+            id: value.id,
+            kind: value.kind,
+            title: value.title,
+            body: value.body,
+            reference_id: value.reference_id,
+            read_at: value.read_at,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+pub mod dto;
+pub mod notification_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    db::entity::{
+        notification::{self, Model as NotificationModel},
+        prelude::Notification,
+        sea_orm_active_enums::NotificationKind,
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        pagination::{CursorPaginator, CursorValue, Page, PageQuery, SortDirection},
+    },
+};
+use chrono::Utc;
+use sea_orm::{ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+
+pub struct NotificationService;
+
+impl NotificationService {
+    const NOTIFICATION: &'static str = "Notification";
+
+    pub async fn find_all(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        page_query: PageQuery,
+    ) -> Result<Page<NotificationModel>> {
+        let paginator = CursorPaginator::<Notification> {
+            sort_column: notification::Column::CreatedAt,
+            id_column: notification::Column::Id,
+            direction: SortDirection::Desc,
+            sort_value: |val| CursorValue::DateTime(val.created_at),
+            id_value: |val| val.id,
+        };
+
+        let select = Notification::find().filter(notification::Column::UserId.eq(ctx.user_id));
+        paginator.fetch(state.db(), select, &page_query).await
+    }
+
+    pub async fn mark_read(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<NotificationModel> {
+        let notification = Notification::find_by_id(id)
+            .filter(notification::Column::UserId.eq(ctx.user_id))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::NOTIFICATION,
+                id: EntityId::Int(id),
+            })?;
+
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+
+        let mut data: notification::ActiveModel = notification.into();
+        data.read_at = Set(Some(Utc::now().naive_utc()));
+        let notification = Notification::update(data).exec(state.db()).await?;
+
+        Ok(notification)
+    }
+
+    // Written with the change it's about, so users are only told about what actually happened
+    pub(in crate::services) async fn notify<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        kind: NotificationKind,
+        title: String,
+        body: String,
+        reference_id: Option<i32>,
+    ) -> Result<()> {
+        let data = notification::ActiveModel {
+            user_id: Set(user_id),
+            kind: Set(kind),
+            title: Set(title),
+            body: Set(body),
+            reference_id: Set(reference_id),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        Notification::insert(data).exec(db).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        notification::{NotificationService, dto::notification_dto::NotificationDto},
+        pagination::{Page, PageQuery},
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Query(page_query): Query<PageQuery>,
+) -> Result<Json<Page<NotificationDto>>> {
+    let notifications = NotificationService::find_all(state, ctx, page_query).await?;
+    Ok(Json(notifications.map(|val| val.into())))
+}
+
+pub async fn mark_read(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<NotificationDto>> {
+    let notification = NotificationService::mark_read(state, ctx, id).await?;
+    Ok(Json(notification.into()))
+}
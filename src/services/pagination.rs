@@ -0,0 +1,212 @@
+use crate::{
+    constants::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+    services::error::{Result, ServiceError},
+};
+use base64::{Engine, engine::general_purpose};
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, Order, QueryFilter, QueryOrder,
+    QuerySelect, Select, Value,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageQuery {
+    pub cursor: Option<String>,
+
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+// Value of the sort column for the last item of a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CursorValue {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    DateTime(NaiveDateTime),
+}
+
+impl From<CursorValue> for Value {
+    fn from(value: CursorValue) -> Self {
+        match value {
+            CursorValue::Int(val) => val.into(),
+            CursorValue::Float(val) => val.into(),
+            CursorValue::Str(val) => val.into(),
+            CursorValue::DateTime(val) => val.into(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Cursor {
+    value: CursorValue,
+    id: i32,
+}
+
+impl Cursor {
+    fn encode(&self) -> Result<String> {
+        let json = serde_json::to_vec(self)?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(json))
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let invalid = || ServiceError::DtoError("Invalid cursor".to_string());
+        let json = general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .map_err(|_| invalid())?;
+        serde_json::from_slice(&json).map_err(|_| invalid())
+    }
+}
+
+// For keyset pagination over computed values, which `CursorPaginator` can't sort by
+pub fn encode_cursor(value: CursorValue, id: i32) -> Result<String> {
+    Cursor { value, id }.encode()
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<(CursorValue, i32)> {
+    let cursor = Cursor::decode(cursor)?;
+    Ok((cursor.value, cursor.id))
+}
+
+pub fn page_limit(page_query: &PageQuery) -> u64 {
+    page_query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE)
+}
+
+/**
+ * Keyset pagination over `sort_column`, with `id_column` as tie breaker so pages stay stable
+ * while rows are inserted. The cursor is opaque to clients and only valid for the same sort.
+ */
+pub struct CursorPaginator<E: EntityTrait> {
+    pub sort_column: E::Column,
+    pub id_column: E::Column,
+    pub direction: SortDirection,
+    pub sort_value: fn(&E::Model) -> CursorValue,
+    pub id_value: fn(&E::Model) -> i32,
+}
+
+impl<E: EntityTrait> CursorPaginator<E> {
+    pub async fn fetch<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        select: Select<E>,
+        page_query: &PageQuery,
+    ) -> Result<Page<E::Model>> {
+        let limit = page_limit(page_query);
+
+        let mut select = select;
+        if let Some(cursor) = &page_query.cursor {
+            let cursor = Cursor::decode(cursor)?;
+            let value: Value = cursor.value.into();
+
+            let (after_sort, after_id) = match self.direction {
+                SortDirection::Asc => (
+                    self.sort_column.gt(value.clone()),
+                    self.id_column.gt(cursor.id),
+                ),
+                SortDirection::Desc => (
+                    self.sort_column.lt(value.clone()),
+                    self.id_column.lt(cursor.id),
+                ),
+            };
+
+            select = select.filter(
+                Condition::any().add(after_sort).add(
+                    Condition::all()
+                        .add(self.sort_column.eq(value))
+                        .add(after_id),
+                ),
+            );
+        }
+
+        let order = match self.direction {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        };
+
+        // Fetch one extra row to know if there is a next page
+        let mut items = select
+            .order_by(self.sort_column, order.clone())
+            .order_by(self.id_column, order)
+            .limit(limit + 1)
+            .all(db)
+            .await?;
+
+        let next_cursor = if items.len() as u64 > limit {
+            items.truncate(limit as usize);
+            items
+                .last()
+                .map(|last| {
+                    Cursor {
+                        value: (self.sort_value)(last),
+                        id: (self.id_value)(last),
+                    }
+                    .encode()
+                })
+                .transpose()?
+        } else {
+            None
+        };
+
+        Ok(Page { items, next_cursor })
+    }
+}
+
+/**
+ * Escapes LIKE wildcards in user input and wraps it for a contains match.
+ */
+pub fn like_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::services::pagination::{Cursor, CursorValue, like_pattern};
+    use anyhow::Result;
+
+    #[test]
+    fn test_cursor_round_trip() -> Result<()> {
+        let cursor = Cursor {
+            value: CursorValue::Str("Coffee & Co".to_string()),
+            id: 42,
+        };
+        let encoded = cursor.encode()?;
+        assert_eq!(Cursor::decode(&encoded)?, cursor);
+        assert!(Cursor::decode("not a cursor").is_err());
+
+        assert_eq!(like_pattern("50%_off"), "%50\\%\\_off%");
+        Ok(())
+    }
+}
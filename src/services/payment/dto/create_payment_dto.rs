@@ -1,4 +1,4 @@
-use crate::db::entity::sea_orm_active_enums::PaymentCategory;
+use crate::db::entity::sea_orm_active_enums::{PaymentCategory, SplitType};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 
@@ -12,4 +12,25 @@ pub struct CreatePaymentDto {
     pub category: PaymentCategory,
 
     pub amount: u64,
+
+    // Hold funds in escrow until the buyer confirms or the release window passes
+    #[serde(default)]
+    pub is_escrow: bool,
+
+    #[serde(default)]
+    pub splits: Vec<CreatePaymentSplitDto>,
+
+    // One of the merchant's stores the payment is taken at
+    pub store_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePaymentSplitDto {
+    pub wallet_address: String,
+
+    pub split_type: SplitType,
+
+    // Basis points of the amount after fee for percentage splits, USDC for fixed splits
+    pub value: u64,
 }
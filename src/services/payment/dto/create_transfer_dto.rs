@@ -7,4 +7,6 @@ pub struct CreateTransferDto {
     pub sender_address: String,
 
     pub payment_id: Uuid,
+
+    pub promo_code: Option<String>,
 }
@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::{
+    db::entity::sea_orm_active_enums::{PaymentCategory, TransferStatus},
+    services::pagination::SortDirection,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaymentSortBy {
+    #[default]
+    CreatedAt,
+    Amount,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPaymentsDto {
+    // Payments with at least one transfer in this status
+    pub status: Option<TransferStatus>,
+
+    pub from: Option<NaiveDateTime>,
+
+    pub to: Option<NaiveDateTime>,
+
+    pub min_amount: Option<u64>,
+
+    pub max_amount: Option<u64>,
+
+    pub category: Option<PaymentCategory>,
+
+    pub store_id: Option<i32>,
+
+    // Matched against title and description
+    pub search: Option<String>,
+
+    #[serde(default)]
+    pub sort_by: PaymentSortBy,
+
+    #[serde(default)]
+    pub direction: SortDirection,
+}
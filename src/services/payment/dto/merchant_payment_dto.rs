@@ -0,0 +1,82 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::entity::{
+    payment::Model as PaymentModel,
+    sea_orm_active_enums::{PaymentCategory, TransferStatus},
+    transfer::Model as TransferModel,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferDto {
+    pub id: i32,
+
+    pub sender_wallet_address: String,
+
+    pub signature: Option<String>,
+
+    pub status: TransferStatus,
+
+    pub amount: u64,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<TransferModel> for TransferDto {
+    fn from(value: TransferModel) -> Self {
+        TransferDto {
+            id: value.id,
+            sender_wallet_address: value.sender_wallet_address,
+            signature: value.signature,
+            status: value.status,
+            amount: value.amount as u64, // base units after discount, never negative
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchantPaymentDto {
+    pub id: i32,
+
+    pub public_id: Uuid,
+
+    pub title: String,
+
+    pub description: String,
+
+    pub category: PaymentCategory,
+
+    pub created_at: NaiveDateTime,
+
+    pub amount: u64,
+
+    pub is_escrow: bool,
+
+    pub created_by_id: Option<i32>,
+
+    pub store_id: Option<i32>,
+
+    pub transfers: Vec<TransferDto>,
+}
+
+impl From<(PaymentModel, Vec<TransferModel>)> for MerchantPaymentDto {
+    fn from((payment, transfers): (PaymentModel, Vec<TransferModel>)) -> Self {
+        MerchantPaymentDto {
+            id: payment.id,
+            public_id: payment.public_id,
+            title: payment.title,
+            description: payment.description,
+            category: payment.category,
+            created_at: payment.created_at,
+            amount: payment.amount as u64, // created from the dto's u64
+            is_escrow: payment.is_escrow,
+            created_by_id: payment.created_by_id,
+            store_id: payment.store_id,
+            transfers: transfers.into_iter().map(|val| val.into()).collect(),
+        }
+    }
+}
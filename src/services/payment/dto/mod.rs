@@ -1,4 +1,6 @@
 pub mod create_payment_dto;
 pub mod create_transfer_dto;
+pub mod list_payments_dto;
+pub mod merchant_payment_dto;
 pub mod payment_dto;
 pub mod submit_transfer_dto;
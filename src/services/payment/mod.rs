@@ -6,29 +6,42 @@ use super::{
     error::{Result, ServiceError},
 };
 use crate::{
-    constants::{BASE_USDC, USDC_MINT},
+    constants::{
+        BASE_USDC, BASIS_POINTS, MAX_PAYMENT_SPLITS, TREASURY_PUBKEY,
+        UNVERIFIED_MERCHANT_DAILY_VOLUME, UNVERIFIED_MERCHANT_MAX_TRANSFER_AMOUNT,
+    },
     ctx::Ctx,
     db::entity::{
+        merchant,
         payment::{self, Column},
-        prelude::Payment,
-        sea_orm_active_enums::TransferStatus,
+        payment_split::{self, Model as PaymentSplitModel},
+        prelude::{Merchant, Payment, PaymentSplit},
+        sea_orm_active_enums::{SplitType, TransferStatus},
         transfer::{self, ActiveModel as TransferModel, Entity as Transfer},
         user,
     },
     services::{
         append_timestamp,
         error::{EntityId, MathErrorType, Web3ErrorType},
+        escrow::EscrowService,
         indexer::Indexer,
+        ledger::LedgerService,
+        pagination::{CursorPaginator, CursorValue, Page, PageQuery, like_pattern},
         payment::dto::{
-            create_payment_dto::CreatePaymentDto,
+            create_payment_dto::{CreatePaymentDto, CreatePaymentSplitDto},
             create_transfer_dto::CreateTransferDto,
+            list_payments_dto::{ListPaymentsDto, PaymentSortBy},
             payment_dto::{PaymentDto, PaymentInput},
             submit_transfer_dto::SubmitTransferDto,
         },
+        promotion::PromotionService,
+        settlement_wallet::SettlementWalletService,
+        store::StoreService,
         user::UserService,
         web3::{
-            deserialize_transaction, get_fee_faucet_pubkey,
-            get_reference_from_transfer_transaction, verify_transaction_signature,
+            TransferLeg, calculate_transfer_fee, deserialize_transaction, get_escrow_pubkey,
+            get_fee_faucet_pubkey, get_reference_from_transfer_transaction,
+            verify_transaction_signature,
         },
     },
 };
@@ -38,19 +51,25 @@ use base64::Engine;
 use convert_case::{Case, Casing};
 use sea_orm::{
     ActiveValue::Set,
-    ColumnTrait, EntityName, EntityTrait, QueryFilter, QuerySelect, RelationTrait,
-    TransactionTrait,
+    ColumnTrait, Condition, ConnectionTrait, DbBackend, EntityName, EntityTrait, FromQueryResult,
+    LoaderTrait, ModelTrait, QueryFilter, QuerySelect, RelationTrait, Statement, TransactionTrait,
     prelude::{DateTimeWithTimeZone, Expr},
-    sea_query::ExprTrait,
+    sea_query::{ExprTrait, Query, extension::postgres::PgExpr},
     sqlx::types::chrono,
 };
 use serde::Serialize;
 use solana_client::rpc_client::SerializableTransaction;
 use solana_keypair::{Keypair, signable::Signable};
 use solana_signer::Signer;
-use std::sync::Arc;
+use spl_token::solana_program::pubkey::Pubkey;
+use std::{str::FromStr, sync::Arc};
 use uuid::Uuid;
 
+#[derive(Debug, FromQueryResult)]
+struct Volume {
+    volume: i64,
+}
+
 pub struct PaymentService;
 
 impl PaymentService {
@@ -76,14 +95,118 @@ impl PaymentService {
         })
     }
 
+    // Merchant's payments with their transfers, staff see every payment of their merchant
+    pub async fn find_all(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        list_payments_dto: ListPaymentsDto,
+        page_query: PageQuery,
+    ) -> Result<Page<(PaymentInput, Vec<transfer::Model>)>> {
+        let to_amount = |val: u64| {
+            i64::try_from(val)
+                .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))
+        };
+
+        let merchant = UserService::find_ctx_merchant(state.clone(), &ctx).await?;
+        let mut select = Payment::find()
+            .filter(payment::Column::MerchantId.eq(merchant.id))
+            .filter(payment::Column::Livemode.eq(ctx.livemode()));
+
+        if let Some(status) = list_payments_dto.status {
+            select = select.filter(
+                payment::Column::Id.in_subquery(
+                    Query::select()
+                        .column(transfer::Column::PaymentId)
+                        .from(Transfer)
+                        .and_where(transfer::Column::Status.eq(status))
+                        .to_owned(),
+                ),
+            );
+        }
+        if let Some(from) = list_payments_dto.from {
+            select = select.filter(payment::Column::CreatedAt.gte(from));
+        }
+        if let Some(to) = list_payments_dto.to {
+            select = select.filter(payment::Column::CreatedAt.lt(to));
+        }
+        if let Some(min_amount) = list_payments_dto.min_amount {
+            select = select.filter(payment::Column::Amount.gte(to_amount(min_amount)?));
+        }
+        if let Some(max_amount) = list_payments_dto.max_amount {
+            select = select.filter(payment::Column::Amount.lte(to_amount(max_amount)?));
+        }
+        if let Some(category) = list_payments_dto.category {
+            select = select.filter(payment::Column::Category.eq(category));
+        }
+        if let Some(store_id) = list_payments_dto.store_id {
+            select = select.filter(payment::Column::StoreId.eq(store_id));
+        }
+        if let Some(search) = list_payments_dto
+            .search
+            .filter(|val| !val.trim().is_empty())
+        {
+            let pattern = like_pattern(search.trim());
+            select = select.filter(
+                Condition::any()
+                    .add(Expr::col(payment::Column::Title).ilike(&pattern))
+                    .add(Expr::col(payment::Column::Description).ilike(&pattern)),
+            );
+        }
+
+        let paginator = match list_payments_dto.sort_by {
+            PaymentSortBy::CreatedAt => CursorPaginator::<Payment> {
+                sort_column: payment::Column::CreatedAt,
+                id_column: payment::Column::Id,
+                direction: list_payments_dto.direction,
+                sort_value: |val| CursorValue::DateTime(val.created_at),
+                id_value: |val| val.id,
+            },
+            PaymentSortBy::Amount => CursorPaginator::<Payment> {
+                sort_column: payment::Column::Amount,
+                id_column: payment::Column::Id,
+                direction: list_payments_dto.direction,
+                sort_value: |val| CursorValue::Int(val.amount),
+                id_value: |val| val.id,
+            },
+        };
+
+        let page = paginator.fetch(state.db(), select, &page_query).await?;
+        let transfers = page.items.load_many(Transfer, state.db()).await?;
+        let mut transfers = transfers.into_iter();
+
+        Ok(page.map(|payment| (payment, transfers.next().unwrap_or_default())))
+    }
+
     pub async fn create(
         state: Arc<AppState>,
-        user_id: i32,
+        ctx: Ctx,
         create_payment_dto: CreatePaymentDto,
     ) -> Result<PaymentInput> {
+        // Escrow releases are paid from the live escrow wallet
+        let livemode = ctx.livemode();
+        if create_payment_dto.is_escrow && !livemode {
+            return Err(ServiceError::DtoError(
+                "Escrow payments can't be created in test mode".to_string(),
+            ));
+        }
+
         let amount = i64::try_from(create_payment_dto.amount)
             .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
 
+        let splits = create_payment_dto.splits;
+        validate_splits(create_payment_dto.amount, &splits)?;
+
+        // Staff create payments on behalf of their merchant, which is paid out to its owner
+        let merchant = UserService::find_ctx_merchant(state.clone(), &ctx).await?;
+        let store_id = match create_payment_dto.store_id {
+            Some(store_id) => Some(
+                StoreService::find_merchant_store(state.db(), merchant.id, store_id)
+                    .await?
+                    .id,
+            ),
+            None => None,
+        };
+
         let data = payment::ActiveModel {
             title: Set(create_payment_dto.title),
             description: Set(create_payment_dto.description),
@@ -91,13 +214,39 @@ impl PaymentService {
             created_at: Set(Utc::now().naive_utc()),
             category: Set(create_payment_dto.category),
             public_id: Set(Uuid::new_v4()),
-            user_id: Set(user_id),
+            user_id: Set(merchant.user_id),
+            is_escrow: Set(create_payment_dto.is_escrow),
+            livemode: Set(livemode),
+            merchant_id: Set(Some(merchant.id)),
+            created_by_id: Set(Some(ctx.user_id)),
+            store_id: Set(store_id),
             ..Default::default()
         };
 
-        let payment = Payment::insert(data)
-            .exec_with_returning(state.db())
-            .await?;
+        let txn = state.db().begin().await?;
+        let payment = Payment::insert(data).exec_with_returning(&txn).await?;
+
+        if !splits.is_empty() {
+            let splits_data = splits
+                .into_iter()
+                .map(|split| -> Result<payment_split::ActiveModel> {
+                    let value = i64::try_from(split.value)
+                        .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+                    Ok(payment_split::ActiveModel {
+                        payment_id: Set(payment.id),
+                        wallet_address: Set(split.wallet_address),
+                        split_type: Set(split.split_type),
+                        value: Set(value),
+                        ..Default::default()
+                    })
+                })
+                .collect::<Result<Vec<payment_split::ActiveModel>>>()?;
+
+            PaymentSplit::insert_many(splits_data).exec(&txn).await?;
+        }
+
+        txn.commit().await?;
         Ok(payment)
     }
 
@@ -110,22 +259,15 @@ impl PaymentService {
 
         // Find the payment
         let payment = Payment::find()
-            .filter(Column::PublicId.eq(payment_id.clone()))
-            .find_also_related(user::Entity)
+            .filter(Column::PublicId.eq(payment_id))
             .one(state.db())
             .await?;
 
-        let (payment, user) = payment.ok_or(ServiceError::EntityNotFound {
+        let payment = payment.ok_or(ServiceError::EntityNotFound {
             entity: Self::TABLE,
             id: EntityId::Str(payment_id.to_string()),
         })?;
 
-        let user = user.ok_or(ServiceError::UserNotFound)?;
-        let receiver_address = user.wallet_address.ok_or(ServiceError::EntityNotFound {
-            entity: "UserWallet",
-            id: EntityId::Int(user.id),
-        })?;
-
         let reference = Keypair::new();
         let reference = reference.pubkey();
         let reference_key = reference.to_string();
@@ -140,30 +282,86 @@ impl PaymentService {
             .checked_mul(BASE_USDC)
             .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
 
+        // Network lookups happen before any row is locked
+        let receiver_address = if payment.is_escrow {
+            get_escrow_pubkey()?.to_string()
+        } else {
+            Self::find_receiver_wallet(state.clone(), &payment).await?
+        };
+        let latest_blockhash = state
+            .web3_for(payment.livemode)
+            .get_latest_blockhash()
+            .await?;
+
+        // The promotion stays locked until the redemption is reserved with the transfer
+        let txn = state.db().begin().await?;
+        let promotion = match create_transfer_dto.promo_code {
+            Some(code) => {
+                Some(PromotionService::apply(&txn, &payment, &code, &sender_address, amount).await?)
+            }
+            None => None,
+        };
+
+        let amount = match &promotion {
+            Some((_, discount)) => amount
+                .checked_sub(*discount)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?,
+            None => amount,
+        };
+
+        if amount == 0 {
+            return Err(ServiceError::DtoError(
+                "Amount after discount must be greater than zero".to_string(),
+            ));
+        }
+
+        Self::check_unverified_limits(&txn, &payment, amount).await?;
+
+        // Splits were checked against the full amount, a discount can leave too little for fixed splits
+        let splits = payment.find_related(PaymentSplit).all(&txn).await?;
+        let (_, amount_after_fee) = calculate_transfer_fee(amount)?;
+        if promotion.is_some() && get_split_amounts(amount_after_fee, &splits).is_err() {
+            return Err(ServiceError::DtoError(
+                "Promo code discount leaves too little to cover the payment's splits".to_string(),
+            ));
+        }
+
+        // Escrow payments are split between recipients on release
+        let leg_splits = if payment.is_escrow { &[][..] } else { &splits };
+        let legs = get_transfer_legs(amount, &receiver_address, leg_splits)?;
+
         let transfer_transaction = state
-            .web3
+            .web3_for(payment.livemode)
             .create_transfer_transaction(
                 &sender_address,
-                &receiver_address,
-                amount,
-                &USDC_MINT.to_string(),
+                &legs,
+                &state.usdc_mint_for(payment.livemode).to_string(),
                 reference,
-            )
-            .await?;
+                latest_blockhash,
+            )?;
 
         // save the transfer in db
         let transfer_data = TransferModel {
             payment_id: Set(payment.id),
             reference_key: Set(reference_key.clone()),
             status: Set(TransferStatus::Pending),
-            sender_wallet_address: Set(sender_address),
+            sender_wallet_address: Set(sender_address.clone()),
+            amount: Set(i64::try_from(amount)
+                .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?),
             ..Default::default()
         };
 
-        transfer::Entity::insert(transfer_data)
-            .exec_with_returning(state.db())
+        let transfer = transfer::Entity::insert(transfer_data)
+            .exec_with_returning(&txn)
             .await?;
 
+        if let Some((promotion, discount)) = promotion {
+            PromotionService::reserve(&txn, &promotion, transfer.id, sender_address, discount)
+                .await?;
+        }
+
+        txn.commit().await?;
+
         let serialized_transaction = bincode::serialize(&transfer_transaction)?;
         let base64_transaction = base64::prelude::BASE64_STANDARD.encode(serialized_transaction);
 
@@ -211,23 +409,112 @@ impl PaymentService {
 
         verify_transaction_signature(&transaction, &fee_faucet)?;
         let signature = state
-            .web3
+            .web3_for(payment.livemode)
             .send_and_confirm_transaction(&transaction)
             .await?;
 
+        let txn = state.db().begin().await?;
         Transfer::update_many()
-            .col_expr(transfer::Column::Signature, Expr::value(signature))
+            .col_expr(transfer::Column::Signature, Expr::value(signature.clone()))
             .col_expr(
                 transfer::Column::Status,
                 Expr::value(TransferStatus::Completed).as_enum("transfer_status"),
             )
             .filter(transfer::Column::ReferenceKey.eq(&reference))
-            .exec(state.db())
+            .exec(&txn)
             .await?;
 
+        let transfer = transfer::Model {
+            signature: Some(signature),
+            status: TransferStatus::Completed,
+            ..transfer
+        };
+        LedgerService::post_transfer(&txn, &payment, &transfer).await?;
+        PromotionService::redeem(&txn, transfer.id).await?;
+
+        if payment.is_escrow {
+            EscrowService::hold(&txn, &payment, &transfer).await?;
+        }
+
+        txn.commit().await?;
         Ok(())
     }
 
+    /**
+     * Unverified merchants are capped per transfer and on their volume of the last 24 hours, pending
+     * transfers included. Their merchant row stays locked until the transfer is saved.
+     */
+    async fn check_unverified_limits<C: ConnectionTrait>(
+        db: &C,
+        payment: &PaymentInput,
+        amount: u64,
+    ) -> Result<()> {
+        if !payment.livemode {
+            return Ok(());
+        }
+
+        let merchant = Merchant::find()
+            .filter(merchant::Column::UserId.eq(payment.user_id))
+            .one(db)
+            .await?;
+        if merchant.is_some_and(|merchant| merchant.is_verified) {
+            return Ok(());
+        }
+        // Only transfers to unverified merchants wait on each other for the volume check
+        Merchant::find()
+            .filter(merchant::Column::UserId.eq(payment.user_id))
+            .lock_exclusive()
+            .one(db)
+            .await?;
+
+        let to_base_units = |val: u64| {
+            val.checked_mul(BASE_USDC)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))
+        };
+
+        if amount > to_base_units(UNVERIFIED_MERCHANT_MAX_TRANSFER_AMOUNT)? {
+            return Err(ServiceError::Custom(format!(
+                "Unverified merchants can't take transfers above {} USDC",
+                UNVERIFIED_MERCHANT_MAX_TRANSFER_AMOUNT
+            )));
+        }
+
+        let since = Utc::now().naive_utc() - ::chrono::Duration::days(1);
+        let volume = Volume::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            "SELECT coalesce(sum(t.amount), 0)::bigint AS volume FROM transfer t \
+             JOIN payment p ON p.id = t.payment_id \
+             WHERE p.user_id = $1 AND p.livemode AND t.status <> 'rejected' AND t.created_at >= $2",
+            [payment.user_id.into(), since.into()],
+        ))
+        .one(db)
+        .await?
+        .map(|val| val.volume)
+        .unwrap_or(0);
+
+        let volume = u64::try_from(volume)
+            .ok()
+            .and_then(|volume| volume.checked_add(amount))
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+        if volume > to_base_units(UNVERIFIED_MERCHANT_DAILY_VOLUME)? {
+            return Err(ServiceError::Custom(format!(
+                "Unverified merchants can't take more than {} USDC a day",
+                UNVERIFIED_MERCHANT_DAILY_VOLUME
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Merchant's default settlement wallet, receiving its share of a payment
+    pub(crate) async fn find_receiver_wallet(
+        state: Arc<AppState>,
+        payment: &PaymentInput,
+    ) -> Result<String> {
+        let mint = state.usdc_mint_for(payment.livemode).to_string();
+        SettlementWalletService::find_default_address(state, payment.user_id, &mint).await
+    }
+
     pub async fn public_create_transfer(state: AppState, payment_id: i32) -> Result<String> {
         // TODO: Use timer wheel algorithm for indexing
         // let mint = USDC_MINT;
@@ -235,9 +522,8 @@ impl PaymentService {
         //     let result = Indexer::poll_payment(
         //         state,
         //         reference_key,
-        //         receiver_address,
+        //         legs,
         //         mint.to_string(),
-        //         amount,
         //     )
         //     .await;
 
@@ -248,3 +534,201 @@ impl PaymentService {
         todo!()
     }
 }
+
+fn validate_splits(amount: u64, splits: &[CreatePaymentSplitDto]) -> Result<()> {
+    if splits.len() > MAX_PAYMENT_SPLITS {
+        return Err(ServiceError::DtoError(format!(
+            "A payment can have at most {} splits",
+            MAX_PAYMENT_SPLITS
+        )));
+    }
+
+    let mut total_percentage: u64 = 0;
+    let mut total_fixed: u64 = 0;
+    for split in splits {
+        Pubkey::from_str(&split.wallet_address)
+            .map_err(|_| ServiceError::DtoError("Invalid split wallet address".to_string()))?;
+
+        if split.value == 0 {
+            return Err(ServiceError::DtoError(
+                "Split value must be greater than zero".to_string(),
+            ));
+        }
+
+        let total = match split.split_type {
+            SplitType::Percentage => &mut total_percentage,
+            SplitType::Fixed => &mut total_fixed,
+        };
+        *total = total
+            .checked_add(split.value)
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+    }
+
+    if total_percentage > BASIS_POINTS {
+        return Err(ServiceError::DtoError(
+            "Split percentages exceed 100%".to_string(),
+        ));
+    }
+
+    let amount = amount
+        .checked_mul(BASE_USDC)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+    let (_, amount_after_fee) = calculate_transfer_fee(amount)?;
+
+    let fixed_amount = total_fixed
+        .checked_mul(BASE_USDC)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+    let percentage_amount = amount_after_fee
+        .checked_mul(total_percentage)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?
+        / BASIS_POINTS;
+
+    let split_amount = fixed_amount
+        .checked_add(percentage_amount)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+    if split_amount > amount_after_fee {
+        return Err(ServiceError::DtoError(
+            "Splits exceed the payment amount".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/**
+ * Splits a transfer amount (in token base units) into the legs of the transfer transaction:
+ * treasury fee first, then one leg per split recipient, and the remainder to the receiver.
+ * Percentage splits are taken from the amount after fee and rounded down.
+ */
+pub(crate) fn get_transfer_legs(
+    amount: u64,
+    receiver_wallet: &str,
+    splits: &[PaymentSplitModel],
+) -> Result<Vec<TransferLeg>> {
+    let (fee, amount_after_fee) = calculate_transfer_fee(amount)?;
+
+    let mut legs = vec![TransferLeg {
+        receiver_wallet: TREASURY_PUBKEY.to_string(),
+        amount: fee,
+    }];
+    legs.extend(get_split_legs(amount_after_fee, receiver_wallet, splits)?);
+
+    legs.retain(|leg| leg.amount > 0);
+    Ok(legs)
+}
+
+// Splits an amount after fee between split recipients, the remainder goes to the receiver
+pub(crate) fn get_split_legs(
+    amount_after_fee: u64,
+    receiver_wallet: &str,
+    splits: &[PaymentSplitModel],
+) -> Result<Vec<TransferLeg>> {
+    let (mut legs, remaining) = get_split_amounts(amount_after_fee, splits)?;
+    legs.push(TransferLeg {
+        receiver_wallet: receiver_wallet.to_string(),
+        amount: remaining,
+    });
+
+    legs.retain(|leg| leg.amount > 0);
+    Ok(legs)
+}
+
+// Legs of the split recipients and the remainder left for the receiver
+pub(crate) fn get_split_amounts(
+    amount_after_fee: u64,
+    splits: &[PaymentSplitModel],
+) -> Result<(Vec<TransferLeg>, u64)> {
+    let mut legs = Vec::with_capacity(splits.len() + 1);
+    let mut remaining = amount_after_fee;
+    for split in splits {
+        let value = u64::try_from(split.value)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        let split_amount = match split.split_type {
+            SplitType::Percentage => {
+                amount_after_fee
+                    .checked_mul(value)
+                    .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?
+                    / BASIS_POINTS
+            }
+            SplitType::Fixed => value
+                .checked_mul(BASE_USDC)
+                .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?,
+        };
+
+        remaining = remaining
+            .checked_sub(split_amount)
+            .ok_or(ServiceError::Custom(
+                "Splits exceed the payment amount".to_string(),
+            ))?;
+
+        legs.push(TransferLeg {
+            receiver_wallet: split.wallet_address.clone(),
+            amount: split_amount,
+        });
+    }
+
+    Ok((legs, remaining))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::{BASE_USDC, TREASURY_PUBKEY},
+        db::entity::{payment_split::Model as PaymentSplitModel, sea_orm_active_enums::SplitType},
+        services::{payment::get_transfer_legs, web3::TransferLeg},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_get_transfer_legs() -> Result<()> {
+        let merchant = "merchant".to_string();
+        let splits = vec![
+            PaymentSplitModel {
+                id: 1,
+                payment_id: 1,
+                wallet_address: "venue".to_string(),
+                split_type: SplitType::Percentage,
+                value: 2_500,
+            },
+            PaymentSplitModel {
+                id: 2,
+                payment_id: 1,
+                wallet_address: "performer".to_string(),
+                split_type: SplitType::Fixed,
+                value: 10,
+            },
+        ];
+
+        let legs = get_transfer_legs(100 * BASE_USDC, &merchant, &splits)?;
+        assert_eq!(
+            legs,
+            vec![
+                TransferLeg {
+                    receiver_wallet: TREASURY_PUBKEY.to_string(),
+                    amount: BASE_USDC,
+                },
+                TransferLeg {
+                    receiver_wallet: "venue".to_string(),
+                    amount: 24_750_000,
+                },
+                TransferLeg {
+                    receiver_wallet: "performer".to_string(),
+                    amount: 10 * BASE_USDC,
+                },
+                TransferLeg {
+                    receiver_wallet: merchant.clone(),
+                    amount: 64_250_000,
+                },
+            ]
+        );
+
+        let total: u64 = legs.iter().map(|leg| leg.amount).sum();
+        assert_eq!(total, 100 * BASE_USDC);
+
+        // Fixed splits larger than the amount after fee are rejected
+        assert!(get_transfer_legs(10 * BASE_USDC, &merchant, &splits).is_err());
+
+        Ok(())
+    }
+}
@@ -5,10 +5,12 @@ use crate::{
     services::{
         AppState,
         error::Result,
+        pagination::{Page, PageQuery},
         payment::{
             PaymentService,
             dto::{
                 create_payment_dto::CreatePaymentDto, create_transfer_dto::CreateTransferDto,
+                list_payments_dto::ListPaymentsDto, merchant_payment_dto::MerchantPaymentDto,
                 payment_dto::PaymentDto, submit_transfer_dto::SubmitTransferDto,
             },
         },
@@ -16,7 +18,7 @@ use crate::{
 };
 use axum::{
     Json,
-    extract::{Multipart, Path, State},
+    extract::{Multipart, Path, Query, State},
 };
 use serde_json::json;
 use uuid::Uuid;
@@ -37,12 +39,22 @@ pub async fn find_one(
     Ok(Json(payment.into()))
 }
 
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Query(list_payments_dto): Query<ListPaymentsDto>,
+    Query(page_query): Query<PageQuery>,
+) -> Result<Json<Page<MerchantPaymentDto>>> {
+    let payments = PaymentService::find_all(state, ctx, list_payments_dto, page_query).await?;
+    Ok(Json(payments.map(|val| val.into())))
+}
+
 pub async fn create(
     State(state): State<Arc<AppState>>,
     ctx: Ctx,
     Json(create_payment_dto): Json<CreatePaymentDto>,
 ) -> Result<Json<PaymentDto>> {
-    let payment_input = PaymentService::create(state, ctx.user_id, create_payment_dto).await?;
+    let payment_input = PaymentService::create(state, ctx, create_payment_dto).await?;
     Ok(Json(payment_input.into()))
 }
 
@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::db::entity::sea_orm_active_enums::DiscountType;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePromotionDto {
+    pub code: String,
+
+    pub discount_type: DiscountType,
+
+    // Basis points for percentage, whole USDC for fixed
+    pub value: u64,
+
+    pub starts_at: Option<NaiveDateTime>,
+
+    pub ends_at: Option<NaiveDateTime>,
+
+    pub max_redemptions: Option<u32>,
+
+    pub per_wallet_limit: Option<u32>,
+}
@@ -0,0 +1,2 @@
+pub mod create_promotion_dto;
+pub mod promotion_dto;
@@ -0,0 +1,48 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{promotion::Model as PromotionModel, sea_orm_active_enums::DiscountType};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromotionDto {
+    pub id: i32,
+
+    pub code: String,
+
+    pub discount_type: DiscountType,
+
+    pub value: u64,
+
+    pub starts_at: NaiveDateTime,
+
+    pub ends_at: Option<NaiveDateTime>,
+
+    pub max_redemptions: Option<i32>,
+
+    pub redemption_count: i32,
+
+    pub per_wallet_limit: Option<i32>,
+
+    pub is_active: bool,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<PromotionModel> for PromotionDto {
+    fn from(value: PromotionModel) -> Self {
+        PromotionDto {
+            id: value.id,
+            code: value.code,
+            discount_type: value.discount_type,
+            value: value.value as u64, // validated to be positive on create
+            starts_at: value.starts_at,
+            ends_at: value.ends_at,
+            max_redemptions: value.max_redemptions,
+            redemption_count: value.redemption_count,
+            per_wallet_limit: value.per_wallet_limit,
+            is_active: value.is_active,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,362 @@
+pub mod dto;
+pub mod promotion_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    constants::{BASE_USDC, BASIS_POINTS, PROMOTION_RESERVATION_SECS},
+    ctx::Ctx,
+    db::entity::{
+        merchant,
+        payment::Model as PaymentModel,
+        prelude::{Merchant, Promotion, PromotionRedemption, Transfer},
+        promotion::{self, Model as PromotionModel},
+        promotion_redemption,
+        sea_orm_active_enums::{DiscountType, TransferStatus},
+        transfer,
+    },
+    services::{
+        AppState,
+        error::{EntityId, MathErrorType, Result, ServiceError},
+        promotion::dto::create_promotion_dto::CreatePromotionDto,
+        user::UserService,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, prelude::Expr, sea_query::ExprTrait,
+};
+
+pub struct PromotionService;
+
+impl PromotionService {
+    const PROMOTION: &'static str = "Promotion";
+
+    pub async fn create(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_promotion_dto: CreatePromotionDto,
+    ) -> Result<PromotionModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let code = normalize_code(&create_promotion_dto.code);
+        if code.len() < 3
+            || code.len() > 32
+            || !code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ServiceError::DtoError(
+                "Promo code must be 3-32 letters, digits, '-' or '_'".to_string(),
+            ));
+        }
+
+        let value = create_promotion_dto.value;
+        if value == 0 {
+            return Err(ServiceError::DtoError(
+                "Discount value must be greater than zero".to_string(),
+            ));
+        }
+        if create_promotion_dto.discount_type == DiscountType::Percentage && value > BASIS_POINTS {
+            return Err(ServiceError::DtoError(
+                "Discount percentage exceeds 100%".to_string(),
+            ));
+        }
+        let value = i64::try_from(value)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        let now = Utc::now().naive_utc();
+        let starts_at = create_promotion_dto.starts_at.unwrap_or(now);
+        if let Some(ends_at) = create_promotion_dto.ends_at
+            && ends_at <= starts_at
+        {
+            return Err(ServiceError::DtoError(
+                "Promotion must end after it starts".to_string(),
+            ));
+        }
+
+        let to_limit = |val: Option<u32>| {
+            val.map(i32::try_from)
+                .transpose()
+                .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))
+        };
+
+        let existing = Promotion::find()
+            .filter(promotion::Column::MerchantId.eq(merchant.id))
+            .filter(promotion::Column::Code.eq(&code))
+            .one(state.db())
+            .await?;
+        if existing.is_some() {
+            return Err(ServiceError::DtoError(
+                "Promo code already exists".to_string(),
+            ));
+        }
+
+        let data = promotion::ActiveModel {
+            merchant_id: Set(merchant.id),
+            code: Set(code),
+            discount_type: Set(create_promotion_dto.discount_type),
+            value: Set(value),
+            starts_at: Set(starts_at),
+            ends_at: Set(create_promotion_dto.ends_at),
+            max_redemptions: Set(to_limit(create_promotion_dto.max_redemptions)?),
+            redemption_count: Set(0),
+            per_wallet_limit: Set(to_limit(create_promotion_dto.per_wallet_limit)?),
+            is_active: Set(true),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let promotion = Promotion::insert(data)
+            .exec_with_returning(state.db())
+            .await?;
+
+        Ok(promotion)
+    }
+
+    pub async fn find_all(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<PromotionModel>> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let promotions = Promotion::find()
+            .filter(promotion::Column::MerchantId.eq(merchant.id))
+            .order_by_desc(promotion::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(promotions)
+    }
+
+    pub async fn deactivate(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<PromotionModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let promotion = Promotion::find_by_id(id)
+            .filter(promotion::Column::MerchantId.eq(merchant.id))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::PROMOTION,
+                id: EntityId::Int(id),
+            })?;
+
+        let mut promotion: promotion::ActiveModel = promotion.into();
+        promotion.is_active = Set(false);
+        let promotion = Promotion::update(promotion).exec(state.db()).await?;
+
+        Ok(promotion)
+    }
+
+    /**
+     * Validates `code` against the payment's merchant and returns the promotion with the discount
+     * (in base units) for `amount`. The promotion row is locked, so `db` must be a transaction
+     * that also calls `reserve`, otherwise concurrent transfers could exceed the redemption limits.
+     */
+    pub async fn apply<C: ConnectionTrait>(
+        db: &C,
+        payment: &PaymentModel,
+        code: &str,
+        wallet_address: &str,
+        amount: u64,
+    ) -> Result<(PromotionModel, u64)> {
+        let invalid = || ServiceError::DtoError("Invalid promo code".to_string());
+
+        let merchant = Merchant::find()
+            .filter(merchant::Column::UserId.eq(payment.user_id))
+            .one(db)
+            .await?
+            .ok_or_else(invalid)?;
+
+        let promotion = Promotion::find()
+            .filter(promotion::Column::MerchantId.eq(merchant.id))
+            .filter(promotion::Column::Code.eq(normalize_code(code)))
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .ok_or_else(invalid)?;
+
+        let now = Utc::now().naive_utc();
+        if !promotion.is_active
+            || promotion.starts_at > now
+            || promotion.ends_at.is_some_and(|ends_at| ends_at <= now)
+        {
+            return Err(ServiceError::DtoError(
+                "Promo code is not active".to_string(),
+            ));
+        }
+
+        if let Some(max_redemptions) = promotion.max_redemptions {
+            let redemptions = Self::count_redemptions(db, promotion.id, None).await?;
+            if redemptions >= max_redemptions as u64 {
+                return Err(ServiceError::DtoError(
+                    "Promo code has been fully redeemed".to_string(),
+                ));
+            }
+        }
+
+        if let Some(per_wallet_limit) = promotion.per_wallet_limit {
+            let redemptions =
+                Self::count_redemptions(db, promotion.id, Some(wallet_address)).await?;
+            if redemptions >= per_wallet_limit as u64 {
+                return Err(ServiceError::DtoError(
+                    "Promo code redemption limit reached for this wallet".to_string(),
+                ));
+            }
+        }
+
+        let discount = calculate_discount(amount, &promotion)?;
+        Ok((promotion, discount))
+    }
+
+    /**
+     * Holds a redemption for a new transfer. It only counts towards the limits while the transfer
+     * is paid or can still be paid, so transfers that are rejected or never sent give it back.
+     */
+    pub async fn reserve<C: ConnectionTrait>(
+        db: &C,
+        promotion: &PromotionModel,
+        transfer_id: i32,
+        wallet_address: String,
+        discount: u64,
+    ) -> Result<()> {
+        let discount = i64::try_from(discount)
+            .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+        let data = promotion_redemption::ActiveModel {
+            promotion_id: Set(promotion.id),
+            transfer_id: Set(transfer_id),
+            wallet_address: Set(wallet_address),
+            discount_amount: Set(discount),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        PromotionRedemption::insert(data).exec(db).await?;
+
+        Ok(())
+    }
+
+    // Called when a transfer completes, `redemption_count` only counts paid redemptions
+    pub async fn redeem<C: ConnectionTrait>(db: &C, transfer_id: i32) -> Result<()> {
+        let redemption = PromotionRedemption::find()
+            .filter(promotion_redemption::Column::TransferId.eq(transfer_id))
+            .one(db)
+            .await?;
+        let Some(redemption) = redemption else {
+            return Ok(());
+        };
+
+        Promotion::update_many()
+            .col_expr(
+                promotion::Column::RedemptionCount,
+                Expr::col(promotion::Column::RedemptionCount).add(1),
+            )
+            .filter(promotion::Column::Id.eq(redemption.promotion_id))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    /**
+     * Redemptions whose transfer completed, or is pending and recent enough to still land.
+     * The wallet has to sign the transfer, so a paid redemption belongs to the wallet that paid.
+     */
+    async fn count_redemptions<C: ConnectionTrait>(
+        db: &C,
+        promotion_id: i32,
+        wallet_address: Option<&str>,
+    ) -> Result<u64> {
+        let pending_since =
+            Utc::now().naive_utc() - chrono::Duration::seconds(PROMOTION_RESERVATION_SECS);
+
+        let mut query = PromotionRedemption::find()
+            .inner_join(Transfer)
+            .filter(promotion_redemption::Column::PromotionId.eq(promotion_id))
+            .filter(
+                Condition::any()
+                    .add(transfer::Column::Status.eq(TransferStatus::Completed))
+                    .add(
+                        Condition::all()
+                            .add(transfer::Column::Status.eq(TransferStatus::Pending))
+                            .add(transfer::Column::CreatedAt.gte(pending_since)),
+                    ),
+            );
+        if let Some(wallet_address) = wallet_address {
+            query = query.filter(promotion_redemption::Column::WalletAddress.eq(wallet_address));
+        }
+
+        Ok(query.count(db).await?)
+    }
+}
+
+// Codes are matched case-insensitively
+fn normalize_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+// Discount in token base units, capped at the amount
+fn calculate_discount(amount: u64, promotion: &PromotionModel) -> Result<u64> {
+    let value = u64::try_from(promotion.value)
+        .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+    let discount = match promotion.discount_type {
+        DiscountType::Percentage => amount
+            .checked_mul(value)
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?
+            .checked_div(BASIS_POINTS)
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?,
+        DiscountType::Fixed => value
+            .checked_mul(BASE_USDC)
+            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?,
+    };
+
+    Ok(discount.min(amount))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::BASE_USDC,
+        db::entity::{promotion::Model as PromotionModel, sea_orm_active_enums::DiscountType},
+        services::promotion::calculate_discount,
+    };
+    use anyhow::Result;
+    use chrono::Utc;
+
+    fn promotion(discount_type: DiscountType, value: i64) -> PromotionModel {
+        PromotionModel {
+            id: 1,
+            merchant_id: 1,
+            code: "SUMMER".to_string(),
+            discount_type,
+            value,
+            starts_at: Utc::now().naive_utc(),
+            ends_at: None,
+            max_redemptions: None,
+            redemption_count: 0,
+            per_wallet_limit: None,
+            is_active: true,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_calculate_discount() -> Result<()> {
+        let amount = 50 * BASE_USDC;
+
+        // 12.5% in basis points
+        let percentage = promotion(DiscountType::Percentage, 1_250);
+        assert_eq!(calculate_discount(amount, &percentage)?, 6_250_000);
+        assert_eq!(calculate_discount(0, &percentage)?, 0);
+
+        // Fixed discounts are whole USDC, capped at the amount
+        let fixed = promotion(DiscountType::Fixed, 5);
+        assert_eq!(calculate_discount(amount, &fixed)?, 5 * BASE_USDC);
+        let fixed = promotion(DiscountType::Fixed, 80);
+        assert_eq!(calculate_discount(amount, &fixed)?, amount);
+
+        let full = promotion(DiscountType::Percentage, 10_000);
+        assert_eq!(calculate_discount(amount, &full)?, amount);
+        assert!(calculate_discount(amount, &promotion(DiscountType::Fixed, -1)).is_err());
+
+        Ok(())
+    }
+}
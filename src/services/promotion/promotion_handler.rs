@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        promotion::{
+            PromotionService,
+            dto::{create_promotion_dto::CreatePromotionDto, promotion_dto::PromotionDto},
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_promotion_dto): Json<CreatePromotionDto>,
+) -> Result<Json<PromotionDto>> {
+    let promotion = PromotionService::create(state, ctx, create_promotion_dto).await?;
+    Ok(Json(promotion.into()))
+}
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<PromotionDto>>> {
+    let promotions = PromotionService::find_all(state, ctx).await?;
+    let promotions = promotions
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<PromotionDto>>();
+
+    Ok(Json(promotions))
+}
+
+pub async fn deactivate(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<PromotionDto>> {
+    let promotion = PromotionService::deactivate(state, ctx, id).await?;
+    Ok(Json(promotion.into()))
+}
@@ -0,0 +1 @@
+pub mod reconciliation_report_dto;
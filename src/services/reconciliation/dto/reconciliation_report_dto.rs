@@ -0,0 +1,99 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{
+    reconciliation_issue::Model as ReconciliationIssueModel,
+    reconciliation_report::Model as ReconciliationReportModel,
+    sea_orm_active_enums::ReconciliationIssueKind,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReportDto {
+    pub id: i32,
+
+    pub window_start: NaiveDateTime,
+
+    pub window_end: NaiveDateTime,
+
+    pub accounts_scanned: i32,
+
+    pub signatures_scanned: i32,
+
+    pub issue_count: i32,
+
+    pub error: Option<String>,
+
+    pub created_at: NaiveDateTime,
+
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl From<ReconciliationReportModel> for ReconciliationReportDto {
+    fn from(value: ReconciliationReportModel) -> Self {
+        ReconciliationReportDto {
+            id: value.id,
+            window_start: value.window_start,
+            window_end: value.window_end,
+            accounts_scanned: value.accounts_scanned,
+            signatures_scanned: value.signatures_scanned,
+            issue_count: value.issue_count,
+            error: value.error,
+            created_at: value.created_at,
+            finished_at: value.finished_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationIssueDto {
+    pub id: i32,
+
+    pub kind: ReconciliationIssueKind,
+
+    pub account: Option<String>,
+
+    pub signature: Option<String>,
+
+    pub transfer_id: Option<i32>,
+
+    pub expected_amount: Option<i64>,
+
+    pub actual_amount: Option<i64>,
+}
+
+impl From<ReconciliationIssueModel> for ReconciliationIssueDto {
+    fn from(value: ReconciliationIssueModel) -> Self {
+        ReconciliationIssueDto {
+            id: value.id,
+            kind: value.kind,
+            account: value.account,
+            signature: value.signature,
+            transfer_id: value.transfer_id,
+            expected_amount: value.expected_amount,
+            actual_amount: value.actual_amount,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReportDetailsDto {
+    #[serde(flatten)]
+    pub report: ReconciliationReportDto,
+
+    pub issues: Vec<ReconciliationIssueDto>,
+}
+
+impl From<(ReconciliationReportModel, Vec<ReconciliationIssueModel>)>
+    for ReconciliationReportDetailsDto
+{
+    fn from(value: (ReconciliationReportModel, Vec<ReconciliationIssueModel>)) -> Self {
+        let (report, issues) = value;
+        ReconciliationReportDetailsDto {
+            report: report.into(),
+            issues: issues.into_iter().map(|val| val.into()).collect(),
+        }
+    }
+}
@@ -0,0 +1,559 @@
+pub mod dto;
+pub mod reconciliation_handler;
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    constants::{
+        RECONCILIATION_JOB_INTERVAL_SECS, RECONCILIATION_LOOKBACK_HOURS,
+        RECONCILIATION_MAX_SIGNATURE_PAGES, RECONCILIATION_RPC_CONCURRENCY, TREASURY_PUBKEY,
+        USDC_MINT,
+    },
+    db::entity::{
+        escrow,
+        payment::{self, Model as PaymentModel},
+        payment_split::Model as PaymentSplitModel,
+        prelude::{
+            Escrow, Merchant, Payment, PaymentSplit, ReconciliationIssue, ReconciliationReport,
+            SettlementWallet, Transfer,
+        },
+        reconciliation_issue::{self, Model as ReconciliationIssueModel},
+        reconciliation_report::{self, Model as ReconciliationReportModel},
+        sea_orm_active_enums::{ReconciliationIssueKind, TransferStatus},
+        settlement_wallet, transfer,
+    },
+    services::{
+        AppState,
+        error::{EntityId, MathErrorType, Result, ServiceError},
+        indexer::Indexer,
+        pagination::{CursorPaginator, CursorValue, Page, PageQuery, SortDirection},
+        payment::get_transfer_legs,
+        web3::get_escrow_pubkey,
+    },
+};
+use chrono::Utc;
+use futures::{StreamExt, stream};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, JoinType, LoaderTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait,
+};
+use solana_signature::Signature;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::solana_program::pubkey::Pubkey;
+use tokio::time::interval;
+
+// Max signatures per `getSignatureStatuses` request
+const SIGNATURE_STATUS_BATCH: usize = 256;
+
+struct Issue {
+    kind: ReconciliationIssueKind,
+    account: Option<String>,
+    signature: Option<String>,
+    transfer_id: Option<i32>,
+    expected_amount: Option<i64>,
+    actual_amount: Option<i64>,
+}
+
+struct Scan {
+    accounts_scanned: usize,
+    signatures_scanned: usize,
+    issues: Vec<Issue>,
+}
+
+pub struct ReconciliationService;
+
+impl ReconciliationService {
+    const REPORT: &'static str = "ReconciliationReport";
+
+    /**
+     * Starts a reconciliation of the last `RECONCILIATION_LOOKBACK_HOURS` in the background.
+     * The report is returned right away and gets `finished_at` set once the scan is done.
+     */
+    pub async fn start(state: Arc<AppState>) -> Result<ReconciliationReportModel> {
+        let now = Utc::now().naive_utc();
+        let data = reconciliation_report::ActiveModel {
+            window_start: Set(now - chrono::Duration::hours(RECONCILIATION_LOOKBACK_HOURS)),
+            window_end: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let report = ReconciliationReport::insert(data)
+            .exec_with_returning(state.db())
+            .await?;
+
+        let pending = report.clone();
+        tokio::spawn(async move {
+            let id = report.id;
+            if let Err(e) = Self::reconcile(state, report).await {
+                tracing::error!("Reconciliation {} failed: {:?}", id, e);
+            }
+        });
+
+        Ok(pending)
+    }
+
+    pub async fn find_all(
+        state: Arc<AppState>,
+        page_query: PageQuery,
+    ) -> Result<Page<ReconciliationReportModel>> {
+        let paginator = CursorPaginator::<ReconciliationReport> {
+            sort_column: reconciliation_report::Column::Id,
+            id_column: reconciliation_report::Column::Id,
+            direction: SortDirection::Desc,
+            sort_value: |val| CursorValue::Int(val.id.into()),
+            id_value: |val| val.id,
+        };
+
+        paginator
+            .fetch(state.db(), ReconciliationReport::find(), &page_query)
+            .await
+    }
+
+    pub async fn find_one(
+        state: Arc<AppState>,
+        id: i32,
+    ) -> Result<(ReconciliationReportModel, Vec<ReconciliationIssueModel>)> {
+        let report = ReconciliationReport::find_by_id(id)
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::REPORT,
+                id: EntityId::Int(id),
+            })?;
+
+        let issues = ReconciliationIssue::find()
+            .filter(reconciliation_issue::Column::ReportId.eq(id))
+            .order_by_asc(reconciliation_issue::Column::Id)
+            .all(state.db())
+            .await?;
+
+        Ok((report, issues))
+    }
+
+    pub async fn run_reconciliation_job(state: Arc<AppState>) {
+        let mut ticker = interval(Duration::from_secs(RECONCILIATION_JOB_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            if let Err(e) = Self::start(state.clone()).await {
+                tracing::error!("Failed to start reconciliation: {:?}", e);
+            }
+        }
+    }
+
+    // Runs the scan and stores its issues, recording a failure on the report row
+    async fn reconcile(
+        state: Arc<AppState>,
+        report: ReconciliationReportModel,
+    ) -> Result<ReconciliationReportModel> {
+        let result = Self::scan(state.clone(), &report).await;
+
+        let mut data: reconciliation_report::ActiveModel = report.clone().into();
+        data.finished_at = Set(Some(Utc::now().naive_utc()));
+        match &result {
+            Ok(scan) => {
+                data.accounts_scanned = Set(to_count(scan.accounts_scanned));
+                data.signatures_scanned = Set(to_count(scan.signatures_scanned));
+                data.issue_count = Set(to_count(scan.issues.len()));
+            }
+            Err(e) => data.error = Set(Some(format!("{:?}", e))),
+        }
+
+        if let Ok(scan) = result
+            && !scan.issues.is_empty()
+        {
+            let issues = scan
+                .issues
+                .into_iter()
+                .map(|issue| reconciliation_issue::ActiveModel {
+                    report_id: Set(report.id),
+                    kind: Set(issue.kind),
+                    account: Set(issue.account),
+                    signature: Set(issue.signature),
+                    transfer_id: Set(issue.transfer_id),
+                    expected_amount: Set(issue.expected_amount),
+                    actual_amount: Set(issue.actual_amount),
+                    created_at: Set(Utc::now().naive_utc()),
+                    ..Default::default()
+                });
+            ReconciliationIssue::insert_many(issues)
+                .exec(state.db())
+                .await?;
+        }
+
+        let report = ReconciliationReport::update(data).exec(state.db()).await?;
+        Ok(report)
+    }
+
+    /**
+     * Walks recent signatures of every merchant's receiving token account and the treasury's.
+     * Only live mode is reconciled, test mode payments settle on the test cluster.
+     * - Signatures of known transfers are checked against the amount that leg should have received.
+     * - Incoming transfers we have no record of are reported as unmatched receipts, with the
+     *   transfer attached when its reference key is in the transaction.
+     * - Completed transfers of the window that never landed on chain are reported as missing.
+     */
+    async fn scan(state: Arc<AppState>, report: &ReconciliationReportModel) -> Result<Scan> {
+        let mint = Pubkey::from_str(USDC_MINT)?;
+        let (since, until) = (
+            report.window_start.and_utc().timestamp(),
+            report.window_end.and_utc().timestamp(),
+        );
+
+        // Settlement wallets by the merchant's user id
+        let mut wallets: HashMap<i32, HashSet<String>> = HashMap::new();
+        let settlement_wallets = SettlementWallet::find()
+            .filter(settlement_wallet::Column::TokenMint.eq(USDC_MINT))
+            .find_also_related(Merchant)
+            .all(state.db())
+            .await?;
+        for (settlement_wallet, merchant) in settlement_wallets {
+            if let Some(merchant) = merchant {
+                wallets
+                    .entry(merchant.user_id)
+                    .or_default()
+                    .insert(settlement_wallet.address);
+            }
+        }
+
+        let mut owners = vec![TREASURY_PUBKEY.to_string()];
+        owners.extend(wallets.values().flatten().cloned());
+        owners.sort();
+        owners.dedup();
+
+        let mut scan = Scan {
+            accounts_scanned: owners.len(),
+            signatures_scanned: 0,
+            issues: Vec::new(),
+        };
+        let mut seen = HashSet::new();
+
+        for owner in owners {
+            let token_account = get_associated_token_address(&Pubkey::from_str(&owner)?, &mint);
+            let signatures = state
+                .web3_for(true)
+                .find_signatures_since(&token_account, since, RECONCILIATION_MAX_SIGNATURE_PAGES)
+                .await?
+                .into_iter()
+                .filter(|status| status.err.is_none())
+                .filter(|status| status.block_time.is_none_or(|time| time <= until))
+                .map(|status| status.signature)
+                .collect::<Vec<_>>();
+
+            scan.signatures_scanned += signatures.len();
+            seen.extend(signatures.iter().cloned());
+
+            Self::match_signatures(
+                &state,
+                &owner,
+                &token_account,
+                &mint,
+                &wallets,
+                signatures,
+                &mut scan,
+            )
+            .await?;
+        }
+
+        Self::find_missing_on_chain(&state, report, &seen, &mut scan).await?;
+
+        Ok(scan)
+    }
+
+    async fn match_signatures(
+        state: &AppState,
+        owner: &str,
+        token_account: &Pubkey,
+        mint: &Pubkey,
+        wallets: &HashMap<i32, HashSet<String>>,
+        signatures: Vec<String>,
+        scan: &mut Scan,
+    ) -> Result<()> {
+        if signatures.is_empty() {
+            return Ok(());
+        }
+
+        let transfers = Transfer::find()
+            .filter(transfer::Column::Signature.is_in(signatures.clone()))
+            .find_also_related(Payment)
+            .all(state.db())
+            .await?;
+        let escrow_signatures = Escrow::find()
+            .filter(escrow::Column::Signature.is_in(signatures.clone()))
+            .all(state.db())
+            .await?
+            .into_iter()
+            .filter_map(|escrow| escrow.signature)
+            .collect::<HashSet<_>>();
+
+        let payments = transfers
+            .iter()
+            .filter_map(|(_, payment)| payment.clone())
+            .collect::<Vec<_>>();
+        let splits = payments
+            .load_many(PaymentSplit, state.db())
+            .await?
+            .into_iter()
+            .zip(payments.iter())
+            .map(|(splits, payment)| (payment.id, splits))
+            .collect::<HashMap<_, _>>();
+
+        let transfers = transfers
+            .into_iter()
+            .filter_map(|(transfer, payment)| {
+                let signature = transfer.signature.clone()?;
+                Some((signature, (transfer, payment?)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Escrow releases and refunds are reconciled by the ledger
+        let signatures = signatures
+            .into_iter()
+            .filter(|signature| !escrow_signatures.contains(signature));
+        let balance_changes = find_all_bounded(
+            signatures,
+            RECONCILIATION_RPC_CONCURRENCY,
+            |signature| async move {
+                Indexer::find_token_balance_change(
+                    state.web3_for(true),
+                    &signature,
+                    token_account,
+                    mint,
+                )
+                .await
+            },
+        )
+        .await;
+
+        for (signature, balance_change) in balance_changes {
+            // A failed lookup is reported on its own instead of failing the whole report
+            let (actual, account_keys) = match balance_change {
+                Ok(balance_change) => balance_change,
+                Err(e) => {
+                    tracing::error!("Failed to fetch transaction {}: {:?}", signature, e);
+                    scan.issues.push(Issue {
+                        kind: ReconciliationIssueKind::LookupFailed,
+                        account: Some(owner.to_string()),
+                        transfer_id: transfers.get(&signature).map(|(transfer, _)| transfer.id),
+                        signature: Some(signature),
+                        expected_amount: None,
+                        actual_amount: None,
+                    });
+                    continue;
+                }
+            };
+
+            if let Some((transfer, payment)) = transfers.get(&signature) {
+                let splits = splits
+                    .get(&payment.id)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let expected = expected_amount(transfer.amount, payment, wallets, splits, owner)?;
+                if expected != actual {
+                    scan.issues.push(Issue {
+                        kind: ReconciliationIssueKind::AmountMismatch,
+                        account: Some(owner.to_string()),
+                        signature: Some(signature),
+                        transfer_id: Some(transfer.id),
+                        expected_amount: Some(expected),
+                        actual_amount: Some(actual),
+                    });
+                }
+                continue;
+            }
+
+            // Outgoing transfers of the account aren't receipts
+            if actual <= 0 {
+                continue;
+            }
+
+            let references = account_keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>();
+            let transfer = Transfer::find()
+                .filter(transfer::Column::ReferenceKey.is_in(references))
+                .one(state.db())
+                .await?;
+
+            scan.issues.push(Issue {
+                kind: ReconciliationIssueKind::UnmatchedReceipt,
+                account: Some(owner.to_string()),
+                signature: Some(signature),
+                transfer_id: transfer.map(|transfer| transfer.id),
+                expected_amount: None,
+                actual_amount: Some(actual),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Completed transfers of the window whose signature wasn't seen and isn't confirmed on chain
+    async fn find_missing_on_chain(
+        state: &AppState,
+        report: &ReconciliationReportModel,
+        seen: &HashSet<String>,
+        scan: &mut Scan,
+    ) -> Result<()> {
+        // Test mode transfers live on the test cluster
+        let transfers = Transfer::find()
+            .join(JoinType::InnerJoin, transfer::Relation::Payment.def())
+            .filter(payment::Column::Livemode.eq(true))
+            .filter(transfer::Column::Status.eq(TransferStatus::Completed))
+            .filter(transfer::Column::CreatedAt.gte(report.window_start))
+            .filter(transfer::Column::CreatedAt.lt(report.window_end))
+            .all(state.db())
+            .await?;
+
+        let mut unseen = Vec::new();
+        for transfer in transfers {
+            match &transfer.signature {
+                Some(signature) if seen.contains(signature) => {}
+                Some(signature) => unseen.push((Signature::from_str(signature).ok(), transfer)),
+                None => scan.issues.push(missing_on_chain(&transfer)),
+            }
+        }
+
+        for batch in unseen.chunks(SIGNATURE_STATUS_BATCH) {
+            let signatures = batch
+                .iter()
+                .filter_map(|(signature, _)| *signature)
+                .collect::<Vec<_>>();
+            // The RPC client blocks, keep it off the runtime
+            let rpc_client = state.web3_for(true).rpc_client.clone();
+            let (signatures, statuses) = tokio::task::spawn_blocking(move || {
+                rpc_client
+                    .get_signature_statuses_with_history(&signatures)
+                    .map(|statuses| (signatures, statuses.value))
+                    .map_err(ServiceError::from)
+            })
+            .await
+            .map_err(|e| ServiceError::Custom(e.to_string()))??;
+            let confirmed = signatures
+                .iter()
+                .zip(statuses)
+                .filter(|(_, status)| status.as_ref().is_some_and(|status| status.err.is_none()))
+                .map(|(signature, _)| *signature)
+                .collect::<HashSet<_>>();
+
+            for (signature, transfer) in batch {
+                if !signature.is_some_and(|signature| confirmed.contains(&signature)) {
+                    scan.issues.push(missing_on_chain(transfer));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Amount `owner` should have received from a transfer: the treasury fee, a split or the
+ * merchant's remainder. Escrow payments only pay the treasury and the escrow wallet up front.
+ */
+fn expected_amount(
+    amount: i64,
+    payment: &PaymentModel,
+    wallets: &HashMap<i32, HashSet<String>>,
+    splits: &[PaymentSplitModel],
+    owner: &str,
+) -> Result<i64> {
+    let amount = u64::try_from(amount)
+        .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+    let legs = if payment.is_escrow {
+        get_transfer_legs(amount, &get_escrow_pubkey()?.to_string(), &[])?
+    } else {
+        // The merchant's share may have gone to any of its settlement wallets
+        let is_merchant_wallet = wallets
+            .get(&payment.user_id)
+            .is_some_and(|addresses| addresses.contains(owner));
+        let receiver_wallet = if is_merchant_wallet { owner } else { "" };
+        get_transfer_legs(amount, receiver_wallet, splits)?
+    };
+
+    let expected = legs
+        .iter()
+        .filter(|leg| leg.receiver_wallet == owner)
+        .try_fold(0u64, |total, leg| total.checked_add(leg.amount))
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+    i64::try_from(expected).map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))
+}
+
+fn missing_on_chain(transfer: &transfer::Model) -> Issue {
+    Issue {
+        kind: ReconciliationIssueKind::MissingOnChain,
+        account: None,
+        signature: transfer.signature.clone(),
+        transfer_id: Some(transfer.id),
+        expected_amount: Some(transfer.amount),
+        actual_amount: None,
+    }
+}
+
+fn to_count(count: usize) -> i32 {
+    i32::try_from(count).unwrap_or(i32::MAX)
+}
+
+// Runs `f` on every item with at most `limit` running at once, keeping each item's own result
+async fn find_all_bounded<T: Clone, R, F: Future<Output = Result<R>>>(
+    items: impl IntoIterator<Item = T>,
+    limit: usize,
+    f: impl Fn(T) -> F,
+) -> Vec<(T, Result<R>)> {
+    stream::iter(items)
+        .map(|item| {
+            let result = f(item.clone());
+            async move { (item, result.await) }
+        })
+        .buffered(limit)
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod test {
+    use crate::services::{error::ServiceError, reconciliation::find_all_bounded};
+    use anyhow::Result;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn test_find_all_bounded() -> Result<()> {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let (running, max_running) = (&running, &max_running);
+
+        let results = find_all_bounded(0..20, 3, |item| async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+
+            match item {
+                5 => Err(ServiceError::Custom("rpc down".to_string())),
+                item => Ok(item * 2),
+            }
+        })
+        .await;
+
+        // One failed item doesn't drop the others
+        assert_eq!(results.len(), 20);
+        assert_eq!(max_running.load(Ordering::SeqCst), 3);
+        for (item, result) in results {
+            match item {
+                5 => assert!(result.is_err()),
+                item => assert_eq!(result.ok(), Some(item * 2)),
+            }
+        }
+        Ok(())
+    }
+}
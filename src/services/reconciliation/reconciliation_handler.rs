@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use crate::services::{
+    AppState,
+    error::Result,
+    pagination::{Page, PageQuery},
+    reconciliation::{
+        ReconciliationService,
+        dto::reconciliation_report_dto::{ReconciliationReportDetailsDto, ReconciliationReportDto},
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    Query(page_query): Query<PageQuery>,
+) -> Result<Json<Page<ReconciliationReportDto>>> {
+    let reports = ReconciliationService::find_all(state, page_query).await?;
+    Ok(Json(reports.map(|val| val.into())))
+}
+
+pub async fn find_one(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<ReconciliationReportDetailsDto>> {
+    let report = ReconciliationService::find_one(state, id).await?;
+    Ok(Json(report.into()))
+}
+
+pub async fn run(State(state): State<Arc<AppState>>) -> Result<Json<ReconciliationReportDto>> {
+    let report = ReconciliationService::start(state).await?;
+    Ok(Json(report.into()))
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+use crate::db::entity::sea_orm_active_enums::UserRoleType;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantRoleDto {
+    pub role: UserRoleType,
+}
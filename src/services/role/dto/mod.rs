@@ -0,0 +1,2 @@
+pub mod grant_role_dto;
+pub mod user_role_dto;
@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{sea_orm_active_enums::UserRoleType, user_role::Model as UserRoleModel};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRoleDto {
+    pub user_id: i32,
+
+    pub role: UserRoleType,
+
+    pub granted_by_id: Option<i32>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<UserRoleModel> for UserRoleDto {
+    fn from(value: UserRoleModel) -> Self {
+        UserRoleDto {
+            user_id: value.user_id,
+            role: value.role,
+            granted_by_id: value.granted_by_id,
+            created_at: value.created_at,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+pub mod dto;
+pub mod role_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    db::entity::{
+        prelude::UserRole,
+        sea_orm_active_enums::{MerchantMemberRole, UserRoleType},
+        user_role::{self, Model as UserRoleModel},
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        role::dto::grant_role_dto::GrantRoleDto,
+        user::UserService,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+    sea_query::OnConflict,
+};
+
+// What a route may require, granted through the user's roles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    ViewPayments,
+    ManagePayments,
+    RefundPayments,
+    ManageMerchant,
+    ManagePlatform,
+}
+
+// Customers have no stored role, owners manage their merchant and staff work with its payments
+pub fn role_permissions(role: UserRoleType) -> &'static [Permission] {
+    match role {
+        UserRoleType::MerchantOwner => &[
+            Permission::ViewPayments,
+            Permission::ManagePayments,
+            Permission::RefundPayments,
+            Permission::ManageMerchant,
+        ],
+        UserRoleType::MerchantStaff => &[Permission::ViewPayments, Permission::ManagePayments],
+        UserRoleType::Admin => &[Permission::ManagePlatform],
+    }
+}
+
+// Granted to staff on top of their role, by what they do at their merchant
+pub fn member_role_permissions(role: MerchantMemberRole) -> &'static [Permission] {
+    match role {
+        MerchantMemberRole::Manager => &[Permission::RefundPayments],
+        MerchantMemberRole::Cashier => &[],
+    }
+}
+
+pub struct RoleService;
+
+impl RoleService {
+    const USER_ROLE: &'static str = "UserRole";
+
+    pub async fn find_roles(state: Arc<AppState>, user_id: i32) -> Result<Vec<UserRoleType>> {
+        let roles = Self::find_user_roles(state, user_id)
+            .await?
+            .into_iter()
+            .map(|val| val.role)
+            .collect();
+
+        Ok(roles)
+    }
+
+    pub async fn find_user_roles(state: Arc<AppState>, user_id: i32) -> Result<Vec<UserRoleModel>> {
+        let roles = UserRole::find()
+            .filter(user_role::Column::UserId.eq(user_id))
+            .order_by_asc(user_role::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(roles)
+    }
+
+    // Granting a role the user already has keeps the original grant
+    pub async fn grant(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        user_id: i32,
+        grant_role_dto: GrantRoleDto,
+    ) -> Result<Vec<UserRoleModel>> {
+        UserService::find_one(state.clone(), user_id).await?;
+        Self::insert_role(state.db(), user_id, grant_role_dto.role, Some(ctx.user_id)).await?;
+
+        Self::find_user_roles(state, user_id).await
+    }
+
+    pub async fn revoke(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        user_id: i32,
+        role: UserRoleType,
+    ) -> Result<Vec<UserRoleModel>> {
+        // Keeps the platform from being left without an admin by accident
+        if user_id == ctx.user_id && role == UserRoleType::Admin {
+            return Err(ServiceError::Custom(
+                "Admins can't revoke their own admin role".to_string(),
+            ));
+        }
+
+        let result = UserRole::delete_many()
+            .filter(user_role::Column::UserId.eq(user_id))
+            .filter(user_role::Column::Role.eq(role))
+            .exec(state.db())
+            .await?;
+        if result.rows_affected == 0 {
+            return Err(ServiceError::EntityNotFound {
+                entity: Self::USER_ROLE,
+                id: EntityId::Int(user_id),
+            });
+        }
+
+        Self::find_user_roles(state, user_id).await
+    }
+
+    // Roles granted by the platform itself, such as owning a newly created merchant, have no granting user
+    pub(in crate::services) async fn insert_role<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        role: UserRoleType,
+        granted_by_id: Option<i32>,
+    ) -> Result<()> {
+        let data = user_role::ActiveModel {
+            user_id: Set(user_id),
+            role: Set(role),
+            granted_by_id: Set(granted_by_id),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        UserRole::insert(data)
+            .on_conflict(
+                OnConflict::columns([user_role::Column::UserId, user_role::Column::Role])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .do_nothing()
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(in crate::services) async fn delete_role<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        role: UserRoleType,
+    ) -> Result<()> {
+        UserRole::delete_many()
+            .filter(user_role::Column::UserId.eq(user_id))
+            .filter(user_role::Column::Role.eq(role))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::sea_orm_active_enums::{MerchantMemberRole, UserRoleType},
+        services::role::{Permission, member_role_permissions, role_permissions},
+    };
+
+    #[test]
+    fn test_role_permissions() {
+        let owner = role_permissions(UserRoleType::MerchantOwner);
+        let staff = role_permissions(UserRoleType::MerchantStaff);
+        let admin = role_permissions(UserRoleType::Admin);
+
+        assert!(owner.contains(&Permission::ManageMerchant));
+        assert!(staff.contains(&Permission::ManagePayments));
+        assert!(!staff.contains(&Permission::ManageMerchant));
+        assert!(!owner.contains(&Permission::ManagePlatform));
+        assert!(!staff.contains(&Permission::RefundPayments));
+        assert_eq!(
+            member_role_permissions(MerchantMemberRole::Manager),
+            &[Permission::RefundPayments]
+        );
+        assert_eq!(admin, &[Permission::ManagePlatform]);
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    db::entity::sea_orm_active_enums::UserRoleType,
+    services::{
+        AppState,
+        error::Result,
+        role::{
+            RoleService,
+            dto::{grant_role_dto::GrantRoleDto, user_role_dto::UserRoleDto},
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<i32>,
+) -> Result<Json<Vec<UserRoleDto>>> {
+    let roles = RoleService::find_user_roles(state, user_id).await?;
+    Ok(Json(roles.into_iter().map(|val| val.into()).collect()))
+}
+
+pub async fn grant(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(user_id): Path<i32>,
+    Json(grant_role_dto): Json<GrantRoleDto>,
+) -> Result<Json<Vec<UserRoleDto>>> {
+    let roles = RoleService::grant(state, ctx, user_id, grant_role_dto).await?;
+    Ok(Json(roles.into_iter().map(|val| val.into()).collect()))
+}
+
+pub async fn revoke(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path((user_id, role)): Path<(i32, UserRoleType)>,
+) -> Result<Json<Vec<UserRoleDto>>> {
+    let roles = RoleService::revoke(state, ctx, user_id, role).await?;
+    Ok(Json(roles.into_iter().map(|val| val.into()).collect()))
+}
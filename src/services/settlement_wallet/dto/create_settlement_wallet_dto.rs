@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSettlementWalletDto {
+    pub address: String,
+
+    // Defaults to USDC
+    pub token_mint: Option<String>,
+
+    // Base58 signature of the nonce message by the wallet
+    pub signature: String,
+
+    pub is_default: Option<bool>,
+}
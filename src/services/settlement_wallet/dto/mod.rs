@@ -0,0 +1,3 @@
+pub mod create_settlement_wallet_dto;
+pub mod settlement_wallet_dto;
+pub mod wallet_nonce_dto;
@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::settlement_wallet::Model as SettlementWalletModel;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementWalletDto {
+    pub id: i32,
+
+    pub address: String,
+
+    pub token_mint: String,
+
+    pub is_default: bool,
+
+    pub verified_at: NaiveDateTime,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<SettlementWalletModel> for SettlementWalletDto {
+    fn from(value: SettlementWalletModel) -> Self {
+        SettlementWalletDto {
+            id: value.id,
+            address: value.address,
+            token_mint: value.token_mint,
+            is_default: value.is_default,
+            verified_at: value.verified_at,
+            created_at: value.created_at,
+        }
+    }
+}
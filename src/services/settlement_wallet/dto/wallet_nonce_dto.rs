@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::db::entity::wallet_challenge::Model as WalletChallengeModel;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWalletNonceDto {
+    pub address: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletNonceDto {
+    pub address: String,
+
+    pub message: String,
+
+    pub expires_at: NaiveDateTime,
+}
+
+impl From<(WalletChallengeModel, String)> for WalletNonceDto {
+    fn from(value: (WalletChallengeModel, String)) -> Self {
+        let (nonce, message) = value;
+        WalletNonceDto {
+            address: nonce.address,
+            message,
+            expires_at: nonce.expires_at,
+        }
+    }
+}
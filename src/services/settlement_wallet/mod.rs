@@ -0,0 +1,315 @@
+pub mod dto;
+pub mod settlement_wallet_handler;
+
+use std::{str::FromStr, sync::Arc};
+
+use crate::{
+    constants::USDC_MINT,
+    ctx::Ctx,
+    db::entity::{
+        merchant::{self, Model as MerchantModel},
+        prelude::SettlementWallet,
+        sea_orm_active_enums::WalletChallengePurpose,
+        settlement_wallet::{self, Model as SettlementWalletModel},
+        wallet_challenge::Model as WalletChallengeModel,
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        settlement_wallet::dto::{
+            create_settlement_wallet_dto::CreateSettlementWalletDto,
+            wallet_nonce_dto::CreateWalletNonceDto,
+        },
+        user::UserService,
+        wallet_challenge::WalletChallengeService,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait, prelude::Expr,
+};
+use spl_token::solana_program::pubkey::Pubkey;
+
+pub struct SettlementWalletService;
+
+impl SettlementWalletService {
+    const SETTLEMENT_WALLET: &'static str = "SettlementWallet";
+
+    /**
+     * Issues a single use nonce for the wallet. The merchant proves ownership by signing the
+     * returned message with the wallet and passing the signature to `create`.
+     */
+    pub async fn create_nonce(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_wallet_nonce_dto: CreateWalletNonceDto,
+    ) -> Result<(WalletChallengeModel, String)> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let address = parse_address(&create_wallet_nonce_dto.address)?.to_string();
+
+        let nonce = WalletChallengeService::issue(
+            state.db(),
+            WalletChallengePurpose::SettlementWallet,
+            &address,
+            None,
+            Some(merchant.id),
+        )
+        .await?;
+
+        let message = ownership_message(&merchant, &nonce);
+        Ok((nonce, message))
+    }
+
+    pub async fn create(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_settlement_wallet_dto: CreateSettlementWalletDto,
+    ) -> Result<SettlementWalletModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let wallet = parse_address(&create_settlement_wallet_dto.address)?;
+        let address = wallet.to_string();
+        let token_mint = match &create_settlement_wallet_dto.token_mint {
+            Some(token_mint) => parse_address(token_mint)?.to_string(),
+            None => USDC_MINT.to_string(),
+        };
+
+        let txn = state.db().begin().await?;
+
+        WalletChallengeService::verify(
+            &txn,
+            WalletChallengePurpose::SettlementWallet,
+            &address,
+            None,
+            Some(merchant.id),
+            &create_settlement_wallet_dto.signature,
+            |nonce| ownership_message(&merchant, nonce),
+        )
+        .await?;
+
+        let existing = SettlementWallet::find()
+            .filter(settlement_wallet::Column::MerchantId.eq(merchant.id))
+            .filter(settlement_wallet::Column::Address.eq(&address))
+            .filter(settlement_wallet::Column::TokenMint.eq(&token_mint))
+            .one(&txn)
+            .await?;
+        if existing.is_some() {
+            return Err(ServiceError::DtoError(
+                "Settlement wallet is already registered".to_string(),
+            ));
+        }
+
+        // The first wallet of a token becomes its default
+        let has_default = Self::find_default(&txn, merchant.id, &token_mint)
+            .await?
+            .is_some();
+        let is_default = create_settlement_wallet_dto.is_default.unwrap_or(false) || !has_default;
+        if is_default {
+            Self::clear_default(&txn, merchant.id, &token_mint).await?;
+        }
+
+        let now = Utc::now().naive_utc();
+        let data = settlement_wallet::ActiveModel {
+            merchant_id: Set(merchant.id),
+            address: Set(address),
+            token_mint: Set(token_mint),
+            is_default: Set(is_default),
+            verified_at: Set(now),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let settlement_wallet = SettlementWallet::insert(data)
+            .exec_with_returning(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(settlement_wallet)
+    }
+
+    pub async fn find_all(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<SettlementWalletModel>> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let settlement_wallets = SettlementWallet::find()
+            .filter(settlement_wallet::Column::MerchantId.eq(merchant.id))
+            .order_by_asc(settlement_wallet::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        Ok(settlement_wallets)
+    }
+
+    pub async fn set_default(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+    ) -> Result<SettlementWalletModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let txn = state.db().begin().await?;
+        let settlement_wallet = Self::find_merchant_wallet(&txn, merchant.id, id).await?;
+        Self::clear_default(&txn, merchant.id, &settlement_wallet.token_mint).await?;
+
+        let mut settlement_wallet: settlement_wallet::ActiveModel = settlement_wallet.into();
+        settlement_wallet.is_default = Set(true);
+        let settlement_wallet = SettlementWallet::update(settlement_wallet)
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(settlement_wallet)
+    }
+
+    pub async fn remove(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<()> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let settlement_wallet = Self::find_merchant_wallet(state.db(), merchant.id, id).await?;
+        if settlement_wallet.is_default {
+            return Err(ServiceError::DtoError(
+                "Set another default wallet before removing this one".to_string(),
+            ));
+        }
+
+        settlement_wallet.delete(state.db()).await?;
+        Ok(())
+    }
+
+    // Default settlement wallet of the user's merchant profile for the token
+    pub async fn find_default_address(
+        state: Arc<AppState>,
+        user_id: i32,
+        token_mint: &str,
+    ) -> Result<String> {
+        let settlement_wallet = SettlementWallet::find()
+            .join(
+                JoinType::InnerJoin,
+                settlement_wallet::Relation::Merchant.def(),
+            )
+            .filter(merchant::Column::UserId.eq(user_id))
+            .filter(settlement_wallet::Column::TokenMint.eq(token_mint))
+            .filter(settlement_wallet::Column::IsDefault.eq(true))
+            .one(state.db())
+            .await?;
+
+        settlement_wallet
+            .map(|val| val.address)
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::SETTLEMENT_WALLET,
+                id: EntityId::Int(user_id),
+            })
+    }
+
+    async fn find_merchant_wallet<C: ConnectionTrait>(
+        db: &C,
+        merchant_id: i32,
+        id: i32,
+    ) -> Result<SettlementWalletModel> {
+        SettlementWallet::find_by_id(id)
+            .filter(settlement_wallet::Column::MerchantId.eq(merchant_id))
+            .one(db)
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::SETTLEMENT_WALLET,
+                id: EntityId::Int(id),
+            })
+    }
+
+    async fn find_default<C: ConnectionTrait>(
+        db: &C,
+        merchant_id: i32,
+        token_mint: &str,
+    ) -> Result<Option<SettlementWalletModel>> {
+        let settlement_wallet = SettlementWallet::find()
+            .filter(settlement_wallet::Column::MerchantId.eq(merchant_id))
+            .filter(settlement_wallet::Column::TokenMint.eq(token_mint))
+            .filter(settlement_wallet::Column::IsDefault.eq(true))
+            .one(db)
+            .await?;
+
+        Ok(settlement_wallet)
+    }
+
+    async fn clear_default<C: ConnectionTrait>(
+        db: &C,
+        merchant_id: i32,
+        token_mint: &str,
+    ) -> Result<()> {
+        SettlementWallet::update_many()
+            .col_expr(settlement_wallet::Column::IsDefault, Expr::value(false))
+            .filter(settlement_wallet::Column::MerchantId.eq(merchant_id))
+            .filter(settlement_wallet::Column::TokenMint.eq(token_mint))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn parse_address(address: &str) -> Result<Pubkey> {
+    Pubkey::from_str(address.trim())
+        .map_err(|_| ServiceError::DtoError("Invalid wallet address".to_string()))
+}
+
+// Message the wallet signs to prove ownership, rebuilt from the stored nonce on verification
+fn ownership_message(merchant: &MerchantModel, nonce: &WalletChallengeModel) -> String {
+    format!(
+        "Zunopay settlement wallet verification\n\nMerchant: {}\nWallet: {}\nNonce: {}\nExpires at: {}",
+        merchant.slug,
+        nonce.address,
+        nonce.nonce,
+        nonce.expires_at.format("%Y-%m-%dT%H:%M:%SZ")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::{
+            merchant::Model as MerchantModel, sea_orm_active_enums::WalletChallengePurpose,
+            wallet_challenge::Model as WalletChallengeModel,
+        },
+        services::{settlement_wallet::ownership_message, web3::verify_message_signature},
+    };
+    use anyhow::Result;
+    use chrono::Utc;
+    use solana_keypair::Keypair;
+    use solana_signer::Signer;
+
+    #[test]
+    fn test_verify_wallet_ownership() -> Result<()> {
+        let wallet = Keypair::new();
+        let merchant = MerchantModel {
+            id: 1,
+            display_name: "Coffee".to_string(),
+            slug: "coffee".to_string(),
+            cover: None,
+            address: "Main street".to_string(),
+            category_id: 1,
+            s3_bucket_slug: "merchant/coffee".to_string(),
+            business_registration_number: None,
+            is_verified: false,
+            user_id: 1,
+            latitude: None,
+            longitude: None,
+            is_active: true,
+        };
+        let nonce = WalletChallengeModel {
+            id: 1,
+            address: wallet.pubkey().to_string(),
+            nonce: "nonce".to_string(),
+            user_id: None,
+            expires_at: Utc::now().naive_utc(),
+            created_at: Utc::now().naive_utc(),
+            purpose: WalletChallengePurpose::SettlementWallet,
+            merchant_id: Some(merchant.id),
+        };
+
+        let message = ownership_message(&merchant, &nonce);
+        let signature = wallet.sign_message(message.as_bytes()).to_string();
+        assert!(verify_message_signature(&wallet.pubkey(), message.as_bytes(), &signature).is_ok());
+
+        let other = Keypair::new();
+        assert!(verify_message_signature(&other.pubkey(), message.as_bytes(), &signature).is_err());
+        assert!(verify_message_signature(&wallet.pubkey(), b"other", &signature).is_err());
+        Ok(())
+    }
+}
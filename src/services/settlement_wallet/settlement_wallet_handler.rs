@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        settlement_wallet::{
+            SettlementWalletService,
+            dto::{
+                create_settlement_wallet_dto::CreateSettlementWalletDto,
+                settlement_wallet_dto::SettlementWalletDto,
+                wallet_nonce_dto::{CreateWalletNonceDto, WalletNonceDto},
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn create_nonce(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_wallet_nonce_dto): Json<CreateWalletNonceDto>,
+) -> Result<Json<WalletNonceDto>> {
+    let nonce = SettlementWalletService::create_nonce(state, ctx, create_wallet_nonce_dto).await?;
+    Ok(Json(nonce.into()))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_settlement_wallet_dto): Json<CreateSettlementWalletDto>,
+) -> Result<Json<SettlementWalletDto>> {
+    let settlement_wallet =
+        SettlementWalletService::create(state, ctx, create_settlement_wallet_dto).await?;
+    Ok(Json(settlement_wallet.into()))
+}
+
+pub async fn find_all(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<Vec<SettlementWalletDto>>> {
+    let settlement_wallets = SettlementWalletService::find_all(state, ctx).await?;
+    let settlement_wallets = settlement_wallets
+        .into_iter()
+        .map(|val| val.into())
+        .collect::<Vec<SettlementWalletDto>>();
+
+    Ok(Json(settlement_wallets))
+}
+
+pub async fn set_default(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<SettlementWalletDto>> {
+    let settlement_wallet = SettlementWalletService::set_default(state, ctx, id).await?;
+    Ok(Json(settlement_wallet.into()))
+}
+
+pub async fn remove(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<()> {
+    SettlementWalletService::remove(state, ctx, id).await
+}
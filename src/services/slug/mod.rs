@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+
+use crate::{
+    constants::{RESERVED_SLUG_WORDS, SLUG_MAX_LENGTH},
+    db::entity::{
+        merchant::{self, Model as MerchantModel},
+        merchant_slug_history,
+        prelude::{Merchant, MerchantSlugHistory},
+    },
+    services::error::{Result, ServiceError},
+};
+use deunicode::deunicode;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QuerySelect,
+    SqlErr,
+};
+
+pub struct SlugService;
+
+impl SlugService {
+    /**
+     * First free slug among `base`, `base-2`, `base-3`... Slugs of other merchants, current or
+     * previous, are taken. A merchant's own previous slugs can be reclaimed.
+     */
+    pub(in crate::services) async fn unique_merchant_slug<C: ConnectionTrait>(
+        db: &C,
+        base: &str,
+        merchant_id: Option<i32>,
+    ) -> Result<String> {
+        let prefix = format!("{}-", base);
+
+        let mut current = Merchant::find()
+            .select_only()
+            .column(merchant::Column::Slug)
+            .filter(
+                merchant::Column::Slug
+                    .eq(base)
+                    .or(merchant::Column::Slug.starts_with(&prefix)),
+            );
+        let mut previous = MerchantSlugHistory::find()
+            .select_only()
+            .column(merchant_slug_history::Column::Slug)
+            .filter(
+                merchant_slug_history::Column::Slug
+                    .eq(base)
+                    .or(merchant_slug_history::Column::Slug.starts_with(&prefix)),
+            );
+        if let Some(id) = merchant_id {
+            current = current.filter(merchant::Column::Id.ne(id));
+            previous = previous.filter(merchant_slug_history::Column::MerchantId.ne(id));
+        }
+
+        let mut taken: HashSet<String> = current.into_tuple().all(db).await?.into_iter().collect();
+        taken.extend(previous.into_tuple::<String>().all(db).await?);
+
+        Ok(first_free_slug(base, &taken))
+    }
+
+    // Current slug first, then previous ones so old links keep working
+    pub(in crate::services) async fn find_merchant<C: ConnectionTrait>(
+        db: &C,
+        slug: &str,
+    ) -> Result<Option<MerchantModel>> {
+        let merchant = Merchant::find()
+            .filter(merchant::Column::Slug.eq(slug))
+            .one(db)
+            .await?;
+        if merchant.is_some() {
+            return Ok(merchant);
+        }
+
+        let previous = MerchantSlugHistory::find()
+            .filter(merchant_slug_history::Column::Slug.eq(slug))
+            .find_also_related(Merchant)
+            .one(db)
+            .await?;
+
+        Ok(resolve_merchant(merchant, previous))
+    }
+
+    /**
+     * Moves the merchant to `slug`, keeping the current one in its history. `slug` has to be
+     * free for this merchant, see `unique_merchant_slug`.
+     */
+    pub(in crate::services) async fn change_merchant_slug<C: ConnectionTrait>(
+        db: &C,
+        merchant: MerchantModel,
+        slug: String,
+    ) -> Result<MerchantModel> {
+        // Reclaiming a previous slug takes it out of the history
+        MerchantSlugHistory::delete_many()
+            .filter(merchant_slug_history::Column::MerchantId.eq(merchant.id))
+            .filter(merchant_slug_history::Column::Slug.eq(&slug))
+            .exec(db)
+            .await?;
+
+        let previous = merchant_slug_history::ActiveModel {
+            merchant_id: Set(merchant.id),
+            slug: Set(merchant.slug.clone()),
+            ..Default::default()
+        };
+        MerchantSlugHistory::insert(previous).exec(db).await?;
+
+        let mut data: merchant::ActiveModel = merchant.into();
+        data.slug = Set(slug);
+        let merchant = Merchant::update(data).exec(db).await?;
+
+        Ok(merchant)
+    }
+}
+
+/**
+ * Lowercase ASCII words joined by dashes. Unicode is transliterated ("Café Déjà Vu" becomes
+ * "cafe-deja-vu") and reserved words are dropped.
+ */
+pub(in crate::services) fn slugify(value: &str) -> Result<String> {
+    let ascii = deunicode(value).to_lowercase();
+    let words: Vec<&str> = ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty() && !RESERVED_SLUG_WORDS.contains(word))
+        .collect();
+
+    let mut slug = String::new();
+    for word in words {
+        let separator = if slug.is_empty() { 0 } else { 1 };
+        if slug.len() + separator + word.len() > SLUG_MAX_LENGTH {
+            break;
+        }
+        if separator == 1 {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+
+    // A single word longer than the limit is cut rather than dropped
+    if slug.is_empty() {
+        slug = ascii
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find(|word| !word.is_empty() && !RESERVED_SLUG_WORDS.contains(word))
+            .map(|word| word.chars().take(SLUG_MAX_LENGTH).collect())
+            .unwrap_or_default();
+    }
+
+    if slug.is_empty() {
+        return Err(ServiceError::DtoError(
+            "Slug needs letters or digits that aren't reserved words".into(),
+        ));
+    }
+
+    Ok(slug)
+}
+
+fn resolve_merchant(
+    current: Option<MerchantModel>,
+    previous: Option<(merchant_slug_history::Model, Option<MerchantModel>)>,
+) -> Option<MerchantModel> {
+    current.or(previous.and_then(|(_, merchant)| merchant))
+}
+
+// Set by Postgres for the unique constraint on `merchant.slug`
+pub(in crate::services) fn is_slug_taken(err: &DbErr) -> bool {
+    matches!(
+        err.sql_err(),
+        Some(SqlErr::UniqueConstraintViolation(message)) if message.contains("merchant_slug_key")
+    )
+}
+
+fn first_free_slug(base: &str, taken: &HashSet<String>) -> String {
+    if !taken.contains(base) {
+        return base.to_string();
+    }
+
+    (2..)
+        .map(|suffix| {
+            let suffix = format!("-{}", suffix);
+            // Keeps the suffixed slug within the limit
+            let base = &base[..base.len().min(SLUG_MAX_LENGTH - suffix.len())];
+            format!("{}{}", base.trim_end_matches('-'), suffix)
+        })
+        .find(|slug| !taken.contains(slug))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{
+        db::entity::{merchant, merchant_slug_history},
+        services::slug::{first_free_slug, resolve_merchant, slugify},
+    };
+    use anyhow::Result;
+    use chrono::Utc;
+
+    #[test]
+    fn test_slugify() -> Result<()> {
+        assert_eq!(slugify("Café Déjà Vu")?, "cafe-deja-vu");
+        assert_eq!(slugify("  Zuno Official -- Coffee & Co!  ")?, "coffee-co");
+        assert_eq!(slugify("北京烤鸭")?, "bei-jing-kao-ya");
+        assert!(slugify("Admin").is_err());
+        assert!(slugify(&"a".repeat(100))?.len() <= 60);
+
+        let taken: HashSet<String> = ["coffee".to_string(), "coffee-2".to_string()].into();
+        assert_eq!(first_free_slug("coffee", &taken), "coffee-3");
+        assert_eq!(first_free_slug("tea", &taken), "tea");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_merchant() -> Result<()> {
+        let merchant = merchant::Model {
+            id: 1,
+            display_name: "Blue Bottle".into(),
+            slug: "blue-bottle".into(),
+            cover: None,
+            address: "1 Main St".into(),
+            s3_bucket_slug: "merchant/bottle".into(),
+            business_registration_number: None,
+            is_verified: false,
+            user_id: 1,
+            latitude: None,
+            longitude: None,
+            is_active: true,
+            category_id: 1,
+        };
+        let previous = merchant_slug_history::Model {
+            id: 1,
+            merchant_id: merchant.id,
+            slug: "bottle".into(),
+            created_at: Utc::now().naive_utc(),
+        };
+
+        // An old slug resolves to the merchant that used to own it
+        let found = resolve_merchant(None, Some((previous.clone(), Some(merchant.clone()))));
+        assert_eq!(found.map(|found| found.slug), Some(merchant.slug.clone()));
+
+        let other = merchant::Model {
+            id: 2,
+            slug: "bottle".into(),
+            ..merchant.clone()
+        };
+        let found = resolve_merchant(Some(other), Some((previous, Some(merchant))));
+        assert_eq!(found.map(|found| found.id), Some(2));
+        assert!(resolve_merchant(None, None).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::db::entity::sea_orm_active_enums::StatementFormat;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStatementDto {
+    pub from: NaiveDateTime,
+
+    pub to: NaiveDateTime,
+
+    pub format: StatementFormat,
+}
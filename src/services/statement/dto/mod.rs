@@ -0,0 +1,2 @@
+pub mod create_statement_dto;
+pub mod statement_export_dto;
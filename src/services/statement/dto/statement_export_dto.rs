@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{
+    sea_orm_active_enums::{StatementFormat, StatementStatus},
+    statement_export::Model as StatementExportModel,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementExportDto {
+    pub id: i32,
+
+    pub format: StatementFormat,
+
+    pub status: StatementStatus,
+
+    pub from: NaiveDateTime,
+
+    pub to: NaiveDateTime,
+
+    // Pre-signed link, only set once the statement is completed
+    pub download_url: Option<String>,
+
+    pub error: Option<String>,
+
+    pub created_at: NaiveDateTime,
+
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+impl From<(StatementExportModel, Option<String>)> for StatementExportDto {
+    fn from((value, download_url): (StatementExportModel, Option<String>)) -> Self {
+        StatementExportDto {
+            id: value.id,
+            format: value.format,
+            status: value.status,
+            from: value.period_start,
+            to: value.period_end,
+            download_url,
+            error: value.error,
+            created_at: value.created_at,
+            completed_at: value.completed_at,
+        }
+    }
+}
@@ -0,0 +1,455 @@
+pub mod dto;
+mod pdf;
+pub mod statement_handler;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    constants::{
+        BASE_USDC, STATEMENT_LINK_EXPIRY_SECS, STATEMENT_MAX_RANGE_DAYS, STATEMENT_SYNC_MAX_ROWS,
+    },
+    ctx::Ctx,
+    db::entity::{
+        escrow,
+        merchant::Model as MerchantModel,
+        payment,
+        payment_split::{self, Model as PaymentSplitModel},
+        prelude::{Escrow, Payment, PaymentSplit, StatementExport, Transfer},
+        sea_orm_active_enums::{EscrowStatus, StatementFormat, StatementStatus, TransferStatus},
+        statement_export::{self, Model as StatementExportModel},
+        transfer,
+    },
+    services::{
+        AppState,
+        error::{EntityId, MathErrorType, Result, ServiceError},
+        payment::get_split_amounts,
+        statement::dto::create_statement_dto::CreateStatementDto,
+        storage::private_folder,
+        user::UserService,
+        web3::calculate_transfer_fee,
+    },
+};
+use axum::body::Bytes;
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, Select,
+};
+use uuid::Uuid;
+
+struct StatementRow {
+    date: NaiveDateTime,
+    payment_id: Uuid,
+    title: String,
+    sender_wallet_address: String,
+    signature: String,
+    gross: u64,
+    fee: u64,
+    splits: u64,
+    net: u64,
+}
+
+pub struct StatementService;
+
+impl StatementService {
+    const STATEMENT: &'static str = "StatementExport";
+
+    /**
+     * Creates a statement of completed transfers for the merchant. Small statements are generated
+     * right away, larger ones in the background; either way the client polls `find_one` for the link.
+     */
+    pub async fn export(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_statement_dto: CreateStatementDto,
+    ) -> Result<StatementExportModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let (from, to) = (create_statement_dto.from, create_statement_dto.to);
+        if to <= from {
+            return Err(ServiceError::DtoError(
+                "Statement period must end after it starts".to_string(),
+            ));
+        }
+        if to - from > chrono::Duration::days(STATEMENT_MAX_RANGE_DAYS) {
+            return Err(ServiceError::DtoError(format!(
+                "Statement period can't exceed {} days",
+                STATEMENT_MAX_RANGE_DAYS
+            )));
+        }
+
+        let data = statement_export::ActiveModel {
+            user_id: Set(ctx.user_id),
+            format: Set(create_statement_dto.format),
+            status: Set(StatementStatus::Pending),
+            period_start: Set(from),
+            period_end: Set(to),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        let export = StatementExport::insert(data)
+            .exec_with_returning(state.db())
+            .await?;
+
+        let rows = Self::find_transfers(ctx.user_id, from, to)
+            .count(state.db())
+            .await?;
+
+        if rows <= STATEMENT_SYNC_MAX_ROWS {
+            return Self::generate(state, export, merchant).await;
+        }
+
+        let pending = export.clone();
+        tokio::spawn(async move {
+            let id = export.id;
+            if let Err(e) = Self::generate(state, export, merchant).await {
+                tracing::error!("Failed to generate statement {}: {:?}", id, e);
+            }
+        });
+
+        Ok(pending)
+    }
+
+    // Statement with a download link once it's completed
+    pub async fn find_one(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+    ) -> Result<(StatementExportModel, Option<String>)> {
+        let export = StatementExport::find_by_id(id)
+            .filter(statement_export::Column::UserId.eq(ctx.user_id))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::STATEMENT,
+                id: EntityId::Int(id),
+            })?;
+
+        let download_url = match &export.s3_key {
+            Some(key) if export.status == StatementStatus::Completed => Some(
+                state
+                    .storage
+                    .get_presigned_url(key, Duration::from_secs(STATEMENT_LINK_EXPIRY_SECS))
+                    .await?,
+            ),
+            _ => None,
+        };
+
+        Ok((export, download_url))
+    }
+
+    // Renders and uploads the statement, recording a failure on the export row
+    async fn generate(
+        state: Arc<AppState>,
+        export: StatementExportModel,
+        merchant: MerchantModel,
+    ) -> Result<StatementExportModel> {
+        let result = Self::render_and_upload(state.clone(), &export, &merchant).await;
+
+        let mut data: statement_export::ActiveModel = export.into();
+        match &result {
+            Ok(key) => {
+                data.status = Set(StatementStatus::Completed);
+                data.s3_key = Set(Some(key.clone()));
+                data.completed_at = Set(Some(Utc::now().naive_utc()));
+            }
+            // Details stay in the logs, the error is shown to the merchant
+            Err(_) => {
+                data.status = Set(StatementStatus::Failed);
+                data.error = Set(Some("Statement generation failed".to_string()));
+            }
+        }
+        let export = StatementExport::update(data).exec(state.db()).await?;
+
+        result.map(|_| export)
+    }
+
+    async fn render_and_upload(
+        state: Arc<AppState>,
+        export: &StatementExportModel,
+        merchant: &MerchantModel,
+    ) -> Result<String> {
+        let transfers =
+            Self::find_transfers(export.user_id, export.period_start, export.period_end)
+                .select_also(Payment)
+                .order_by_asc(transfer::Column::CreatedAt)
+                .all(state.db())
+                .await?;
+
+        let payment_ids = transfers
+            .iter()
+            .filter_map(|(_, payment)| payment.as_ref().map(|payment| payment.id))
+            .collect::<Vec<_>>();
+        let splits = PaymentSplit::find()
+            .filter(payment_split::Column::PaymentId.is_in(payment_ids))
+            .all(state.db())
+            .await?;
+        let transfer_ids = transfers
+            .iter()
+            .map(|(transfer, _)| transfer.id)
+            .collect::<Vec<_>>();
+        let escrows = Escrow::find()
+            .filter(escrow::Column::TransferId.is_in(transfer_ids))
+            .all(state.db())
+            .await?;
+
+        let mut rows = Vec::with_capacity(transfers.len());
+        for (transfer, payment) in transfers {
+            let Some(payment) = payment else { continue };
+
+            let gross = u64::try_from(transfer.amount)
+                .map_err(|_| ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+            let payment_splits = splits
+                .iter()
+                .filter(|split| split.payment_id == payment.id)
+                .cloned()
+                .collect::<Vec<_>>();
+            let escrow_status = payment.is_escrow.then(|| {
+                escrows
+                    .iter()
+                    .find(|escrow| escrow.transfer_id == transfer.id)
+                    .map(|escrow| escrow.status.clone())
+            });
+            let (fee, split_total, net) =
+                get_merchant_net(gross, &payment_splits, escrow_status.as_ref())?;
+
+            rows.push(StatementRow {
+                date: transfer.created_at,
+                payment_id: payment.public_id,
+                title: payment.title,
+                sender_wallet_address: transfer.sender_wallet_address,
+                signature: transfer.signature.unwrap_or_default(),
+                gross,
+                fee,
+                splits: split_total,
+                net,
+            });
+        }
+
+        let (file, mime_type, ext) = match export.format {
+            StatementFormat::Csv => (to_csv(&rows), "text/csv", "csv"),
+            StatementFormat::Pdf => (
+                pdf::render(&to_pdf_lines(export, merchant, &rows)),
+                "application/pdf",
+                "pdf",
+            ),
+        };
+
+        // A random name, statements must not be reachable by guessing the key
+        let folder = private_folder(&merchant.s3_bucket_slug, "statements");
+        state
+            .storage
+            .upload_file_as(&folder, &Bytes::from(file), None, mime_type, ext)
+            .await
+    }
+
+    // Completed transfers of the merchant's live payments within [from, to)
+    fn find_transfers(user_id: i32, from: NaiveDateTime, to: NaiveDateTime) -> Select<Transfer> {
+        Transfer::find()
+            .join(JoinType::InnerJoin, transfer::Relation::Payment.def())
+            .filter(payment::Column::UserId.eq(user_id))
+            .filter(payment::Column::Livemode.eq(true))
+            .filter(transfer::Column::Status.eq(TransferStatus::Completed))
+            .filter(transfer::Column::CreatedAt.gte(from))
+            .filter(transfer::Column::CreatedAt.lt(to))
+    }
+}
+
+/**
+ * Returns (fee, splits, net) of a transfer, net being what the merchant received. Escrow payments
+ * only pay the merchant and splits once released, a held or refunded escrow nets nothing.
+ */
+fn get_merchant_net(
+    gross: u64,
+    splits: &[PaymentSplitModel],
+    escrow_status: Option<&Option<EscrowStatus>>,
+) -> Result<(u64, u64, u64)> {
+    let (fee, amount_after_fee) = calculate_transfer_fee(gross)?;
+    if let Some(status) = escrow_status
+        && status != &Some(EscrowStatus::Released)
+    {
+        return Ok((fee, 0, 0));
+    }
+
+    let (split_legs, net) = get_split_amounts(amount_after_fee, splits)?;
+    let split_total = split_legs.iter().map(|leg| leg.amount).sum();
+
+    Ok((fee, split_total, net))
+}
+
+// Base units to a decimal USDC amount
+fn format_usdc(amount: u64) -> String {
+    format!("{}.{:06}", amount / BASE_USDC, amount % BASE_USDC)
+}
+
+// Also keeps spreadsheets from running cells that look like formulas
+fn escape_csv(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn to_csv(rows: &[StatementRow]) -> Vec<u8> {
+    let mut csv = String::from(
+        "date,payment_id,title,sender_wallet_address,signature,gross_usdc,treasury_fee_usdc,splits_usdc,net_usdc\n",
+    );
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            row.date.format("%Y-%m-%d %H:%M:%S"),
+            row.payment_id,
+            escape_csv(&row.title),
+            row.sender_wallet_address,
+            row.signature,
+            format_usdc(row.gross),
+            format_usdc(row.fee),
+            format_usdc(row.splits),
+            format_usdc(row.net),
+        ));
+    }
+
+    csv.into_bytes()
+}
+
+fn to_pdf_lines(
+    export: &StatementExportModel,
+    merchant: &MerchantModel,
+    rows: &[StatementRow],
+) -> Vec<String> {
+    let row_line =
+        |date: &str, gross: &str, fee: &str, splits: &str, net: &str, sender: &str, sig: &str| {
+            format!(
+                "{:<19}  {:>16}  {:>14}  {:>16}  {:>16}  {:<44}  {}",
+                date, gross, fee, splits, net, sender, sig
+            )
+        };
+
+    let mut lines = vec![
+        format!("Statement for {}", merchant.display_name),
+        format!(
+            "Period: {} to {}",
+            export.period_start.format("%Y-%m-%d %H:%M:%S"),
+            export.period_end.format("%Y-%m-%d %H:%M:%S")
+        ),
+        String::new(),
+        row_line(
+            "Date",
+            "Gross (USDC)",
+            "Fee (USDC)",
+            "Splits (USDC)",
+            "Net (USDC)",
+            "Sender wallet",
+            "Signature",
+        ),
+    ];
+
+    let (mut gross, mut fee, mut splits, mut net) = (0u64, 0u64, 0u64, 0u64);
+    for row in rows {
+        gross = gross.saturating_add(row.gross);
+        fee = fee.saturating_add(row.fee);
+        splits = splits.saturating_add(row.splits);
+        net = net.saturating_add(row.net);
+
+        lines.push(row_line(
+            &row.date.format("%Y-%m-%d %H:%M:%S").to_string(),
+            &format_usdc(row.gross),
+            &format_usdc(row.fee),
+            &format_usdc(row.splits),
+            &format_usdc(row.net),
+            &row.sender_wallet_address,
+            &row.signature,
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(row_line(
+        &format!("Total ({} transfers)", rows.len()),
+        &format_usdc(gross),
+        &format_usdc(fee),
+        &format_usdc(splits),
+        &format_usdc(net),
+        "",
+        "",
+    ));
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::BASE_USDC,
+        db::entity::{
+            payment_split::Model as PaymentSplitModel,
+            sea_orm_active_enums::{EscrowStatus, SplitType},
+        },
+        services::statement::{escape_csv, format_usdc, get_merchant_net, pdf},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_statement_formatting() -> Result<()> {
+        assert_eq!(format_usdc(12_345_600), "12.345600");
+        assert_eq!(format_usdc(990), "0.000990");
+        assert_eq!(escape_csv("Coffee, \"large\""), "\"Coffee, \"\"large\"\"\"");
+        assert_eq!(
+            escape_csv("=HYPERLINK(\"x\")"),
+            "\"'=HYPERLINK(\"\"x\"\")\""
+        );
+        assert_eq!(escape_csv("-10% off"), "'-10% off");
+
+        let lines = (0..200)
+            .map(|i| format!("line ({})", i))
+            .collect::<Vec<_>>();
+        let pdf = String::from_utf8(pdf::render(&lines))?;
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.contains("/Count 3"));
+        assert!(pdf.contains("(line \\(199\\)) Tj"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merchant_net() -> Result<()> {
+        let gross = 100 * BASE_USDC;
+        let splits = vec![PaymentSplitModel {
+            id: 1,
+            payment_id: 1,
+            wallet_address: "venue".to_string(),
+            split_type: SplitType::Fixed,
+            value: 9,
+        }];
+
+        assert_eq!(
+            get_merchant_net(gross, &[], None)?,
+            (BASE_USDC, 0, 99 * BASE_USDC)
+        );
+        assert_eq!(
+            get_merchant_net(gross, &splits, None)?,
+            (BASE_USDC, 9 * BASE_USDC, 90 * BASE_USDC)
+        );
+
+        // Escrow nets nothing until it's released
+        let released = Some(EscrowStatus::Released);
+        assert_eq!(
+            get_merchant_net(gross, &splits, Some(&released))?,
+            (BASE_USDC, 9 * BASE_USDC, 90 * BASE_USDC)
+        );
+        for status in [Some(EscrowStatus::Held), Some(EscrowStatus::Refunded), None] {
+            assert_eq!(
+                get_merchant_net(gross, &splits, Some(&status))?,
+                (BASE_USDC, 0, 0)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+/**
+ * Minimal PDF writer for text-only statements. Lines are laid out top to bottom in Courier on
+ * landscape A4 pages, so fixed-width columns stay aligned without font metrics.
+ */
+const PAGE_WIDTH: u32 = 842;
+const PAGE_HEIGHT: u32 = 595;
+const MARGIN: u32 = 20;
+const FONT_SIZE: u32 = 6;
+const LEADING: u32 = 8;
+
+pub fn render(lines: &[String]) -> Vec<u8> {
+    let lines_per_page = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+    let pages: Vec<&[String]> = if lines.is_empty() {
+        vec![&[]]
+    } else {
+        lines.chunks(lines_per_page).collect()
+    };
+
+    // Objects 1-3 are the catalog, page tree and font, each page then takes two objects
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + i * 2).collect();
+    let kids = page_ids
+        .iter()
+        .map(|id| format!("{} 0 R", id))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Courier >>".to_string(),
+    ];
+
+    for (page, page_id) in pages.iter().zip(&page_ids) {
+        let mut content = format!(
+            "BT /F1 {} Tf {} TL {} {} Td\n",
+            FONT_SIZE,
+            LEADING,
+            MARGIN,
+            PAGE_HEIGHT - MARGIN - FONT_SIZE
+        );
+        for line in page.iter() {
+            content.push_str(&format!("({}) Tj T*\n", escape(line)));
+        }
+        content.push_str("ET");
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            page_id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+
+    let xref_offset = pdf.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        xref.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    xref.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    ));
+    pdf.extend_from_slice(xref.as_bytes());
+
+    pdf
+}
+
+// Standard fonts only cover ASCII reliably, anything else is replaced
+fn escape(line: &str) -> String {
+    line.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        statement::{
+            StatementService,
+            dto::{
+                create_statement_dto::CreateStatementDto, statement_export_dto::StatementExportDto,
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn export(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_statement_dto): Json<CreateStatementDto>,
+) -> Result<Json<StatementExportDto>> {
+    let export = StatementService::export(state.clone(), ctx.clone(), create_statement_dto).await?;
+    let export = StatementService::find_one(state, ctx, export.id).await?;
+    Ok(Json(export.into()))
+}
+
+pub async fn find_one(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<StatementExportDto>> {
+    let export = StatementService::find_one(state, ctx, id).await?;
+    Ok(Json(export.into()))
+}
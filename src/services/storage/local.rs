@@ -0,0 +1,212 @@
+use std::{
+    io::ErrorKind,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use axum::{
+    body::Bytes,
+    extract::{Query, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use tokio::io::AsyncReadExt;
+
+use crate::{
+    constants::LOCAL_STORAGE_ROUTE,
+    services::{
+        error::{Result, ServiceError},
+        storage::{Storage, StoredObject, is_private, object_key},
+    },
+};
+
+/**
+ * Stores objects under a directory, served by `ServeDir` at `LOCAL_STORAGE_ROUTE`.
+ * Meant for development and tests. Private objects are only served with a link signed by
+ * `get_presigned_url`, checked by `mw_check_signature`.
+ */
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+    secret: String,
+}
+
+// Query of a signed link, `expires` is a unix timestamp
+#[derive(Debug, Deserialize)]
+pub struct SignedQuery {
+    expires: i64,
+    signature: String,
+}
+
+impl LocalStorage {
+    pub fn new(
+        root: impl Into<PathBuf>,
+        base_url: impl Into<String>,
+        secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into(),
+            secret: secret.into(),
+        }
+    }
+
+    // Keys are built by services, this only guards against escaping the root directory
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        let key = Path::new(key);
+        if !key
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(ServiceError::StorageError(format!(
+                "Invalid key {}",
+                key.display()
+            )));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+fn sign(secret: &str, key: &str, expires: i64) -> Result<Hmac<Sha256>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|_| ServiceError::Custom("Invalid storage secret".to_string()))?;
+    mac.update(format!("{}.{}", key, expires).as_bytes());
+    Ok(mac)
+}
+
+pub fn is_signed(secret: &str, key: &str, query: &SignedQuery) -> bool {
+    let Ok(signature) = URL_SAFE_NO_PAD.decode(&query.signature) else {
+        return false;
+    };
+
+    query.expires >= Utc::now().timestamp()
+        && sign(secret, key, query.expires).is_ok_and(|mac| mac.verify_slice(&signature).is_ok())
+}
+
+// Keys never need escaping, so encoded paths are refused rather than decoded and checked
+pub async fn mw_check_signature(
+    State(secret): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let key = path.trim_start_matches('/');
+    let allowed = !path.contains('%')
+        && (!is_private(key)
+            || Query::<SignedQuery>::try_from_uri(request.uri())
+                .is_ok_and(|Query(query)| is_signed(&secret, key, &query)));
+    if !allowed {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    next.run(request).await
+}
+
+pub fn public_url(base_url: &str, key: &str) -> String {
+    format!(
+        "{}{}/{}",
+        base_url.trim_end_matches('/'),
+        LOCAL_STORAGE_ROUTE,
+        key
+    )
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn upload_file_as(
+        &self,
+        s3_folder: &str,
+        file: &Bytes,
+        filename: Option<String>,
+        _mime_type: &str,
+        ext: &str,
+    ) -> Result<String> {
+        let key = object_key(s3_folder, filename, ext);
+        let path = self.path(&key)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ServiceError::StorageError(e.to_string()))?;
+        }
+        tokio::fs::write(&path, file)
+            .await
+            .map_err(|e| ServiceError::StorageError(e.to_string()))?;
+
+        Ok(key)
+    }
+
+    async fn get_presigned_url(&self, key: &str, expires_in: Duration) -> Result<String> {
+        self.path(key)?;
+        if !is_private(key) {
+            return Ok(self.get_public_url(key));
+        }
+
+        let expires = Utc::now().timestamp() + expires_in.as_secs() as i64;
+        let signature = sign(&self.secret, key, expires)?.finalize().into_bytes();
+        Ok(format!(
+            "{}?expires={}&signature={}",
+            self.get_public_url(key),
+            expires,
+            URL_SAFE_NO_PAD.encode(signature)
+        ))
+    }
+
+    async fn get_presigned_put_url(
+        &self,
+        _key: &str,
+        _content_type: &str,
+        _size: i64,
+        _expires_in: Duration,
+    ) -> Result<String> {
+        Err(ServiceError::StorageError(
+            "Direct uploads need the s3 storage backend".to_string(),
+        ))
+    }
+
+    async fn head_object(&self, key: &str) -> Result<Option<StoredObject>> {
+        match tokio::fs::metadata(self.path(key)?).await {
+            Ok(metadata) => Ok(Some(StoredObject {
+                size: metadata.len() as i64,
+                content_type: None,
+            })),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ServiceError::StorageError(e.to_string())),
+        }
+    }
+
+    async fn read_prefix(&self, key: &str, len: usize) -> Result<Bytes> {
+        let file = tokio::fs::File::open(self.path(key)?)
+            .await
+            .map_err(|e| ServiceError::StorageError(e.to_string()))?;
+
+        let mut prefix = Vec::with_capacity(len);
+        file.take(len as u64)
+            .read_to_end(&mut prefix)
+            .await
+            .map_err(|e| ServiceError::StorageError(e.to_string()))?;
+        Ok(Bytes::from(prefix))
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(ServiceError::StorageError(e.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn get_public_url(&self, key: &str) -> String {
+        public_url(&self.base_url, key)
+    }
+}
@@ -0,0 +1,206 @@
+pub mod local;
+pub mod s3;
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use strum_macros::{AsRefStr, EnumString};
+use uuid::Uuid;
+
+use crate::{
+    config::config,
+    constants::PRIVATE_STORAGE_PREFIX,
+    error::Error,
+    services::{
+        error::{Result, ServiceError},
+        storage::{local::LocalStorage, s3::S3Storage},
+    },
+};
+
+// Metadata of a stored object
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredObject {
+    pub size: i64,
+
+    // Not every backend keeps it
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum StorageBackend {
+    S3,
+    Local,
+}
+
+impl StorageBackend {
+    pub fn from_config() -> crate::error::Result<Self> {
+        config()
+            .STORAGE_BACKEND
+            .parse()
+            .map_err(|_| Error::EnvInvalid("SERVICE_STORAGE_BACKEND"))
+    }
+}
+
+/**
+ * Where uploaded files live. Keys are relative paths such as `merchant/coffee/cover/<uuid>`,
+ * the same for every backend so switching backends only needs the files copied over. Public
+ * files are linked by `get_public_url`, private ones under `private_folder` only by pre-signed
+ * URLs.
+ */
+#[async_trait]
+pub trait Storage: Send + Sync {
+    // Upload for content `infer` can't detect, such as text formats
+    async fn upload_file_as(
+        &self,
+        s3_folder: &str,
+        file: &Bytes,
+        filename: Option<String>,
+        mime_type: &str,
+        ext: &str,
+    ) -> Result<String>;
+
+    // Time limited download link for private objects
+    async fn get_presigned_url(&self, key: &str, expires_in: Duration) -> Result<String>;
+
+    // Lets the client upload straight to the backend, the size and content type are signed in
+    async fn get_presigned_put_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        size: i64,
+        expires_in: Duration,
+    ) -> Result<String>;
+
+    async fn head_object(&self, key: &str) -> Result<Option<StoredObject>>;
+
+    // First `len` bytes, enough to sniff the file type
+    async fn read_prefix(&self, key: &str, len: usize) -> Result<Bytes>;
+
+    async fn delete_object(&self, key: &str) -> Result<()>;
+
+    fn get_public_url(&self, key: &str) -> String;
+
+    async fn upload_file(
+        &self,
+        s3_folder: &str,
+        file: &Bytes,
+        filename: Option<String>,
+    ) -> Result<String> {
+        let kind = infer::get(file)
+            .ok_or_else(|| ServiceError::StorageError("Invalid file".to_string()))?;
+        self.upload_file_as(
+            s3_folder,
+            file,
+            filename,
+            kind.mime_type(),
+            kind.extension(),
+        )
+        .await
+    }
+}
+
+pub async fn new_storage() -> crate::error::Result<Arc<dyn Storage>> {
+    let storage: Arc<dyn Storage> = match StorageBackend::from_config()? {
+        StorageBackend::S3 => Arc::new(S3Storage::new().await),
+        StorageBackend::Local => Arc::new(LocalStorage::new(
+            &config().LOCAL_STORAGE_DIR,
+            &config().PUBLIC_URL,
+            &config().ACCESS_SECRET_KEY,
+        )),
+    };
+
+    Ok(storage)
+}
+
+// Same as the configured backend's `Storage::get_public_url`, for DTOs that have no state
+pub fn public_url(key: &str) -> String {
+    match StorageBackend::from_config() {
+        Ok(StorageBackend::Local) => local::public_url(&config().PUBLIC_URL, key),
+        _ => s3::public_url(&config().AWS_BUCKET_NAME, key),
+    }
+}
+
+// Folder only reachable through pre-signed URLs, e.g. `private/merchant/coffee/statements/`
+pub fn private_folder(owner_folder: &str, folder: &str) -> String {
+    format!("{}/{}/{}/", PRIVATE_STORAGE_PREFIX, owner_folder, folder)
+}
+
+// Only the top level folder counts, so a merchant slugged `private` stays public
+pub fn is_private(key: &str) -> bool {
+    key.split('/').next() == Some(PRIVATE_STORAGE_PREFIX)
+}
+
+fn object_key(folder: &str, filename: Option<String>, ext: &str) -> String {
+    match filename {
+        Some(name) => format!("{}/{}.{}", folder, name, ext),
+        None => format!("{}{}.{}", folder, Uuid::new_v4(), ext),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::services::storage::{
+        Storage, is_private,
+        local::{LocalStorage, SignedQuery, is_signed},
+        private_folder,
+    };
+    use anyhow::Result;
+    use axum::{body::Bytes, extract::Query, http::Uri};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_local_storage() -> Result<()> {
+        let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        let storage = LocalStorage::new(&root, "http://localhost:3000/", "secret");
+
+        let png = Bytes::from_static(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        let key = storage
+            .upload_file("merchant/coffee", &png, Some("cover".to_string()))
+            .await?;
+        assert_eq!(key, "merchant/coffee/cover.png");
+        assert_eq!(tokio::fs::read(root.join(&key)).await?, png.to_vec());
+        assert_eq!(
+            storage
+                .get_presigned_url(&key, Duration::from_secs(60))
+                .await?,
+            "http://localhost:3000/storage/merchant/coffee/cover.png"
+        );
+
+        let escaped = storage
+            .upload_file_as("../outside", &png, None, "image/png", "png")
+            .await;
+        assert!(escaped.is_err());
+
+        let statement = storage
+            .upload_file_as(
+                &private_folder("merchant/coffee", "statements"),
+                &png,
+                None,
+                "text/csv",
+                "csv",
+            )
+            .await?;
+        assert!(is_private(&statement));
+        let url: Uri = storage
+            .get_presigned_url(&statement, Duration::from_secs(60))
+            .await?
+            .parse()?;
+        let Query(query) = Query::<SignedQuery>::try_from_uri(&url)?;
+        assert!(is_signed("secret", &statement, &query));
+        assert!(!is_signed("other", &statement, &query));
+        assert!(!is_signed(
+            "secret",
+            "private/merchant/tea/statements/x.csv",
+            &query
+        ));
+        assert!(!is_private("merchant/documents/store/cover.png"));
+        assert!(!is_private("merchant/private/cover.png"));
+
+        tokio::fs::remove_dir_all(root).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use aws_config::{SdkConfig, meta::region::RegionProviderChain};
+use aws_sdk_s3::{
+    Client, config::Credentials, error::SdkError, presigning::PresigningConfig,
+    primitives::ByteStream,
+};
+use axum::body::Bytes;
+
+use crate::{
+    config::config,
+    services::{
+        error::{Result, ServiceError},
+        storage::{Storage, StoredObject, object_key},
+    },
+};
+
+pub struct S3Storage {
+    pub client: Client,
+    bucket: String,
+    config: SdkConfig,
+}
+
+impl S3Storage {
+    pub async fn new() -> Self {
+        let credentials = Credentials::new(
+            config().AWS_ACCESS_KEY_ID.clone(),
+            config().AWS_SECRET_ACCESS_KEY.clone(),
+            None,
+            None,
+            "aws-creds",
+        );
+
+        let bucket = config().AWS_BUCKET_NAME.clone();
+        let region = config().AWS_BUCKET_REGION.as_str();
+        let region_provider = RegionProviderChain::default_provider().or_else(region);
+
+        let config = aws_config::from_env()
+            .region(region_provider)
+            .credentials_provider(credentials)
+            .load()
+            .await;
+
+        let client = Client::new(&config);
+        Self {
+            client,
+            bucket,
+            config,
+        }
+    }
+}
+
+pub fn public_url(bucket: &str, key: &str) -> String {
+    format!("https://{}.s3.amazonaws.com/{}", bucket, key)
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn upload_file_as(
+        &self,
+        s3_folder: &str,
+        file: &Bytes,
+        filename: Option<String>,
+        mime_type: &str,
+        ext: &str,
+    ) -> Result<String> {
+        let key = object_key(s3_folder, filename, ext);
+
+        let builder = self
+            .client
+            .put_object()
+            .bucket(self.bucket.clone())
+            .body(ByteStream::from(file.clone()))
+            .key(&key)
+            .content_type(mime_type);
+
+        builder.send().await?;
+        Ok(key)
+    }
+
+    async fn get_presigned_url(&self, key: &str, expires_in: Duration) -> Result<String> {
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| ServiceError::S3Error(e.to_string()))?;
+
+        let request = self
+            .client
+            .get_object()
+            .bucket(self.bucket.clone())
+            .key(key)
+            .presigned(presigning_config)
+            .await?;
+
+        Ok(request.uri().to_string())
+    }
+
+    async fn get_presigned_put_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        size: i64,
+        expires_in: Duration,
+    ) -> Result<String> {
+        let presigning_config = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| ServiceError::S3Error(e.to_string()))?;
+
+        let request = self
+            .client
+            .put_object()
+            .bucket(self.bucket.clone())
+            .key(key)
+            .content_type(content_type)
+            .content_length(size)
+            .presigned(presigning_config)
+            .await?;
+
+        Ok(request.uri().to_string())
+    }
+
+    async fn head_object(&self, key: &str) -> Result<Option<StoredObject>> {
+        let output = self
+            .client
+            .head_object()
+            .bucket(self.bucket.clone())
+            .key(key)
+            .send()
+            .await;
+
+        match output {
+            Ok(output) => Ok(Some(StoredObject {
+                size: output.content_length().unwrap_or_default(),
+                content_type: output.content_type().map(|val| val.to_string()),
+            })),
+            Err(SdkError::ServiceError(e)) if e.err().is_not_found() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn read_prefix(&self, key: &str, len: usize) -> Result<Bytes> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(self.bucket.clone())
+            .key(key)
+            .range(format!("bytes=0-{}", len.saturating_sub(1)))
+            .send()
+            .await?;
+
+        let body = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| ServiceError::S3Error(e.to_string()))?;
+        Ok(body.into_bytes())
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(self.bucket.clone())
+            .key(key)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    fn get_public_url(&self, key: &str) -> String {
+        public_url(&self.bucket, key)
+    }
+}
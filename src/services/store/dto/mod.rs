@@ -0,0 +1,3 @@
+pub mod opening_hours_dto;
+pub mod store_dto;
+pub mod store_form_dto;
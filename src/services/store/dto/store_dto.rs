@@ -0,0 +1,44 @@
+use serde::Serialize;
+
+use crate::{
+    db::entity::store::Model as StoreModel,
+    services::{
+        media::dto::image_variants_dto::ImageVariantsDto,
+        store::dto::opening_hours_dto::OpeningHoursDto,
+    },
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreDto {
+    pub id: i32,
+
+    pub name: String,
+
+    pub address: String,
+
+    pub latitude: Option<f64>,
+
+    pub longitude: Option<f64>,
+
+    pub opening_hours: Vec<OpeningHoursDto>,
+
+    pub cover: Option<ImageVariantsDto>,
+}
+
+impl From<StoreModel> for StoreDto {
+    fn from(value: StoreModel) -> Self {
+        StoreDto {
+            id: value.id,
+            name: value.name,
+            address: value.address,
+            latitude: value.latitude,
+            longitude: value.longitude,
+            // Only ever written from validated opening hours
+            opening_hours: serde_json::from_value(value.opening_hours).unwrap_or_default(),
+            cover: value
+                .cover
+                .map(|cover_key| ImageVariantsDto::from_key(&cover_key)),
+        }
+    }
+}
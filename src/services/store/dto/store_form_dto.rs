@@ -0,0 +1,23 @@
+use crate::{
+    constants::IMAGE_MAX_SIZE_BYTES, services::store::dto::opening_hours_dto::OpeningHoursDto,
+};
+use axum::body::Bytes;
+use macros::FromMultipart;
+
+// Fields left out of an update keep their current value
+#[derive(FromMultipart)]
+pub struct StoreFormDto {
+    pub name: Option<String>,
+
+    pub address: Option<String>,
+
+    pub latitude: Option<f64>,
+
+    pub longitude: Option<f64>,
+
+    #[multipart(json)]
+    pub opening_hours: Option<Vec<OpeningHoursDto>>,
+
+    #[multipart(limit = IMAGE_MAX_SIZE_BYTES)]
+    pub cover: Option<Bytes>,
+}
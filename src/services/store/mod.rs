@@ -0,0 +1,283 @@
+pub mod dto;
+pub mod store_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    db::entity::{
+        merchant::Model as MerchantModel,
+        prelude::Store,
+        store::{self, Model as StoreModel},
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        media::MediaService,
+        store::dto::{opening_hours_dto::OpeningHoursDto, store_form_dto::StoreFormDto},
+        user::UserService,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder,
+};
+
+pub struct StoreService;
+
+impl StoreService {
+    const STORE: &'static str = "Store";
+
+    pub async fn find_merchant_stores(
+        state: Arc<AppState>,
+        merchant_id: i32,
+    ) -> Result<Vec<StoreModel>> {
+        let stores = Store::find()
+            .filter(store::Column::MerchantId.eq(merchant_id))
+            .order_by_asc(store::Column::Name)
+            .all(state.db())
+            .await?;
+
+        Ok(stores)
+    }
+
+    pub async fn find_all(state: Arc<AppState>, ctx: Ctx) -> Result<Vec<StoreModel>> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        Self::find_merchant_stores(state, merchant.id).await
+    }
+
+    pub async fn create(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        store_form_dto: StoreFormDto,
+    ) -> Result<StoreModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+
+        let (name, address) = match (store_form_dto.name, store_form_dto.address) {
+            (Some(name), Some(address)) => (name.trim().to_string(), address),
+            _ => {
+                return Err(ServiceError::DtoError(
+                    "Missing one or more required fields".into(),
+                ));
+            }
+        };
+        validate_name(&name)?;
+        validate_coordinates(store_form_dto.latitude, store_form_dto.longitude)?;
+        let opening_hours = store_form_dto.opening_hours.unwrap_or_default();
+        validate_opening_hours(&opening_hours)?;
+        Self::check_name_available(state.db(), merchant.id, &name, None).await?;
+
+        let cover = match store_form_dto.cover {
+            Some(cover) => Some(Self::upload_cover(state.clone(), &merchant, cover).await?),
+            None => None,
+        };
+
+        let data = store::ActiveModel {
+            merchant_id: Set(merchant.id),
+            name: Set(name),
+            address: Set(address),
+            latitude: Set(store_form_dto.latitude),
+            longitude: Set(store_form_dto.longitude),
+            opening_hours: Set(serde_json::to_value(opening_hours)?),
+            cover: Set(cover.clone()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        let store = Store::insert(data).exec_with_returning(state.db()).await;
+
+        // The cover is uploaded first, it goes away again when the store can't be saved
+        if let (Err(_), Some(cover)) = (&store, &cover) {
+            MediaService::delete_image(state.storage.as_ref(), cover).await;
+        }
+
+        Ok(store?)
+    }
+
+    pub async fn update(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+        store_form_dto: StoreFormDto,
+    ) -> Result<StoreModel> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let store = Self::find_merchant_store(state.db(), merchant.id, id).await?;
+
+        // Coordinates are only ever updated together
+        let (latitude, longitude) = match (store_form_dto.latitude, store_form_dto.longitude) {
+            (None, None) => (store.latitude, store.longitude),
+            coordinates => coordinates,
+        };
+        validate_coordinates(latitude, longitude)?;
+
+        let previous_cover = store.cover.clone();
+        let mut data: store::ActiveModel = store.into();
+        if let Some(name) = store_form_dto.name {
+            let name = name.trim().to_string();
+            validate_name(&name)?;
+            Self::check_name_available(state.db(), merchant.id, &name, Some(id)).await?;
+            data.name = Set(name);
+        }
+        if let Some(address) = store_form_dto.address {
+            data.address = Set(address);
+        }
+        data.latitude = Set(latitude);
+        data.longitude = Set(longitude);
+        if let Some(opening_hours) = store_form_dto.opening_hours {
+            validate_opening_hours(&opening_hours)?;
+            data.opening_hours = Set(serde_json::to_value(opening_hours)?);
+        }
+        let uploaded_cover = match store_form_dto.cover {
+            Some(cover) => {
+                let cover = Self::upload_cover(state.clone(), &merchant, cover).await?;
+                data.cover = Set(Some(cover.clone()));
+                Some(cover)
+            }
+            None => None,
+        };
+        let store = Store::update(data).exec(state.db()).await;
+
+        // Whichever of the new and the replaced cover ends up unused is deleted
+        let unused_cover = match store {
+            Ok(_) => uploaded_cover.and(previous_cover),
+            Err(_) => uploaded_cover,
+        };
+        if let Some(cover) = unused_cover {
+            MediaService::delete_image(state.storage.as_ref(), &cover).await;
+        }
+        let store = store?;
+
+        Ok(store)
+    }
+
+    // Payments taken at the store are kept, they just lose the store
+    pub async fn remove(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<()> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let store = Self::find_merchant_store(state.db(), merchant.id, id).await?;
+        let cover = store.cover.clone();
+        store.delete(state.db()).await?;
+
+        if let Some(cover) = cover {
+            MediaService::delete_image(state.storage.as_ref(), &cover).await;
+        }
+
+        Ok(())
+    }
+
+    pub(in crate::services) async fn find_merchant_store<C: ConnectionTrait>(
+        db: &C,
+        merchant_id: i32,
+        id: i32,
+    ) -> Result<StoreModel> {
+        let store = Store::find_by_id(id)
+            .filter(store::Column::MerchantId.eq(merchant_id))
+            .one(db)
+            .await?;
+
+        store.ok_or(ServiceError::EntityNotFound {
+            entity: Self::STORE,
+            id: EntityId::Int(id),
+        })
+    }
+
+    async fn check_name_available<C: ConnectionTrait>(
+        db: &C,
+        merchant_id: i32,
+        name: &str,
+        except_id: Option<i32>,
+    ) -> Result<()> {
+        let mut select = Store::find()
+            .filter(store::Column::MerchantId.eq(merchant_id))
+            .filter(store::Column::Name.eq(name));
+        if let Some(id) = except_id {
+            select = select.filter(store::Column::Id.ne(id));
+        }
+
+        if select.one(db).await?.is_some() {
+            return Err(ServiceError::DtoError(
+                "Merchant already has a store with this name".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn upload_cover(
+        state: Arc<AppState>,
+        merchant: &MerchantModel,
+        cover: axum::body::Bytes,
+    ) -> Result<String> {
+        let s3_folder = format!("{}/store", merchant.s3_bucket_slug);
+        MediaService::upload_image(state.storage.as_ref(), &s3_folder, cover).await
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(ServiceError::DtoError(
+            "Store name must be between 1 and 100 characters".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+pub(in crate::services) fn validate_coordinates(
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    match (latitude, longitude) {
+        (None, None) => Ok(()),
+        (Some(latitude), Some(longitude))
+            if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) =>
+        {
+            Ok(())
+        }
+        (Some(_), Some(_)) => Err(ServiceError::DtoError(
+            "Coordinates are out of range".into(),
+        )),
+        _ => Err(ServiceError::DtoError(
+            "Latitude and longitude must be set together".into(),
+        )),
+    }
+}
+
+fn validate_opening_hours(opening_hours: &[OpeningHoursDto]) -> Result<()> {
+    if opening_hours
+        .iter()
+        .any(|opening_hours| opening_hours.opens == opening_hours.closes)
+    {
+        return Err(ServiceError::DtoError(
+            "Opening and closing times must differ".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::services::store::{
+        dto::opening_hours_dto::OpeningHoursDto, validate_coordinates, validate_opening_hours,
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_store_validation() -> Result<()> {
+        assert!(validate_coordinates(Some(-33.86), Some(151.2)).is_ok());
+        assert!(validate_coordinates(None, None).is_ok());
+        assert!(validate_coordinates(Some(91.0), Some(0.0)).is_err());
+        assert!(validate_coordinates(Some(10.0), None).is_err());
+
+        let opening_hours: Vec<OpeningHoursDto> = serde_json::from_str(
+            r#"[{"day":"monday","opens":"09:00:00","closes":"17:00:00"},
+                {"day":"friday","opens":"18:00:00","closes":"02:00:00"}]"#,
+        )?;
+        validate_opening_hours(&opening_hours)?;
+        let closed: Vec<OpeningHoursDto> =
+            serde_json::from_str(r#"[{"day":"sunday","opens":"09:00:00","closes":"09:00:00"}]"#)?;
+        assert!(validate_opening_hours(&closed).is_err());
+
+        Ok(())
+    }
+}
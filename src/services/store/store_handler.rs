@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        store::{
+            StoreService,
+            dto::{store_dto::StoreDto, store_form_dto::StoreFormDto},
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+pub async fn find_all(State(state): State<Arc<AppState>>, ctx: Ctx) -> Result<Json<Vec<StoreDto>>> {
+    let stores = StoreService::find_all(state, ctx).await?;
+    Ok(Json(stores.into_iter().map(|val| val.into()).collect()))
+}
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    store_form_dto: StoreFormDto,
+) -> Result<Json<StoreDto>> {
+    let store = StoreService::create(state, ctx, store_form_dto).await?;
+    Ok(Json(store.into()))
+}
+
+pub async fn update(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+    store_form_dto: StoreFormDto,
+) -> Result<Json<StoreDto>> {
+    let store = StoreService::update(state, ctx, id, store_form_dto).await?;
+    Ok(Json(store.into()))
+}
+
+pub async fn remove(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<()> {
+    StoreService::remove(state, ctx, id).await?;
+    Ok(())
+}
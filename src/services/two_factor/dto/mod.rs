@@ -0,0 +1 @@
+pub mod two_factor_dto;
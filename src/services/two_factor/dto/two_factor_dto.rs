@@ -0,0 +1,59 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeDto {
+    // Current TOTP code, or an unused recovery code where allowed
+    pub code: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorSetupDto {
+    // Base32 secret for manual entry in the authenticator app
+    pub secret: String,
+
+    pub otpauth_uri: String,
+}
+
+impl From<(String, String)> for TwoFactorSetupDto {
+    fn from(value: (String, String)) -> Self {
+        let (secret, otpauth_uri) = value;
+        TwoFactorSetupDto {
+            secret,
+            otpauth_uri,
+        }
+    }
+}
+
+// Only returned once, the codes are stored hashed
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
+
+impl From<Vec<String>> for RecoveryCodesDto {
+    fn from(recovery_codes: Vec<String>) -> Self {
+        RecoveryCodesDto { recovery_codes }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepUpTokenDto {
+    pub step_up_token: String,
+
+    pub expires_at: NaiveDateTime,
+}
+
+impl From<(String, NaiveDateTime)> for StepUpTokenDto {
+    fn from(value: (String, NaiveDateTime)) -> Self {
+        let (step_up_token, expires_at) = value;
+        StepUpTokenDto {
+            step_up_token,
+            expires_at,
+        }
+    }
+}
@@ -0,0 +1,501 @@
+pub mod dto;
+pub mod two_factor_handler;
+
+use std::sync::Arc;
+
+use crate::{
+    config::config,
+    constants::{
+        RECOVERY_CODE_COUNT, STEP_UP_TOKEN_EXPIRY_SECS, TOTP_ISSUER, TOTP_STEP_SECS,
+        TWO_FACTOR_CHALLENGE_EXPIRY_SECS, TWO_FACTOR_LOCKOUT_SECS, TWO_FACTOR_MAX_ATTEMPTS,
+    },
+    ctx::Ctx,
+    db::entity::{
+        prelude::{RecoveryCode, User},
+        recovery_code,
+        user::{self, Model as UserModel},
+    },
+    services::{
+        AppState,
+        auth::dto::authorization_dto::PurposeClaims,
+        error::{Result, ServiceError},
+        two_factor::dto::two_factor_dto::TwoFactorCodeDto,
+    },
+};
+use aes_gcm::{Aes256Gcm, KeyInit, aead::Aead};
+use base64::{Engine, engine::general_purpose};
+use chrono::{NaiveDateTime, Utc};
+use jsonwebtoken::{Algorithm as JwtAlgorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::Rng;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QuerySelect, TransactionTrait,
+};
+use sha2::{Digest, Sha256, digest::generic_array::GenericArray};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+// Which codes a verification accepts
+#[derive(Clone, Copy, PartialEq)]
+enum CodeKind {
+    // TOTP of the not yet confirmed secret
+    Enrollment,
+    // TOTP or a recovery code once two factor is on
+    Any,
+}
+
+pub struct TwoFactorService;
+
+impl TwoFactorService {
+    const CHALLENGE_AUDIENCE: &'static str = "two-factor-login";
+    const STEP_UP_AUDIENCE: &'static str = "step-up";
+
+    /**
+     * Starts enrollment with a fresh secret, replacing any unconfirmed one. Two factor only turns on
+     * once a code from the authenticator app is confirmed.
+     */
+    pub async fn setup(state: Arc<AppState>, ctx: Ctx) -> Result<(String, String)> {
+        let user = User::find_by_id(ctx.user_id)
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::UserNotFound)?;
+        if user.totp_enabled_at.is_some() {
+            return Err(ServiceError::DtoError(
+                "Two factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let secret = Secret::generate_secret()
+            .to_bytes()
+            .map_err(|e| ServiceError::Custom(e.to_string()))?;
+        let totp = build_totp(secret.clone(), &user)?;
+
+        let mut data: user::ActiveModel = user.into();
+        data.totp_secret = Set(Some(encrypt_secret(&config().TWO_FACTOR_SECRET, &secret)?));
+        data.totp_last_step = Set(None);
+        User::update(data).exec(state.db()).await?;
+
+        Ok((totp.get_secret_base32(), totp.get_url()))
+    }
+
+    // Turns two factor on with a code of the new secret, returning the recovery codes
+    pub async fn confirm(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        two_factor_code_dto: TwoFactorCodeDto,
+    ) -> Result<Vec<String>> {
+        let user = Self::verify_code(
+            state.db(),
+            ctx.user_id,
+            &two_factor_code_dto.code,
+            CodeKind::Enrollment,
+        )
+        .await?;
+
+        let txn = state.db().begin().await?;
+        let mut data: user::ActiveModel = user.into();
+        data.totp_enabled_at = Set(Some(Utc::now().naive_utc()));
+        User::update(data).exec(&txn).await?;
+
+        let recovery_codes = Self::replace_recovery_codes(&txn, ctx.user_id).await?;
+        txn.commit().await?;
+
+        Ok(recovery_codes)
+    }
+
+    pub async fn disable(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        two_factor_code_dto: TwoFactorCodeDto,
+    ) -> Result<()> {
+        let user = Self::verify_code(
+            state.db(),
+            ctx.user_id,
+            &two_factor_code_dto.code,
+            CodeKind::Any,
+        )
+        .await?;
+
+        let txn = state.db().begin().await?;
+        let mut data: user::ActiveModel = user.into();
+        data.totp_secret = Set(None);
+        data.totp_enabled_at = Set(None);
+        data.totp_last_step = Set(None);
+        User::update(data).exec(&txn).await?;
+
+        RecoveryCode::delete_many()
+            .filter(recovery_code::Column::UserId.eq(ctx.user_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(())
+    }
+
+    // Invalidates the remaining recovery codes and issues a new set
+    pub async fn regenerate_recovery_codes(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        two_factor_code_dto: TwoFactorCodeDto,
+    ) -> Result<Vec<String>> {
+        Self::verify_code(
+            state.db(),
+            ctx.user_id,
+            &two_factor_code_dto.code,
+            CodeKind::Any,
+        )
+        .await?;
+
+        let txn = state.db().begin().await?;
+        let recovery_codes = Self::replace_recovery_codes(&txn, ctx.user_id).await?;
+        txn.commit().await?;
+
+        Ok(recovery_codes)
+    }
+
+    // Short lived token sent with sensitive requests, see `mw_require_step_up`
+    pub async fn create_step_up_token(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        two_factor_code_dto: TwoFactorCodeDto,
+    ) -> Result<(String, NaiveDateTime)> {
+        Self::verify_code(
+            state.db(),
+            ctx.user_id,
+            &two_factor_code_dto.code,
+            CodeKind::Any,
+        )
+        .await?;
+
+        create_purpose_token(
+            ctx.user_id,
+            Self::STEP_UP_AUDIENCE,
+            STEP_UP_TOKEN_EXPIRY_SECS,
+        )
+    }
+
+    pub fn verify_step_up_token(user_id: i32, step_up_token: &str) -> Result<()> {
+        let token_user_id = verify_purpose_token(step_up_token, Self::STEP_UP_AUDIENCE)?;
+        if token_user_id != user_id {
+            return Err(ServiceError::PermissionDenied);
+        }
+
+        Ok(())
+    }
+
+    // First step of a password login for users with two factor on
+    pub fn create_challenge_token(user_id: i32) -> Result<(String, NaiveDateTime)> {
+        create_purpose_token(
+            user_id,
+            Self::CHALLENGE_AUDIENCE,
+            TWO_FACTOR_CHALLENGE_EXPIRY_SECS,
+        )
+    }
+
+    // Second step of the login, returns the user the challenge was issued for
+    pub async fn verify_challenge(
+        state: Arc<AppState>,
+        challenge_token: &str,
+        code: &str,
+    ) -> Result<UserModel> {
+        let user_id = verify_purpose_token(challenge_token, Self::CHALLENGE_AUDIENCE)?;
+
+        Self::verify_code(state.db(), user_id, code, CodeKind::Any).await
+    }
+
+    /**
+     * Checks the code with the user row locked, so a TOTP step or recovery code is only accepted
+     * once. Failed attempts are counted and lock two factor for a while after too many, so this runs
+     * in its own transaction that is committed on failure too.
+     */
+    async fn verify_code(
+        db: &DatabaseConnection,
+        user_id: i32,
+        code: &str,
+        kind: CodeKind,
+    ) -> Result<UserModel> {
+        let now = Utc::now().naive_utc();
+        let txn = db.begin().await?;
+
+        let user = User::find_by_id(user_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(ServiceError::UserNotFound)?;
+
+        if user
+            .two_factor_locked_until
+            .is_some_and(|until| until > now)
+        {
+            return Err(ServiceError::DtoError(
+                "Too many failed two factor attempts, try again later".to_string(),
+            ));
+        }
+
+        let is_enabled = user.totp_enabled_at.is_some();
+        let secret = match (&user.totp_secret, kind) {
+            (Some(secret), CodeKind::Enrollment) if !is_enabled => secret,
+            (Some(secret), CodeKind::Any) if is_enabled => secret,
+            (_, CodeKind::Enrollment) => {
+                return Err(ServiceError::DtoError(
+                    "No two factor enrollment in progress".to_string(),
+                ));
+            }
+            _ => {
+                return Err(ServiceError::DtoError(
+                    "Two factor authentication is not enabled".to_string(),
+                ));
+            }
+        };
+
+        let code = code.trim();
+        let mut data: user::ActiveModel = user.clone().into();
+
+        let is_verified = if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            let secret = decrypt_secret(&config().TWO_FACTOR_SECRET, secret)?;
+            let totp = build_totp(secret, &user)?;
+            let step = matching_step(&totp, code, Utc::now().timestamp() as u64)
+                .filter(|step| user.totp_last_step.is_none_or(|last| *step > last));
+
+            if let Some(step) = step {
+                data.totp_last_step = Set(Some(step));
+            }
+            step.is_some()
+        } else if kind == CodeKind::Any {
+            let recovery_code = RecoveryCode::find()
+                .filter(recovery_code::Column::UserId.eq(user_id))
+                .filter(recovery_code::Column::CodeHash.eq(hash_recovery_code(code)))
+                .filter(recovery_code::Column::UsedAt.is_null())
+                .one(&txn)
+                .await?;
+
+            if let Some(recovery_code) = &recovery_code {
+                let mut used: recovery_code::ActiveModel = recovery_code.clone().into();
+                used.used_at = Set(Some(now));
+                RecoveryCode::update(used).exec(&txn).await?;
+            }
+            recovery_code.is_some()
+        } else {
+            false
+        };
+
+        if is_verified {
+            data.two_factor_failed_attempts = Set(0);
+            data.two_factor_locked_until = Set(None);
+        } else if user.two_factor_failed_attempts + 1 >= TWO_FACTOR_MAX_ATTEMPTS {
+            data.two_factor_failed_attempts = Set(0);
+            data.two_factor_locked_until = Set(Some(
+                now + chrono::Duration::seconds(TWO_FACTOR_LOCKOUT_SECS),
+            ));
+        } else {
+            data.two_factor_failed_attempts = Set(user.two_factor_failed_attempts + 1);
+        }
+
+        let user = User::update(data).exec(&txn).await?;
+        txn.commit().await?;
+
+        if !is_verified {
+            return Err(ServiceError::DtoError(
+                "Invalid two factor code".to_string(),
+            ));
+        }
+
+        Ok(user)
+    }
+
+    async fn replace_recovery_codes<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<String>> {
+        RecoveryCode::delete_many()
+            .filter(recovery_code::Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+
+        let now = Utc::now().naive_utc();
+        let recovery_codes = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect::<Vec<_>>();
+
+        let data = recovery_codes
+            .iter()
+            .map(|code| recovery_code::ActiveModel {
+                user_id: Set(user_id),
+                code_hash: Set(hash_recovery_code(code)),
+                created_at: Set(now),
+                ..Default::default()
+            });
+        RecoveryCode::insert_many(data).exec(db).await?;
+
+        Ok(recovery_codes)
+    }
+}
+
+fn build_totp(secret: Vec<u8>, user: &UserModel) -> Result<TOTP> {
+    let account_name = user
+        .email
+        .clone()
+        .unwrap_or_else(|| format!("user-{}", user.id));
+
+    // Skew is handled in `matching_step` so the accepted step can be recorded
+    TOTP::new(
+        Algorithm::SHA1,
+        6,
+        0,
+        TOTP_STEP_SECS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name,
+    )
+    .map_err(|e| ServiceError::Custom(e.to_string()))
+}
+
+// Time step the code belongs to, allowing one step of clock drift either way
+fn matching_step(totp: &TOTP, code: &str, now: u64) -> Option<i64> {
+    let current = now / TOTP_STEP_SECS;
+    [current.saturating_sub(1), current, current + 1]
+        .into_iter()
+        .find(|step| totp.check(code, step * TOTP_STEP_SECS))
+        .and_then(|step| step.try_into().ok())
+}
+
+// Ten random characters shown as `xxxxx-xxxxx`, without look-alike characters
+fn generate_recovery_code() -> String {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let mut rng = rand::rng();
+    let code = (0..10)
+        .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+        .collect::<String>();
+
+    format!("{}-{}", &code[..5], &code[5..])
+}
+
+// Recovery codes are random enough that a plain hash is sufficient
+fn hash_recovery_code(code: &str) -> String {
+    let normalized = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+
+    general_purpose::STANDARD.encode(Sha256::digest(normalized.as_bytes()))
+}
+
+fn encrypt_secret(key: &str, secret: &[u8]) -> Result<String> {
+    let cipher = Aes256Gcm::new_from_slice(&Sha256::digest(key.as_bytes()))
+        .map_err(|_| ServiceError::Custom("Failed to create AES secret".to_string()))?;
+
+    let mut nonce = [0u8; 12];
+    rand::rng().fill(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(GenericArray::from_slice(&nonce), secret)
+        .map_err(|_| ServiceError::Custom("Failed to encrypt TOTP secret".to_string()))?;
+
+    let mut result = nonce.to_vec();
+    result.extend_from_slice(&ciphertext);
+    Ok(general_purpose::STANDARD.encode(result))
+}
+
+fn decrypt_secret(key: &str, encrypted: &str) -> Result<Vec<u8>> {
+    let invalid = || ServiceError::Custom("Failed to decrypt TOTP secret".to_string());
+
+    let bytes = general_purpose::STANDARD
+        .decode(encrypted)
+        .map_err(|_| invalid())?;
+    let (nonce, ciphertext) = bytes.split_at_checked(12).ok_or_else(invalid)?;
+
+    let cipher = Aes256Gcm::new_from_slice(&Sha256::digest(key.as_bytes()))
+        .map_err(|_| ServiceError::Custom("Failed to create AES secret".to_string()))?;
+    cipher
+        .decrypt(GenericArray::from_slice(nonce), ciphertext)
+        .map_err(|_| invalid())
+}
+
+fn create_purpose_token(
+    user_id: i32,
+    audience: &str,
+    expiry_secs: i64,
+) -> Result<(String, NaiveDateTime)> {
+    let now = Utc::now();
+    let expires_at = now + chrono::Duration::seconds(expiry_secs);
+    let claims = PurposeClaims {
+        sub: user_id.to_string(),
+        aud: audience.to_string(),
+        iat: now.timestamp() as usize,
+        exp: expires_at.timestamp() as usize,
+    };
+
+    let token = jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config().ACCESS_SECRET_KEY.as_bytes()),
+    )?;
+
+    Ok((token, expires_at.naive_utc()))
+}
+
+// Returns the user id the token was issued for
+fn verify_purpose_token(token: &str, audience: &str) -> Result<i32> {
+    let mut validation = Validation::new(JwtAlgorithm::HS256);
+    validation.set_audience(&[audience]);
+    validation.set_required_spec_claims(&["exp", "aud", "sub"]);
+
+    let claims = jsonwebtoken::decode::<PurposeClaims>(
+        token,
+        &DecodingKey::from_secret(config().ACCESS_SECRET_KEY.as_bytes()),
+        &validation,
+    )?
+    .claims;
+
+    claims
+        .sub
+        .parse()
+        .map_err(|_| ServiceError::Custom("Invalid token subject".to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::services::two_factor::{
+        decrypt_secret, encrypt_secret, generate_recovery_code, hash_recovery_code, matching_step,
+    };
+    use anyhow::Result;
+    use totp_rs::{Algorithm, TOTP};
+
+    #[test]
+    fn test_totp_and_recovery_codes() -> Result<()> {
+        // RFC 6238 SHA1 test secret
+        let secret = b"12345678901234567890".to_vec();
+        let totp = TOTP::new(
+            Algorithm::SHA1,
+            6,
+            0,
+            30,
+            secret.clone(),
+            None,
+            "test".to_string(),
+        )?;
+
+        let now = 1_111_111_109;
+        let code = totp.generate(now);
+        assert_eq!(code, "081804");
+        assert_eq!(matching_step(&totp, &code, now), Some(37_037_036));
+        // One step of drift is accepted, two are not
+        assert_eq!(matching_step(&totp, &code, now + 30), Some(37_037_036));
+        assert_eq!(matching_step(&totp, &code, now + 60), None);
+
+        let encrypted = encrypt_secret("key", &secret)?;
+        assert_eq!(decrypt_secret("key", &encrypted)?, secret);
+        assert!(decrypt_secret("other key", &encrypted).is_err());
+
+        let recovery_code = generate_recovery_code();
+        assert_eq!(recovery_code.len(), 11);
+        assert_eq!(
+            hash_recovery_code(&recovery_code),
+            hash_recovery_code(&format!(
+                " {} ",
+                recovery_code.replace('-', "").to_uppercase()
+            ))
+        );
+        Ok(())
+    }
+}
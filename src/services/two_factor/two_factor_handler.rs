@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        two_factor::{
+            TwoFactorService,
+            dto::two_factor_dto::{
+                RecoveryCodesDto, StepUpTokenDto, TwoFactorCodeDto, TwoFactorSetupDto,
+            },
+        },
+    },
+};
+use axum::{Json, extract::State};
+
+pub async fn setup(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<TwoFactorSetupDto>> {
+    let setup = TwoFactorService::setup(state, ctx).await?;
+    Ok(Json(setup.into()))
+}
+
+pub async fn confirm(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(two_factor_code_dto): Json<TwoFactorCodeDto>,
+) -> Result<Json<RecoveryCodesDto>> {
+    let recovery_codes = TwoFactorService::confirm(state, ctx, two_factor_code_dto).await?;
+    Ok(Json(recovery_codes.into()))
+}
+
+pub async fn disable(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(two_factor_code_dto): Json<TwoFactorCodeDto>,
+) -> Result<()> {
+    TwoFactorService::disable(state, ctx, two_factor_code_dto).await
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(two_factor_code_dto): Json<TwoFactorCodeDto>,
+) -> Result<Json<RecoveryCodesDto>> {
+    let recovery_codes =
+        TwoFactorService::regenerate_recovery_codes(state, ctx, two_factor_code_dto).await?;
+    Ok(Json(recovery_codes.into()))
+}
+
+pub async fn create_step_up_token(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(two_factor_code_dto): Json<TwoFactorCodeDto>,
+) -> Result<Json<StepUpTokenDto>> {
+    let step_up_token =
+        TwoFactorService::create_step_up_token(state, ctx, two_factor_code_dto).await?;
+    Ok(Json(step_up_token.into()))
+}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{constants::UPLOAD_MAX_SIZE_BYTES, db::entity::sea_orm_active_enums::UploadPurpose};
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUploadDto {
+    pub purpose: UploadPurpose,
+
+    pub content_type: String,
+
+    // Bytes, the upload has to match it exactly
+    #[validate(range(min = 1, max = UPLOAD_MAX_SIZE_BYTES))]
+    pub size: i64,
+}
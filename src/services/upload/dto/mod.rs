@@ -0,0 +1,2 @@
+pub mod create_upload_dto;
+pub mod upload_dto;
@@ -0,0 +1,61 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{sea_orm_active_enums::UploadPurpose, upload::Model as UploadModel};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDto {
+    pub id: i32,
+
+    pub purpose: UploadPurpose,
+
+    pub content_type: String,
+
+    pub size: i64,
+
+    pub expires_at: NaiveDateTime,
+
+    pub confirmed_at: Option<NaiveDateTime>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<UploadModel> for UploadDto {
+    fn from(value: UploadModel) -> Self {
+        UploadDto {
+            id: value.id,
+            purpose: value.purpose,
+            content_type: value.content_type,
+            size: value.size,
+            expires_at: value.expires_at,
+            confirmed_at: value.confirmed_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+// The client PUTs the file to `url` with the declared content type and size, then confirms it
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresignedUploadDto {
+    #[serde(flatten)]
+    pub upload: UploadDto,
+
+    pub url: String,
+}
+
+impl From<(UploadModel, String)> for PresignedUploadDto {
+    fn from((upload, url): (UploadModel, String)) -> Self {
+        PresignedUploadDto {
+            upload: upload.into(),
+            url,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadUrlDto {
+    pub url: String,
+}
@@ -0,0 +1,312 @@
+pub mod dto;
+pub mod upload_handler;
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use crate::{
+    constants::{
+        UPLOAD_CLEANUP_BATCH_SIZE, UPLOAD_CLEANUP_JOB_INTERVAL_SECS, UPLOAD_CONTENT_TYPES,
+        UPLOAD_DOWNLOAD_LINK_EXPIRY_SECS, UPLOAD_URL_EXPIRY_SECS,
+    },
+    ctx::Ctx,
+    db::entity::{
+        prelude::Upload,
+        sea_orm_active_enums::UploadPurpose,
+        upload::{self, Model as UploadModel},
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        storage::private_folder,
+        upload::dto::create_upload_dto::CreateUploadDto,
+        user::UserService,
+        verification::VerificationService,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, ModelTrait, QueryFilter,
+    QuerySelect, sea_query::Expr,
+};
+use tokio::time::interval;
+use uuid::Uuid;
+
+// Enough of the file to sniff its type
+const SNIFF_LEN: usize = 512;
+
+pub struct UploadService;
+
+impl UploadService {
+    const UPLOAD: &'static str = "Upload";
+
+    /**
+     * Reserves a key under the owner's folder and returns a pre-signed PUT URL for it.
+     * Verification documents go to the merchant's folder, so only merchant owners can create them.
+     */
+    pub async fn create(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        create_upload_dto: CreateUploadDto,
+    ) -> Result<(UploadModel, String)> {
+        let ext = content_type_extension(&create_upload_dto.content_type)?;
+
+        let (s3_folder, merchant_id) = match create_upload_dto.purpose {
+            UploadPurpose::VerificationDocument => {
+                let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+                (
+                    VerificationService::get_documents_s3_folder(&merchant),
+                    Some(merchant.id),
+                )
+            }
+            UploadPurpose::UserDocument => {
+                let user = UserService::find_one(state.clone(), ctx.user_id).await?;
+                (private_folder(&user.s3_bucket_slug, "documents"), None)
+            }
+        };
+
+        let key = format!("{}{}.{}", s3_folder, Uuid::new_v4(), ext);
+        let expires_in = Duration::from_secs(UPLOAD_URL_EXPIRY_SECS);
+        let url = state
+            .storage
+            .get_presigned_put_url(
+                &key,
+                &create_upload_dto.content_type,
+                create_upload_dto.size,
+                expires_in,
+            )
+            .await?;
+
+        let now = Utc::now().naive_utc();
+        let data = upload::ActiveModel {
+            user_id: Set(ctx.user_id),
+            merchant_id: Set(merchant_id),
+            purpose: Set(create_upload_dto.purpose),
+            key: Set(key),
+            content_type: Set(create_upload_dto.content_type),
+            size: Set(create_upload_dto.size),
+            expires_at: Set(now + expires_in),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let upload = Upload::insert(data).exec_with_returning(state.db()).await?;
+
+        Ok((upload, url))
+    }
+
+    /**
+     * Checks the uploaded object against what was declared: size, content type and the file's
+     * own magic bytes. A file that doesn't match is deleted.
+     */
+    pub async fn confirm(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<UploadModel> {
+        let upload = Self::find_own(state.db(), ctx.user_id, id).await?;
+        if upload.confirmed_at.is_some() {
+            return Ok(upload);
+        }
+        if upload.expires_at < Utc::now().naive_utc() {
+            return Err(ServiceError::DtoError("Upload has expired".into()));
+        }
+
+        let object = state
+            .storage
+            .head_object(&upload.key)
+            .await?
+            .ok_or(ServiceError::DtoError("File hasn't been uploaded".into()))?;
+
+        let sniffed = if object.size == upload.size {
+            let prefix = state.storage.read_prefix(&upload.key, SNIFF_LEN).await?;
+            infer::get(&prefix).map(|kind| kind.mime_type())
+        } else {
+            None
+        };
+        let content_type_matches = object
+            .content_type
+            .as_deref()
+            .is_none_or(|content_type| content_type == upload.content_type);
+
+        if sniffed != Some(upload.content_type.as_str()) || !content_type_matches {
+            state.storage.delete_object(&upload.key).await?;
+            return Err(ServiceError::DtoError(
+                "Uploaded file doesn't match the declared size or content type".into(),
+            ));
+        }
+
+        let mut data: upload::ActiveModel = upload.into();
+        data.confirmed_at = Set(Some(Utc::now().naive_utc()));
+        let upload = Upload::update(data).exec(state.db()).await?;
+
+        Ok(upload)
+    }
+
+    // Time limited link, uploads are private
+    pub async fn find_download_url(state: Arc<AppState>, ctx: Ctx, id: i32) -> Result<String> {
+        let upload = Self::find_own(state.db(), ctx.user_id, id).await?;
+        if upload.confirmed_at.is_none() {
+            return Err(ServiceError::DtoError("Upload isn't confirmed".into()));
+        }
+
+        state
+            .storage
+            .get_presigned_url(
+                &upload.key,
+                Duration::from_secs(UPLOAD_DOWNLOAD_LINK_EXPIRY_SECS),
+            )
+            .await
+    }
+
+    /**
+     * Attaches confirmed uploads to what they were uploaded for, each upload can only be used
+     * once. Runs in the caller's transaction.
+     */
+    pub(in crate::services) async fn consume<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        merchant_id: Option<i32>,
+        purpose: UploadPurpose,
+        ids: &[i32],
+    ) -> Result<Vec<UploadModel>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(id) = find_duplicate(ids) {
+            return Err(ServiceError::DtoError(format!(
+                "Upload {} is listed more than once",
+                id
+            )));
+        }
+
+        let merchant_filter = match merchant_id {
+            Some(merchant_id) => upload::Column::MerchantId.eq(merchant_id),
+            None => upload::Column::MerchantId.is_null(),
+        };
+        let uploads = Upload::find()
+            .filter(upload::Column::Id.is_in(ids.iter().copied()))
+            .filter(upload::Column::UserId.eq(user_id))
+            .filter(merchant_filter)
+            .filter(upload::Column::Purpose.eq(purpose))
+            .filter(upload::Column::ConfirmedAt.is_not_null())
+            .filter(upload::Column::ConsumedAt.is_null())
+            .lock_exclusive()
+            .all(db)
+            .await?;
+
+        if let Some(id) = ids
+            .iter()
+            .find(|id| !uploads.iter().any(|upload| upload.id == **id))
+        {
+            return Err(ServiceError::DtoError(format!(
+                "Upload {} isn't a confirmed, unused upload for {:?}",
+                id, purpose
+            )));
+        }
+
+        Upload::update_many()
+            .col_expr(
+                upload::Column::ConsumedAt,
+                Expr::value(Utc::now().naive_utc()),
+            )
+            .filter(upload::Column::Id.is_in(ids.iter().copied()))
+            .exec(db)
+            .await?;
+
+        Ok(uploads)
+    }
+
+    /**
+     * Deletes uploads that expired without being confirmed, object first. An object that fails
+     * to delete keeps its row so the next run retries it.
+     */
+    pub async fn delete_expired(state: Arc<AppState>) -> Result<usize> {
+        let uploads = Upload::find()
+            .filter(upload::Column::ConfirmedAt.is_null())
+            .filter(upload::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+            .limit(UPLOAD_CLEANUP_BATCH_SIZE)
+            .all(state.db())
+            .await?;
+
+        let mut deleted = 0;
+        for upload in uploads {
+            if let Err(e) = state.storage.delete_object(&upload.key).await {
+                tracing::warn!("Failed to delete expired upload {}: {:?}", upload.key, e);
+                continue;
+            }
+            upload.delete(state.db()).await?;
+            deleted += 1;
+        }
+
+        Ok(deleted)
+    }
+
+    pub async fn run_cleanup_job(state: Arc<AppState>) {
+        let mut ticker = interval(Duration::from_secs(UPLOAD_CLEANUP_JOB_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            match Self::delete_expired(state.clone()).await {
+                Ok(deleted) if deleted > 0 => tracing::info!("Deleted {} expired uploads", deleted),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Upload cleanup failed: {:?}", e),
+            }
+        }
+    }
+
+    async fn find_own<C: ConnectionTrait>(db: &C, user_id: i32, id: i32) -> Result<UploadModel> {
+        let upload = Upload::find_by_id(id)
+            .filter(upload::Column::UserId.eq(user_id))
+            .one(db)
+            .await?;
+
+        upload.ok_or(ServiceError::EntityNotFound {
+            entity: Self::UPLOAD,
+            id: EntityId::Int(id),
+        })
+    }
+}
+
+fn content_type_extension(content_type: &str) -> Result<&'static str> {
+    UPLOAD_CONTENT_TYPES
+        .iter()
+        .find(|(allowed, _)| *allowed == content_type)
+        .map(|(_, ext)| *ext)
+        .ok_or_else(|| {
+            let allowed: Vec<&str> = UPLOAD_CONTENT_TYPES
+                .iter()
+                .map(|(allowed, _)| *allowed)
+                .collect();
+            ServiceError::DtoError(format!(
+                "Content type must be one of {}",
+                allowed.join(", ")
+            ))
+        })
+}
+
+fn find_duplicate(ids: &[i32]) -> Option<i32> {
+    let mut seen = HashSet::new();
+    ids.iter().copied().find(|id| !seen.insert(*id))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        constants::UPLOAD_CONTENT_TYPES,
+        services::upload::{content_type_extension, find_duplicate},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn test_content_type_extension() -> Result<()> {
+        for (content_type, ext) in UPLOAD_CONTENT_TYPES {
+            assert_eq!(content_type_extension(content_type)?, *ext);
+        }
+        assert!(content_type_extension("text/html").is_err());
+        assert!(content_type_extension("application/octet-stream").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_duplicate() -> Result<()> {
+        assert_eq!(find_duplicate(&[1, 2, 3]), None);
+        assert_eq!(find_duplicate(&[1, 2, 1, 2]), Some(1));
+
+        Ok(())
+    }
+}
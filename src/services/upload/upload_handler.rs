@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        upload::{
+            UploadService,
+            dto::{
+                create_upload_dto::CreateUploadDto,
+                upload_dto::{DownloadUrlDto, PresignedUploadDto, UploadDto},
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use validator::Validate;
+
+pub async fn create(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(create_upload_dto): Json<CreateUploadDto>,
+) -> Result<Json<PresignedUploadDto>> {
+    create_upload_dto.validate()?;
+
+    let upload = UploadService::create(state, ctx, create_upload_dto).await?;
+    Ok(Json(upload.into()))
+}
+
+pub async fn confirm(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<UploadDto>> {
+    let upload = UploadService::confirm(state, ctx, id).await?;
+    Ok(Json(upload.into()))
+}
+
+pub async fn find_download_url(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<DownloadUrlDto>> {
+    let url = UploadService::find_download_url(state, ctx, id).await?;
+    Ok(Json(DownloadUrlDto { url }))
+}
@@ -1,91 +1,104 @@
-use crate::{
-    db::entity::sea_orm_active_enums::MerchantCategory,
-    services::error::{Result, ServiceError},
-};
-use axum::{body::Bytes, extract::Multipart};
-use sea_orm::IntoActiveValue;
+use crate::constants::IMAGE_MAX_SIZE_BYTES;
+use axum::body::Bytes;
+use macros::FromMultipart;
+use validator::Validate;
 
+#[derive(FromMultipart, Validate)]
+#[multipart(validate)]
 pub struct CreateMerchantProfileDto {
+    #[validate(length(min = 1, max = 100))]
     pub display_name: String,
+
+    #[multipart(limit = IMAGE_MAX_SIZE_BYTES)]
     pub cover: Option<Bytes>,
+
+    #[validate(length(min = 1))]
     pub address: String,
+
+    pub latitude: Option<f64>,
+
+    pub longitude: Option<f64>,
+
+    #[multipart(rename = "business_registration_number")]
     pub business_registration_number: Option<String>,
-    pub category: MerchantCategory,
+
+    pub category_id: i32,
 }
 
-/*
- * TODO: Write a macro that converts multipart to dto
- */
+#[cfg(test)]
+mod test {
+    use crate::services::user::dto::create_merchant_profile_dto::CreateMerchantProfileDto;
+    use anyhow::Result;
+    use axum::{
+        body::Body,
+        extract::{FromRequest, Request},
+        http::header::CONTENT_TYPE,
+    };
 
-// Multipart limits the default file size to 2MB
-pub async fn from_multipart_to_create_merchant_profle_dto(
-    mut form: Multipart,
-) -> Result<CreateMerchantProfileDto> {
-    let mut display_name: Option<String> = None;
-    let mut address: Option<String> = None;
-    let mut business_registration_number: Option<String> = None;
-    let mut cover: Option<Bytes> = None;
-    let mut category: Option<MerchantCategory> = None;
+    const BOUNDARY: &str = "zuno-boundary";
 
-    while let Some(field) = form.next_field().await? {
-        match field.name() {
-            Some("displayName") => {
-                display_name = Some(
-                    field
-                        .text()
-                        .await
-                        .map_err(|_| ServiceError::DtoError("Display name is invalid".into()))?,
-                );
-            }
-            Some("address") => {
-                address = Some(
-                    field
-                        .text()
-                        .await
-                        .map_err(|_| ServiceError::DtoError("Address is invalid".into()))?,
-                );
-            }
-            Some("category") => {
-                let cat = field
-                    .text()
-                    .await
-                    .map_err(|_| ServiceError::DtoError("Category is invalid".into()))?;
-                category = Some(
-                    cat.parse::<MerchantCategory>()
-                        .map_err(|_| ServiceError::DtoError("failed to parse Category ".into()))?,
-                );
-            }
-            Some("business_registration_number") => {
-                business_registration_number = Some(field.text().await.map_err(|_| {
-                    ServiceError::DtoError("Business registration number is invalid".into())
-                })?);
-            }
-            Some("cover") => {
-                cover = Some(
-                    field
-                        .bytes()
-                        .await
-                        .map_err(|_| ServiceError::DtoError("Cover is invalid".into()))?,
-                );
-            }
-            _ => {}
+    fn multipart_request(fields: &[(&str, &str)]) -> Result<Request> {
+        let mut body = String::new();
+        for (name, value) in fields {
+            body.push_str(&format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            ));
         }
+        body.push_str(&format!("--{BOUNDARY}--\r\n"));
+
+        Ok(Request::builder()
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(body))?)
     }
 
-    let (display_name, address, category) = match (display_name, address, category) {
-        (Some(t), Some(d), Some(c)) => (t, d, c),
-        _ => {
-            return Err(ServiceError::DtoError(
-                "Missing one or more required fields".into(),
-            ));
-        }
-    };
+    #[tokio::test]
+    async fn test_create_merchant_profile_from_multipart() -> Result<()> {
+        let request = multipart_request(&[
+            ("displayName", "Corner Cafe"),
+            ("address", "1 Main St"),
+            ("latitude", " -33.86 "),
+            ("categoryId", "3"),
+            ("business_registration_number", "123-456"),
+        ])?;
+        let dto = CreateMerchantProfileDto::from_request(request, &()).await?;
+        assert_eq!(dto.display_name, "Corner Cafe");
+        assert_eq!(dto.latitude, Some(-33.86));
+        assert_eq!(dto.longitude, None);
+        assert_eq!(dto.category_id, 3);
+        assert_eq!(dto.business_registration_number.as_deref(), Some("123-456"));
+        assert!(dto.cover.is_none());
+
+        // Missing, unparsable and invalid fields are rejected before the handler runs
+        let missing = multipart_request(&[("displayName", "Corner Cafe"), ("categoryId", "3")])?;
+        assert!(
+            CreateMerchantProfileDto::from_request(missing, &())
+                .await
+                .is_err()
+        );
+        let unparsable = multipart_request(&[
+            ("displayName", "Corner Cafe"),
+            ("address", "1 Main St"),
+            ("categoryId", "cafe"),
+        ])?;
+        assert!(
+            CreateMerchantProfileDto::from_request(unparsable, &())
+                .await
+                .is_err()
+        );
+        let invalid = multipart_request(&[
+            ("displayName", ""),
+            ("address", "1 Main St"),
+            ("categoryId", "3"),
+        ])?;
+        assert!(
+            CreateMerchantProfileDto::from_request(invalid, &())
+                .await
+                .is_err()
+        );
 
-    Ok(CreateMerchantProfileDto {
-        display_name,
-        business_registration_number,
-        address,
-        cover,
-        category,
-    })
+        Ok(())
+    }
 }
@@ -1,8 +1,8 @@
 use serde::Serialize;
 
 use crate::{
-    db::entity::{merchant, sea_orm_active_enums::MerchantCategory},
-    services::get_public_url,
+    db::entity::{merchant, store::Model as StoreModel},
+    services::{media::dto::image_variants_dto::ImageVariantsDto, store::dto::store_dto::StoreDto},
 };
 
 #[derive(Serialize)]
@@ -10,10 +10,13 @@ use crate::{
 pub struct MerchantDto {
     pub id: i32,
     pub display_name: String,
-    pub cover: Option<String>,
+    pub slug: String,
+    pub cover: Option<ImageVariantsDto>,
     pub address: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
     pub is_verified: bool,
-    pub category: MerchantCategory,
+    pub category_id: i32,
 }
 
 impl From<merchant::Model> for MerchantDto {
@@ -21,10 +24,52 @@ impl From<merchant::Model> for MerchantDto {
         MerchantDto {
             id: value.id,
             display_name: value.display_name,
-            cover: value.cover.map(|cover_key| get_public_url(&cover_key)),
+            slug: value.slug,
+            cover: value
+                .cover
+                .map(|cover_key| ImageVariantsDto::from_key(&cover_key)),
             address: value.address,
+            latitude: value.latitude,
+            longitude: value.longitude,
             is_verified: value.is_verified,
-            category: value.category,
+            category_id: value.category_id,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchantDetailsDto {
+    #[serde(flatten)]
+    pub merchant: MerchantDto,
+
+    pub stores: Vec<StoreDto>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MerchantSearchDto {
+    #[serde(flatten)]
+    pub merchant: MerchantDto,
+
+    // Meters from the searched coordinates
+    pub distance: Option<f64>,
+}
+
+impl From<(merchant::Model, Option<f64>)> for MerchantSearchDto {
+    fn from((merchant, distance): (merchant::Model, Option<f64>)) -> Self {
+        MerchantSearchDto {
+            merchant: merchant.into(),
+            distance,
+        }
+    }
+}
+
+impl From<(merchant::Model, Vec<StoreModel>)> for MerchantDetailsDto {
+    fn from((merchant, stores): (merchant::Model, Vec<StoreModel>)) -> Self {
+        MerchantDetailsDto {
+            merchant: merchant.into(),
+            stores: stores.into_iter().map(|val| val.into()).collect(),
         }
     }
 }
@@ -1,3 +1,7 @@
 pub mod create_merchant_profile_dto;
 pub mod merchant_dto;
+pub mod search_merchants_dto;
+pub mod update_merchant_slug_dto;
+pub mod update_merchant_status_dto;
+pub mod upload_avatar_dto;
 pub mod user_dto;
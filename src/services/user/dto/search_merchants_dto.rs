@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::constants::DISCOVERY_MAX_RADIUS_METERS;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MerchantSortBy {
+    #[default]
+    Name,
+    // Nearest of the merchant and its stores, needs coordinates
+    Distance,
+}
+
+#[derive(Debug, Default, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMerchantsDto {
+    // Matched against the display name
+    pub search: Option<String>,
+
+    // Includes the category's sub categories
+    pub category_id: Option<i32>,
+
+    #[validate(range(min = -90.0, max = 90.0))]
+    pub latitude: Option<f64>,
+
+    #[validate(range(min = -180.0, max = 180.0))]
+    pub longitude: Option<f64>,
+
+    // Meters, only used with coordinates
+    #[validate(range(min = 1.0, max = DISCOVERY_MAX_RADIUS_METERS))]
+    pub radius: Option<f64>,
+
+    #[serde(default)]
+    pub sort_by: MerchantSortBy,
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMerchantSlugDto {
+    // Normalized the same way as generated slugs
+    #[validate(length(min = 1, max = 100))]
+    pub slug: String,
+}
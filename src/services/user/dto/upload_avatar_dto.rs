@@ -0,0 +1,9 @@
+use crate::constants::IMAGE_MAX_SIZE_BYTES;
+use axum::body::Bytes;
+use macros::FromMultipart;
+
+#[derive(FromMultipart)]
+pub struct UploadAvatarDto {
+    #[multipart(limit = IMAGE_MAX_SIZE_BYTES)]
+    pub avatar: Bytes,
+}
@@ -1,11 +1,15 @@
-use crate::db::entity::user::Model as UserModel;
+use crate::{
+    db::entity::user::Model as UserModel,
+    services::media::dto::image_variants_dto::ImageVariantsDto,
+};
 use serde::Serialize;
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UserDto {
     id: i32,
-    email: String,
+    email: Option<String>,
+    avatar: Option<ImageVariantsDto>,
 }
 
 impl From<UserModel> for UserDto {
@@ -13,6 +17,9 @@ impl From<UserModel> for UserDto {
         UserDto {
             id: value.id,
             email: value.email,
+            avatar: value
+                .avatar
+                .map(|avatar_key| ImageVariantsDto::from_key(&avatar_key)),
         }
     }
 }
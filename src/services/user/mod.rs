@@ -3,20 +3,60 @@ pub mod user_handler;
 use std::sync::Arc;
 
 use crate::{
+    constants::{DISCOVERY_DEFAULT_RADIUS_METERS, MERCHANT_SLUG_ATTEMPTS},
     ctx::Ctx,
     db::entity::{
         merchant::{self, Column, Model as MerchantModel},
         prelude::{Merchant, User},
-        user::Model as UserModel,
+        sea_orm_active_enums::UserRoleType,
+        store::Model as StoreModel,
+        user::{self, Model as UserModel},
     },
     services::{
         AppState,
+        category::CategoryService,
         error::{EntityId, Result, ServiceError},
-        user::dto::create_merchant_profile_dto::CreateMerchantProfileDto,
+        media::{MediaService, ProcessedImage},
+        pagination::{
+            CursorValue, Page, PageQuery, decode_cursor, encode_cursor, like_pattern, page_limit,
+        },
+        role::RoleService,
+        slug::{SlugService, is_slug_taken, slugify},
+        store::{StoreService, validate_coordinates},
+        user::dto::{
+            create_merchant_profile_dto::CreateMerchantProfileDto,
+            search_merchants_dto::{MerchantSortBy, SearchMerchantsDto},
+            update_merchant_slug_dto::UpdateMerchantSlugDto,
+            update_merchant_status_dto::UpdateMerchantStatusDto,
+            upload_avatar_dto::UploadAvatarDto,
+        },
     },
 };
-use convert_case::{Case, Casing};
-use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DbBackend, EntityTrait, FromQueryResult, QueryFilter,
+    QuerySelect, Statement, TransactionTrait, Value,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, FromQueryResult)]
+struct MerchantMatch {
+    id: i32,
+    display_name: String,
+    distance: Option<f64>,
+}
+
+// Positional parameters for hand built statements
+#[derive(Default)]
+struct Params {
+    values: Vec<Value>,
+}
+
+impl Params {
+    fn bind(&mut self, value: impl Into<Value>) -> String {
+        self.values.push(value.into());
+        format!("${}", self.values.len())
+    }
+}
 
 pub struct UserService;
 
@@ -32,21 +72,207 @@ impl UserService {
         })
     }
 
-    pub async fn find_merchant(state: Arc<AppState>, slug: String) -> Result<MerchantModel> {
+    // Public merchant page with all of its stores
+    pub async fn find_merchant(
+        state: Arc<AppState>,
+        slug: String,
+    ) -> Result<(MerchantModel, Vec<StoreModel>)> {
+        let merchant = SlugService::find_merchant(state.db(), &slug).await?.ok_or(
+            ServiceError::EntityNotFound {
+                entity: Self::MERCHANT,
+                id: EntityId::Str(slug),
+            },
+        )?;
+
+        let stores = StoreService::find_merchant_stores(state, merchant.id).await?;
+        Ok((merchant, stores))
+    }
+
+    // Merchant profile owned by the user
+    pub async fn find_user_merchant(state: Arc<AppState>, user_id: i32) -> Result<MerchantModel> {
         let merchant = Merchant::find()
-            .filter(Column::Slug.eq(&slug))
+            .filter(Column::UserId.eq(user_id))
             .one(state.db())
             .await?;
 
         merchant.ok_or(ServiceError::EntityNotFound {
             entity: Self::MERCHANT,
-            id: EntityId::Str(slug),
+            id: EntityId::Int(user_id),
         })
     }
 
-    pub async fn find_all_merchants(state: Arc<AppState>) -> Result<Vec<MerchantModel>> {
-        let merchants = Merchant::find().all(state.db()).await?;
-        Ok(merchants)
+    // Merchant the user owns, or works for as staff
+    pub async fn find_ctx_merchant(state: Arc<AppState>, ctx: &Ctx) -> Result<MerchantModel> {
+        let Some(member) = &ctx.member else {
+            return Self::find_user_merchant(state, ctx.user_id).await;
+        };
+
+        let merchant = Merchant::find_by_id(member.merchant_id)
+            .one(state.db())
+            .await?;
+        merchant.ok_or(ServiceError::EntityNotFound {
+            entity: Self::MERCHANT,
+            id: EntityId::Int(member.merchant_id),
+        })
+    }
+
+    /**
+     * Discovery for the consumer app, only verified and active merchants are listed.
+     * With coordinates, a merchant's distance is the nearest of its own location and its stores,
+     * and merchants outside the radius are left out.
+     */
+    pub async fn search_merchants(
+        state: Arc<AppState>,
+        search_merchants_dto: SearchMerchantsDto,
+        page_query: PageQuery,
+    ) -> Result<Page<(MerchantModel, Option<f64>)>> {
+        let origin = match (
+            search_merchants_dto.latitude,
+            search_merchants_dto.longitude,
+        ) {
+            (Some(latitude), Some(longitude)) => Some((latitude, longitude)),
+            (None, None) => None,
+            _ => {
+                return Err(ServiceError::DtoError(
+                    "Latitude and longitude must be set together".into(),
+                ));
+            }
+        };
+        if search_merchants_dto.sort_by == MerchantSortBy::Distance && origin.is_none() {
+            return Err(ServiceError::DtoError(
+                "Sorting by distance needs coordinates".into(),
+            ));
+        }
+
+        let mut params = Params::default();
+        let mut conditions = vec!["m.is_verified".to_string(), "m.is_active".to_string()];
+        let mut match_conditions = vec!["TRUE".to_string()];
+
+        if let Some(category_id) = search_merchants_dto.category_id {
+            let category_id = params.bind(category_id);
+            conditions.push(format!(
+                "m.category_id IN (SELECT id FROM category \
+                 WHERE id = {category_id} OR parent_id = {category_id})"
+            ));
+        }
+        if let Some(search) = search_merchants_dto
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty())
+        {
+            conditions.push(format!(
+                "m.display_name ILIKE {}",
+                params.bind(like_pattern(search))
+            ));
+        }
+
+        let distance = match origin {
+            Some((latitude, longitude)) => {
+                let origin = format!(
+                    "ll_to_earth({}, {})",
+                    params.bind(latitude),
+                    params.bind(longitude)
+                );
+                let radius = params.bind(
+                    search_merchants_dto
+                        .radius
+                        .unwrap_or(DISCOVERY_DEFAULT_RADIUS_METERS),
+                );
+
+                // earth_box is a cheap, index backed pre filter, the exact distance is checked after
+                conditions.push(format!(
+                    "((m.latitude IS NOT NULL AND m.longitude IS NOT NULL \
+                     AND earth_box({origin}, {radius}) @> ll_to_earth(m.latitude, m.longitude)) \
+                     OR EXISTS (SELECT 1 FROM store s WHERE s.merchant_id = m.id \
+                     AND s.latitude IS NOT NULL AND s.longitude IS NOT NULL \
+                     AND earth_box({origin}, {radius}) @> ll_to_earth(s.latitude, s.longitude)))"
+                ));
+                match_conditions.push(format!("distance <= {radius}"));
+
+                format!(
+                    "least(earth_distance({origin}, ll_to_earth(m.latitude, m.longitude)), \
+                     (SELECT min(earth_distance({origin}, ll_to_earth(s.latitude, s.longitude))) \
+                     FROM store s WHERE s.merchant_id = m.id))"
+                )
+            }
+            None => "NULL::double precision".to_string(),
+        };
+
+        let sort_column = match search_merchants_dto.sort_by {
+            MerchantSortBy::Name => "display_name",
+            MerchantSortBy::Distance => "distance",
+        };
+        if let Some(cursor) = &page_query.cursor {
+            let (value, id) = decode_cursor(cursor)?;
+            let value = match (search_merchants_dto.sort_by, value) {
+                (MerchantSortBy::Name, value @ CursorValue::Str(_))
+                | (MerchantSortBy::Distance, value @ CursorValue::Float(_)) => value,
+                _ => return Err(ServiceError::DtoError("Invalid cursor".into())),
+            };
+            match_conditions.push(format!(
+                "({sort_column}, id) > ({}, {})",
+                params.bind(value),
+                params.bind(id)
+            ));
+        }
+
+        // Fetch one extra row to know if there is a next page
+        let limit = page_limit(&page_query);
+        let sql = format!(
+            "SELECT id, display_name, distance FROM ( \
+             SELECT m.id, m.display_name, {distance} AS distance \
+             FROM merchant m WHERE {} \
+             ) matches WHERE {} \
+             ORDER BY {sort_column} ASC, id ASC LIMIT {}",
+            conditions.join(" AND "),
+            match_conditions.join(" AND "),
+            limit + 1
+        );
+        let mut matches = MerchantMatch::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            params.values,
+        ))
+        .all(state.db())
+        .await?;
+
+        let next_cursor = if matches.len() as u64 > limit {
+            matches.truncate(limit as usize);
+            matches
+                .last()
+                .map(|last| {
+                    let value = match search_merchants_dto.sort_by {
+                        MerchantSortBy::Name => CursorValue::Str(last.display_name.clone()),
+                        MerchantSortBy::Distance => {
+                            CursorValue::Float(last.distance.unwrap_or_default())
+                        }
+                    };
+                    encode_cursor(value, last.id)
+                })
+                .transpose()?
+        } else {
+            None
+        };
+
+        let mut merchants: HashMap<i32, MerchantModel> = Merchant::find()
+            .filter(Column::Id.is_in(matches.iter().map(|val| val.id)))
+            .all(state.db())
+            .await?
+            .into_iter()
+            .map(|merchant| (merchant.id, merchant))
+            .collect();
+
+        let items = matches
+            .into_iter()
+            .filter_map(|val| {
+                merchants
+                    .remove(&val.id)
+                    .map(|merchant| (merchant, val.distance))
+            })
+            .collect();
+
+        Ok(Page { items, next_cursor })
     }
 
     pub async fn create_merchant_profile(
@@ -56,36 +282,171 @@ impl UserService {
     ) -> Result<MerchantModel> {
         let user = Self::find_one(state.clone(), ctx.user_id).await?;
         let display_name = create_merchant_profile_dto.display_name;
-        let slug = display_name.to_case(Case::Kebab);
-        let s3_bucket_slug = Self::get_merchant_s3_bucket(&slug);
+        let base_slug = slugify(&display_name)?;
 
+        validate_coordinates(
+            create_merchant_profile_dto.latitude,
+            create_merchant_profile_dto.longitude,
+        )?;
+        CategoryService::find_category(state.db(), create_merchant_profile_dto.category_id).await?;
         let cover = match create_merchant_profile_dto.cover {
-            Some(cover) => Some(
-                state
-                    .s3
-                    .upload_file(&s3_bucket_slug, &cover, Some("cover".to_string()))
-                    .await?,
-            ),
+            Some(cover) => Some(MediaService::process_image(cover).await?),
             None => None,
         };
 
-        let data = merchant::ActiveModel {
+        let mut data = merchant::ActiveModel {
             display_name: Set(display_name),
             address: Set(create_merchant_profile_dto.address),
+            latitude: Set(create_merchant_profile_dto.latitude),
+            longitude: Set(create_merchant_profile_dto.longitude),
             business_registration_number: Set(
                 create_merchant_profile_dto.business_registration_number
             ),
-            category: Set(create_merchant_profile_dto.category),
-            slug: Set(slug),
-            s3_bucket_slug: Set(s3_bucket_slug),
-            cover: Set(cover),
+            category_id: Set(create_merchant_profile_dto.category_id),
+            user_id: Set(user.id),
             ..Default::default()
         };
 
-        let merchat = Merchant::insert(data)
-            .exec_with_returning(state.db())
-            .await?;
-        Ok(merchat)
+        // A concurrent sign up can take the slug between the lookup and the insert, so try the
+        // next free one. The cover is uploaded once the slug, and with it the S3 folder, is final.
+        let mut attempts = 0;
+        let (txn, merchant) = loop {
+            let slug = SlugService::unique_merchant_slug(state.db(), &base_slug, None).await?;
+            data.s3_bucket_slug = Set(Self::get_merchant_s3_bucket(&slug));
+            data.slug = Set(slug);
+
+            let txn = state.db().begin().await?;
+            match Merchant::insert(data.clone())
+                .exec_with_returning(&txn)
+                .await
+            {
+                Ok(merchant) => break (txn, merchant),
+                Err(e) if attempts < MERCHANT_SLUG_ATTEMPTS && is_slug_taken(&e) => {
+                    txn.rollback().await?;
+                    attempts += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        RoleService::insert_role(&txn, user.id, UserRoleType::MerchantOwner, None).await?;
+        txn.commit().await?;
+
+        let merchant = match cover {
+            Some(cover) => Self::save_merchant_cover(&state, merchant, cover).await,
+            None => merchant,
+        };
+
+        Ok(merchant)
+    }
+
+    // The merchant is already created, a cover that fails to save is logged instead of failing it
+    async fn save_merchant_cover(
+        state: &AppState,
+        merchant: MerchantModel,
+        cover: ProcessedImage,
+    ) -> MerchantModel {
+        let s3_folder = format!("{}/cover", merchant.s3_bucket_slug);
+        let key =
+            match MediaService::upload_variants(state.storage.as_ref(), &s3_folder, cover).await {
+                Ok(key) => key,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to upload cover of merchant {}: {:?}",
+                        merchant.id,
+                        e
+                    );
+                    return merchant;
+                }
+            };
+
+        let mut data: merchant::ActiveModel = merchant.clone().into();
+        data.cover = Set(Some(key.clone()));
+        match Merchant::update(data).exec(state.db()).await {
+            Ok(merchant) => merchant,
+            Err(e) => {
+                tracing::warn!("Failed to save cover of merchant {}: {:?}", merchant.id, e);
+                MediaService::delete_image(state.storage.as_ref(), &key).await;
+                merchant
+            }
+        }
+    }
+
+    // Previous slugs keep resolving to the merchant, the S3 folder stays where it is
+    pub async fn update_merchant_slug(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        update_merchant_slug_dto: UpdateMerchantSlugDto,
+    ) -> Result<MerchantModel> {
+        let slug = slugify(&update_merchant_slug_dto.slug)?;
+
+        let txn = state.db().begin().await?;
+        let merchant = Merchant::find()
+            .filter(Column::UserId.eq(ctx.user_id))
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::MERCHANT,
+                id: EntityId::Int(ctx.user_id),
+            })?;
+        if merchant.slug == slug {
+            return Ok(merchant);
+        }
+
+        if SlugService::unique_merchant_slug(&txn, &slug, Some(merchant.id)).await? != slug {
+            return Err(ServiceError::DtoError("Slug is taken".into()));
+        }
+        let merchant = SlugService::change_merchant_slug(&txn, merchant, slug).await?;
+        txn.commit().await?;
+
+        Ok(merchant)
+    }
+
+    // Replaces the avatar, the previous variants are deleted once the new one is saved
+    pub async fn update_avatar(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        upload_avatar_dto: UploadAvatarDto,
+    ) -> Result<UserModel> {
+        let user = Self::find_one(state.clone(), ctx.user_id).await?;
+        let s3_folder = format!("{}/avatar", user.s3_bucket_slug);
+        let avatar = MediaService::upload_image(
+            state.storage.as_ref(),
+            &s3_folder,
+            upload_avatar_dto.avatar,
+        )
+        .await?;
+
+        let previous = user.avatar.clone();
+        let mut data: user::ActiveModel = user.into();
+        data.avatar = Set(Some(avatar));
+        let user = User::update(data).exec(state.db()).await?;
+
+        if let Some(previous) = previous {
+            MediaService::delete_image(state.storage.as_ref(), &previous).await;
+        }
+
+        Ok(user)
+    }
+
+    // Inactive merchants are hidden from discovery, e.g. while closed or suspended
+    pub async fn update_merchant_status(
+        state: Arc<AppState>,
+        id: i32,
+        update_merchant_status_dto: UpdateMerchantStatusDto,
+    ) -> Result<MerchantModel> {
+        let merchant = Merchant::find_by_id(id).one(state.db()).await?.ok_or(
+            ServiceError::EntityNotFound {
+                entity: Self::MERCHANT,
+                id: EntityId::Int(id),
+            },
+        )?;
+
+        let mut data: merchant::ActiveModel = merchant.into();
+        data.is_active = Set(update_merchant_status_dto.is_active);
+        let merchant = Merchant::update(data).exec(state.db()).await?;
+
+        Ok(merchant)
     }
 
     fn get_merchant_s3_bucket(merchant_slug: &String) -> String {
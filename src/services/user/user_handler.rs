@@ -1,52 +1,87 @@
 use crate::services::error::Result;
-use crate::services::user::dto::create_merchant_profile_dto::{
-    CreateMerchantProfileDto, from_multipart_to_create_merchant_profle_dto,
-};
+use crate::services::user::dto::create_merchant_profile_dto::CreateMerchantProfileDto;
+use crate::services::user::dto::search_merchants_dto::SearchMerchantsDto;
+use crate::services::user::dto::update_merchant_slug_dto::UpdateMerchantSlugDto;
+use crate::services::user::dto::update_merchant_status_dto::UpdateMerchantStatusDto;
+use crate::services::user::dto::upload_avatar_dto::UploadAvatarDto;
 use crate::{
     ctx::Ctx,
     services::{
         AppState,
+        pagination::{Page, PageQuery},
         user::{
             UserService,
-            dto::{merchant_dto::MerchantDto, user_dto::UserDto},
+            dto::{
+                merchant_dto::{MerchantDetailsDto, MerchantDto, MerchantSearchDto},
+                user_dto::UserDto,
+            },
         },
     },
 };
-use axum::extract::{Multipart, Path};
+use axum::extract::{Path, Query};
 use axum::{Json, extract::State};
 use std::sync::Arc;
+use validator::Validate;
 
 pub async fn find_me(State(state): State<Arc<AppState>>, ctx: Ctx) -> Result<Json<UserDto>> {
     let user = UserService::find_one(state, ctx.user_id).await?;
     Ok(Json(user.into()))
 }
 
+pub async fn update_avatar(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    upload_avatar_dto: UploadAvatarDto,
+) -> Result<Json<UserDto>> {
+    let user = UserService::update_avatar(state, ctx, upload_avatar_dto).await?;
+    Ok(Json(user.into()))
+}
+
 pub async fn find_merchant(
     State(state): State<Arc<AppState>>,
     Path(slug): Path<String>,
-) -> Result<Json<MerchantDto>> {
+) -> Result<Json<MerchantDetailsDto>> {
     let merchant = UserService::find_merchant(state, slug).await?;
     Ok(Json(merchant.into()))
 }
 
-pub async fn find_all_merchants(
+pub async fn search_merchants(
     State(state): State<Arc<AppState>>,
-) -> Result<Json<Vec<MerchantDto>>> {
-    let merchants = UserService::find_all_merchants(state).await?;
-    let merchants = merchants
-        .into_iter()
-        .map(|val| val.into())
-        .collect::<Vec<MerchantDto>>();
-
-    Ok(Json(merchants))
+    Query(search_merchants_dto): Query<SearchMerchantsDto>,
+    Query(page_query): Query<PageQuery>,
+) -> Result<Json<Page<MerchantSearchDto>>> {
+    search_merchants_dto.validate()?;
+    let merchants = UserService::search_merchants(state, search_merchants_dto, page_query).await?;
+    Ok(Json(merchants.map(|val| val.into())))
 }
 
 pub async fn create_merchant_profile(
     State(state): State<Arc<AppState>>,
     ctx: Ctx,
-    mut form: Multipart,
+    create_merchant_profile_dto: CreateMerchantProfileDto,
 ) -> Result<Json<MerchantDto>> {
-    let body = from_multipart_to_create_merchant_profle_dto(form).await?;
-    let merchant = UserService::create_merchant_profile(state, ctx, body).await?;
+    let merchant =
+        UserService::create_merchant_profile(state, ctx, create_merchant_profile_dto).await?;
+    Ok(Json(merchant.into()))
+}
+
+pub async fn update_merchant_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+    Json(update_merchant_status_dto): Json<UpdateMerchantStatusDto>,
+) -> Result<Json<MerchantDto>> {
+    let merchant =
+        UserService::update_merchant_status(state, id, update_merchant_status_dto).await?;
+    Ok(Json(merchant.into()))
+}
+
+pub async fn update_merchant_slug(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Json(update_merchant_slug_dto): Json<UpdateMerchantSlugDto>,
+) -> Result<Json<MerchantDto>> {
+    update_merchant_slug_dto.validate()?;
+
+    let merchant = UserService::update_merchant_slug(state, ctx, update_merchant_slug_dto).await?;
     Ok(Json(merchant.into()))
 }
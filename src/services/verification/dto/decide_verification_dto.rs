@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize)]
+pub enum VerificationDecision {
+    Approve,
+    NeedsInfo,
+    Reject,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct DecideVerificationDto {
+    pub decision: VerificationDecision,
+
+    // Shown to the merchant, required unless approving
+    #[validate(length(max = 2000))]
+    pub note: Option<String>,
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+use crate::db::entity::sea_orm_active_enums::VerificationStatus;
+
+// Without a status the queue holds the cases waiting on a reviewer
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListVerificationDto {
+    pub status: Option<VerificationStatus>,
+}
@@ -0,0 +1,4 @@
+pub mod decide_verification_dto;
+pub mod list_verification_dto;
+pub mod submit_verification_dto;
+pub mod verification_case_dto;
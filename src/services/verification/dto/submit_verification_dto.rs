@@ -0,0 +1,159 @@
+use crate::{
+    constants::VERIFICATION_MAX_DOCUMENTS,
+    db::entity::sea_orm_active_enums::VerificationDocumentKind,
+    services::error::{Result, ServiceError},
+};
+use axum::body::Bytes;
+use macros::FromMultipart;
+
+// Each document is a file field named after its kind, a kind can be sent more than once.
+// Documents uploaded directly to storage are sent as `<kind>UploadId` text fields instead
+#[derive(FromMultipart)]
+pub struct SubmitVerificationDto {
+    pub business_registration_number: Option<String>,
+
+    pub business_registration: Vec<Bytes>,
+
+    pub proof_of_address: Vec<Bytes>,
+
+    pub owner_identity: Vec<Bytes>,
+
+    pub other: Vec<Bytes>,
+
+    pub business_registration_upload_id: Vec<i32>,
+
+    pub proof_of_address_upload_id: Vec<i32>,
+
+    pub owner_identity_upload_id: Vec<i32>,
+
+    pub other_upload_id: Vec<i32>,
+}
+
+impl SubmitVerificationDto {
+    pub fn documents(&self) -> Vec<(VerificationDocumentKind, &Bytes)> {
+        [
+            (
+                VerificationDocumentKind::BusinessRegistration,
+                &self.business_registration,
+            ),
+            (
+                VerificationDocumentKind::ProofOfAddress,
+                &self.proof_of_address,
+            ),
+            (
+                VerificationDocumentKind::OwnerIdentity,
+                &self.owner_identity,
+            ),
+            (VerificationDocumentKind::Other, &self.other),
+        ]
+        .into_iter()
+        .flat_map(|(kind, files)| files.iter().map(move |file| (kind.clone(), file)))
+        .collect()
+    }
+
+    pub fn uploads(&self) -> Vec<(VerificationDocumentKind, i32)> {
+        [
+            (
+                VerificationDocumentKind::BusinessRegistration,
+                &self.business_registration_upload_id,
+            ),
+            (
+                VerificationDocumentKind::ProofOfAddress,
+                &self.proof_of_address_upload_id,
+            ),
+            (
+                VerificationDocumentKind::OwnerIdentity,
+                &self.owner_identity_upload_id,
+            ),
+            (VerificationDocumentKind::Other, &self.other_upload_id),
+        ]
+        .into_iter()
+        .flat_map(|(kind, ids)| ids.iter().map(move |id| (kind.clone(), *id)))
+        .collect()
+    }
+
+    pub fn check_document_count(&self) -> Result<()> {
+        let count = self.documents().len() + self.uploads().len();
+        if count == 0 {
+            return Err(ServiceError::DtoError(
+                "At least one document is required".into(),
+            ));
+        }
+        if count > VERIFICATION_MAX_DOCUMENTS {
+            return Err(ServiceError::DtoError(format!(
+                "At most {} documents can be submitted at once",
+                VERIFICATION_MAX_DOCUMENTS
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::sea_orm_active_enums::VerificationDocumentKind,
+        services::verification::dto::submit_verification_dto::SubmitVerificationDto,
+    };
+    use anyhow::Result;
+    use axum::{
+        body::Body,
+        extract::{FromRequest, Request},
+        http::header::CONTENT_TYPE,
+    };
+
+    const BOUNDARY: &str = "zuno-boundary";
+
+    fn multipart_request(fields: &[(&str, &str)]) -> Result<Request> {
+        let mut body = String::new();
+        for (name, value) in fields {
+            body.push_str(&format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            ));
+        }
+        body.push_str(&format!("--{BOUNDARY}--\r\n"));
+
+        Ok(Request::builder()
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from(body))?)
+    }
+
+    #[tokio::test]
+    async fn test_submit_verification_from_multipart() -> Result<()> {
+        // A kind sent twice keeps both documents
+        let request = multipart_request(&[
+            ("businessRegistrationNumber", "123-456"),
+            ("ownerIdentity", "front"),
+            ("ownerIdentity", "back"),
+            ("proofOfAddressUploadId", " 7 "),
+        ])?;
+        let dto = SubmitVerificationDto::from_request(request, &()).await?;
+        assert_eq!(dto.business_registration_number.as_deref(), Some("123-456"));
+        let documents = dto.documents();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].0, VerificationDocumentKind::OwnerIdentity);
+        assert_eq!(documents[1].1.as_ref(), b"back");
+        assert_eq!(
+            dto.uploads(),
+            vec![(VerificationDocumentKind::ProofOfAddress, 7)]
+        );
+        dto.check_document_count()?;
+
+        // No documents and unparsable upload ids are rejected
+        let empty = multipart_request(&[("businessRegistrationNumber", "123-456")])?;
+        let dto = SubmitVerificationDto::from_request(empty, &()).await?;
+        assert!(dto.check_document_count().is_err());
+        let unparsable = multipart_request(&[("otherUploadId", "seven")])?;
+        assert!(
+            SubmitVerificationDto::from_request(unparsable, &())
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
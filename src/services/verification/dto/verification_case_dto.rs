@@ -0,0 +1,115 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::entity::{
+    sea_orm_active_enums::{VerificationDocumentKind, VerificationStatus},
+    verification_case::Model as VerificationCaseModel,
+    verification_document::Model as VerificationDocumentModel,
+    verification_event::Model as VerificationEventModel,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationCaseDto {
+    pub id: i32,
+
+    pub merchant_id: i32,
+
+    pub status: VerificationStatus,
+
+    pub reviewer_id: Option<i32>,
+
+    pub created_at: NaiveDateTime,
+
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<VerificationCaseModel> for VerificationCaseDto {
+    fn from(value: VerificationCaseModel) -> Self {
+        VerificationCaseDto {
+            id: value.id,
+            merchant_id: value.merchant_id,
+            status: value.status,
+            reviewer_id: value.reviewer_id,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationDocumentDto {
+    pub id: i32,
+
+    pub kind: VerificationDocumentKind,
+
+    // Presigned, documents are never public
+    pub url: String,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<(VerificationDocumentModel, String)> for VerificationDocumentDto {
+    fn from((document, url): (VerificationDocumentModel, String)) -> Self {
+        VerificationDocumentDto {
+            id: document.id,
+            kind: document.kind,
+            url,
+            created_at: document.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationEventDto {
+    pub from_status: Option<VerificationStatus>,
+
+    pub to_status: VerificationStatus,
+
+    pub actor_id: Option<i32>,
+
+    pub note: Option<String>,
+
+    pub created_at: NaiveDateTime,
+}
+
+impl From<VerificationEventModel> for VerificationEventDto {
+    fn from(value: VerificationEventModel) -> Self {
+        VerificationEventDto {
+            from_status: value.from_status,
+            to_status: value.to_status,
+            actor_id: value.actor_id,
+            note: value.note,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationCaseDetailsDto {
+    #[serde(flatten)]
+    pub case: VerificationCaseDto,
+
+    pub documents: Vec<VerificationDocumentDto>,
+
+    pub events: Vec<VerificationEventDto>,
+}
+
+pub type VerificationCaseDetails = (
+    VerificationCaseModel,
+    Vec<(VerificationDocumentModel, String)>,
+    Vec<VerificationEventModel>,
+);
+
+impl From<VerificationCaseDetails> for VerificationCaseDetailsDto {
+    fn from((case, documents, events): VerificationCaseDetails) -> Self {
+        VerificationCaseDetailsDto {
+            case: case.into(),
+            documents: documents.into_iter().map(|val| val.into()).collect(),
+            events: events.into_iter().map(|val| val.into()).collect(),
+        }
+    }
+}
@@ -0,0 +1,485 @@
+pub mod dto;
+pub mod verification_handler;
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    constants::VERIFICATION_DOCUMENT_LINK_EXPIRY_SECS,
+    ctx::Ctx,
+    db::entity::{
+        merchant::{self, Model as MerchantModel},
+        prelude::{Merchant, VerificationCase, VerificationDocument, VerificationEvent},
+        sea_orm_active_enums::{
+            NotificationKind, UploadPurpose, VerificationDocumentKind, VerificationStatus,
+        },
+        verification_case::{self, Model as VerificationCaseModel},
+        verification_document, verification_event,
+    },
+    services::{
+        AppState,
+        error::{EntityId, Result, ServiceError},
+        notification::NotificationService,
+        pagination::{CursorPaginator, CursorValue, Page, PageQuery, SortDirection},
+        storage::private_folder,
+        upload::UploadService,
+        user::UserService,
+        verification::dto::{
+            decide_verification_dto::{DecideVerificationDto, VerificationDecision},
+            list_verification_dto::ListVerificationDto,
+            submit_verification_dto::SubmitVerificationDto,
+            verification_case_dto::VerificationCaseDetails,
+        },
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseTransaction, EntityTrait, ModelTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+
+pub struct VerificationService;
+
+impl VerificationService {
+    const VERIFICATION_CASE: &'static str = "VerificationCase";
+
+    pub async fn find_own(state: Arc<AppState>, ctx: Ctx) -> Result<VerificationCaseDetails> {
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        let case = VerificationCase::find()
+            .filter(verification_case::Column::MerchantId.eq(merchant.id))
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::VERIFICATION_CASE,
+                id: EntityId::Int(merchant.id),
+            })?;
+
+        Self::find_details(state, case).await
+    }
+
+    /**
+     * Opens the merchant's case, or reopens it after the reviewer asked for more information or
+     * rejected it. Documents uploaded here are deleted again if the submission fails.
+     */
+    pub async fn submit(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        submit_verification_dto: SubmitVerificationDto,
+    ) -> Result<VerificationCaseDetails> {
+        submit_verification_dto.check_document_count()?;
+        let merchant = UserService::find_user_merchant(state.clone(), ctx.user_id).await?;
+        if merchant.is_verified {
+            return Err(ServiceError::Custom(
+                "Merchant is already verified".to_string(),
+            ));
+        }
+
+        let existing = VerificationCase::find()
+            .filter(verification_case::Column::MerchantId.eq(merchant.id))
+            .one(state.db())
+            .await?;
+        if let Some(case) = &existing
+            && !can_transition(&case.status, &VerificationStatus::Submitted)
+        {
+            return Err(ServiceError::Custom(format!(
+                "Verification can't be submitted while {:?}",
+                case.status
+            )));
+        }
+
+        let s3_folder = Self::get_documents_s3_folder(&merchant);
+        let mut uploaded = Vec::new();
+        let result = async {
+            let mut documents = Vec::new();
+            for (kind, file) in submit_verification_dto.documents() {
+                let key = state.storage.upload_file(&s3_folder, file, None).await?;
+                uploaded.push(key.clone());
+                documents.push((kind, key));
+            }
+
+            Self::save_submission(
+                &state,
+                ctx,
+                merchant,
+                existing,
+                documents,
+                submit_verification_dto,
+            )
+            .await
+        }
+        .await;
+
+        let case = match result {
+            Ok(case) => case,
+            Err(e) => {
+                for key in uploaded {
+                    if let Err(e) = state.storage.delete_object(&key).await {
+                        tracing::warn!("Failed to delete verification document {}: {:?}", key, e);
+                    }
+                }
+                return Err(e);
+            }
+        };
+
+        Self::find_details(state, case).await
+    }
+
+    async fn save_submission(
+        state: &AppState,
+        ctx: Ctx,
+        merchant: MerchantModel,
+        existing: Option<VerificationCaseModel>,
+        mut documents: Vec<(VerificationDocumentKind, String)>,
+        submit_verification_dto: SubmitVerificationDto,
+    ) -> Result<VerificationCaseModel> {
+        let txn = state.db().begin().await?;
+
+        // Files the client already uploaded directly to storage
+        let direct_uploads = submit_verification_dto.uploads();
+        let upload_ids = direct_uploads.iter().map(|(_, id)| *id).collect::<Vec<_>>();
+        let uploads = UploadService::consume(
+            &txn,
+            ctx.user_id,
+            Some(merchant.id),
+            UploadPurpose::VerificationDocument,
+            &upload_ids,
+        )
+        .await?;
+        for (kind, id) in direct_uploads {
+            if let Some(upload) = uploads.iter().find(|upload| upload.id == id) {
+                documents.push((kind, upload.key.clone()));
+            }
+        }
+
+        let case = match existing {
+            Some(case) => {
+                Self::transition(
+                    &txn,
+                    case.id,
+                    VerificationStatus::Submitted,
+                    ctx.user_id,
+                    None,
+                )
+                .await?
+            }
+            None => {
+                let now = Utc::now().naive_utc();
+                let data = verification_case::ActiveModel {
+                    merchant_id: Set(merchant.id),
+                    status: Set(VerificationStatus::Submitted),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                };
+                let case = VerificationCase::insert(data)
+                    .exec_with_returning(&txn)
+                    .await?;
+                Self::insert_event(
+                    &txn,
+                    case.id,
+                    None,
+                    VerificationStatus::Submitted,
+                    Some(ctx.user_id),
+                    None,
+                )
+                .await?;
+                case
+            }
+        };
+
+        let documents_data = documents
+            .into_iter()
+            .map(|(kind, key)| verification_document::ActiveModel {
+                case_id: Set(case.id),
+                kind: Set(kind),
+                s3_key: Set(key),
+                created_at: Set(Utc::now().naive_utc()),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        VerificationDocument::insert_many(documents_data)
+            .exec(&txn)
+            .await?;
+
+        if let Some(business_registration_number) =
+            submit_verification_dto.business_registration_number
+        {
+            let mut merchant: merchant::ActiveModel = merchant.into();
+            merchant.business_registration_number = Set(Some(business_registration_number));
+            Merchant::update(merchant).exec(&txn).await?;
+        }
+
+        txn.commit().await?;
+        Ok(case)
+    }
+
+    // Oldest first, so merchants are reviewed in the order they submitted
+    pub async fn find_queue(
+        state: Arc<AppState>,
+        list_verification_dto: ListVerificationDto,
+        page_query: PageQuery,
+    ) -> Result<Page<VerificationCaseModel>> {
+        let select = match list_verification_dto.status {
+            Some(status) => {
+                VerificationCase::find().filter(verification_case::Column::Status.eq(status))
+            }
+            None => VerificationCase::find().filter(
+                verification_case::Column::Status
+                    .is_in([VerificationStatus::Submitted, VerificationStatus::InReview]),
+            ),
+        };
+
+        let paginator = CursorPaginator::<VerificationCase> {
+            sort_column: verification_case::Column::UpdatedAt,
+            id_column: verification_case::Column::Id,
+            direction: SortDirection::Asc,
+            sort_value: |val| CursorValue::DateTime(val.updated_at),
+            id_value: |val| val.id,
+        };
+
+        paginator.fetch(state.db(), select, &page_query).await
+    }
+
+    pub async fn find_one(state: Arc<AppState>, id: i32) -> Result<VerificationCaseDetails> {
+        let case = VerificationCase::find_by_id(id)
+            .one(state.db())
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::VERIFICATION_CASE,
+                id: EntityId::Int(id),
+            })?;
+
+        Self::find_details(state, case).await
+    }
+
+    // Takes a submitted case off the queue for the reviewing admin
+    pub async fn start_review(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+    ) -> Result<VerificationCaseDetails> {
+        let txn = state.db().begin().await?;
+        let case =
+            Self::transition(&txn, id, VerificationStatus::InReview, ctx.user_id, None).await?;
+        txn.commit().await?;
+
+        Self::find_details(state, case).await
+    }
+
+    /**
+     * Records the reviewer's decision, which is the only way a merchant becomes verified.
+     * The merchant owner is notified in the same transaction.
+     */
+    pub async fn decide(
+        state: Arc<AppState>,
+        ctx: Ctx,
+        id: i32,
+        decide_verification_dto: DecideVerificationDto,
+    ) -> Result<VerificationCaseDetails> {
+        let note = decide_verification_dto
+            .note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty());
+
+        let status = match decide_verification_dto.decision {
+            VerificationDecision::Approve => VerificationStatus::Approved,
+            VerificationDecision::NeedsInfo => VerificationStatus::NeedsInfo,
+            VerificationDecision::Reject => VerificationStatus::Rejected,
+        };
+        if status != VerificationStatus::Approved && note.is_none() {
+            return Err(ServiceError::DtoError(
+                "A note for the merchant is required".to_string(),
+            ));
+        }
+
+        let txn = state.db().begin().await?;
+        let case = Self::transition(&txn, id, status.clone(), ctx.user_id, note.clone()).await?;
+
+        let merchant =
+            case.find_related(Merchant)
+                .one(&txn)
+                .await?
+                .ok_or(ServiceError::EntityNotFound {
+                    entity: Self::VERIFICATION_CASE,
+                    id: EntityId::Int(id),
+                })?;
+        let owner_id = merchant.user_id;
+
+        let mut data: merchant::ActiveModel = merchant.into();
+        data.is_verified = Set(status == VerificationStatus::Approved);
+        Merchant::update(data).exec(&txn).await?;
+
+        let (title, body) = decision_message(&status, note);
+        NotificationService::notify(
+            &txn,
+            owner_id,
+            NotificationKind::MerchantVerification,
+            title,
+            body,
+            Some(case.id),
+        )
+        .await?;
+
+        txn.commit().await?;
+        Self::find_details(state, case).await
+    }
+
+    async fn find_details(
+        state: Arc<AppState>,
+        case: VerificationCaseModel,
+    ) -> Result<VerificationCaseDetails> {
+        let documents = case
+            .find_related(VerificationDocument)
+            .order_by_asc(verification_document::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+        let events = case
+            .find_related(VerificationEvent)
+            .order_by_asc(verification_event::Column::CreatedAt)
+            .all(state.db())
+            .await?;
+
+        let mut documents_with_url = Vec::with_capacity(documents.len());
+        for document in documents {
+            let url = state
+                .storage
+                .get_presigned_url(
+                    &document.s3_key,
+                    Duration::from_secs(VERIFICATION_DOCUMENT_LINK_EXPIRY_SECS),
+                )
+                .await?;
+            documents_with_url.push((document, url));
+        }
+
+        Ok((case, documents_with_url, events))
+    }
+
+    // The case row stays locked for the transition so concurrent reviews can't both apply
+    async fn transition(
+        txn: &DatabaseTransaction,
+        id: i32,
+        status: VerificationStatus,
+        actor_id: i32,
+        note: Option<String>,
+    ) -> Result<VerificationCaseModel> {
+        let case = VerificationCase::find_by_id(id)
+            .lock_exclusive()
+            .one(txn)
+            .await?
+            .ok_or(ServiceError::EntityNotFound {
+                entity: Self::VERIFICATION_CASE,
+                id: EntityId::Int(id),
+            })?;
+
+        if !can_transition(&case.status, &status) {
+            return Err(ServiceError::Custom(format!(
+                "Verification can't move from {:?} to {:?}",
+                case.status, status
+            )));
+        }
+
+        let from_status = case.status.clone();
+        let mut data: verification_case::ActiveModel = case.into();
+        data.status = Set(status.clone());
+        data.updated_at = Set(Utc::now().naive_utc());
+        if status == VerificationStatus::InReview {
+            data.reviewer_id = Set(Some(actor_id));
+        }
+        let case = VerificationCase::update(data).exec(txn).await?;
+
+        Self::insert_event(
+            txn,
+            case.id,
+            Some(from_status),
+            status,
+            Some(actor_id),
+            note,
+        )
+        .await?;
+
+        Ok(case)
+    }
+
+    async fn insert_event<C: ConnectionTrait>(
+        db: &C,
+        case_id: i32,
+        from_status: Option<VerificationStatus>,
+        to_status: VerificationStatus,
+        actor_id: Option<i32>,
+        note: Option<String>,
+    ) -> Result<()> {
+        let data = verification_event::ActiveModel {
+            case_id: Set(case_id),
+            from_status: Set(from_status),
+            to_status: Set(to_status),
+            actor_id: Set(actor_id),
+            note: Set(note),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        VerificationEvent::insert(data).exec(db).await?;
+
+        Ok(())
+    }
+
+    pub(in crate::services) fn get_documents_s3_folder(merchant: &MerchantModel) -> String {
+        private_folder(&merchant.s3_bucket_slug, "verification")
+    }
+}
+
+fn can_transition(from: &VerificationStatus, to: &VerificationStatus) -> bool {
+    matches!(
+        (from, to),
+        (VerificationStatus::Submitted, VerificationStatus::InReview)
+            | (VerificationStatus::InReview, VerificationStatus::NeedsInfo)
+            | (VerificationStatus::InReview, VerificationStatus::Approved)
+            | (VerificationStatus::InReview, VerificationStatus::Rejected)
+            | (VerificationStatus::NeedsInfo, VerificationStatus::Submitted)
+            | (VerificationStatus::Rejected, VerificationStatus::Submitted)
+    )
+}
+
+fn decision_message(status: &VerificationStatus, note: Option<String>) -> (String, String) {
+    let (title, body) = match status {
+        VerificationStatus::Approved => (
+            "Merchant verified",
+            "Your merchant is verified and its payment limits are lifted.",
+        ),
+        VerificationStatus::NeedsInfo => (
+            "More information needed",
+            "Your verification needs more information before it can be approved.",
+        ),
+        _ => (
+            "Verification rejected",
+            "Your verification was rejected, you can submit it again with new documents.",
+        ),
+    };
+
+    let body = match note {
+        Some(note) => format!("{}\n\n{}", body, note),
+        None => body.to_string(),
+    };
+    (title.to_string(), body)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        db::entity::sea_orm_active_enums::VerificationStatus,
+        services::verification::{can_transition, decision_message},
+    };
+
+    #[test]
+    fn test_verification_transitions() {
+        use VerificationStatus::*;
+
+        assert!(can_transition(&Submitted, &InReview));
+        assert!(can_transition(&InReview, &Approved));
+        assert!(can_transition(&NeedsInfo, &Submitted));
+        assert!(!can_transition(&Submitted, &Approved));
+        assert!(!can_transition(&Approved, &Submitted));
+        assert!(!can_transition(&Rejected, &Approved));
+
+        let (_, body) = decision_message(&NeedsInfo, Some("Upload a utility bill".to_string()));
+        assert!(body.ends_with("Upload a utility bill"));
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use crate::{
+    ctx::Ctx,
+    services::{
+        AppState,
+        error::Result,
+        pagination::{Page, PageQuery},
+        verification::{
+            VerificationService,
+            dto::{
+                decide_verification_dto::DecideVerificationDto,
+                list_verification_dto::ListVerificationDto,
+                submit_verification_dto::SubmitVerificationDto,
+                verification_case_dto::{VerificationCaseDetailsDto, VerificationCaseDto},
+            },
+        },
+    },
+};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use validator::Validate;
+
+pub async fn find_own(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+) -> Result<Json<VerificationCaseDetailsDto>> {
+    let case = VerificationService::find_own(state, ctx).await?;
+    Ok(Json(case.into()))
+}
+
+pub async fn submit(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    submit_verification_dto: SubmitVerificationDto,
+) -> Result<Json<VerificationCaseDetailsDto>> {
+    let case = VerificationService::submit(state, ctx, submit_verification_dto).await?;
+    Ok(Json(case.into()))
+}
+
+pub async fn find_queue(
+    State(state): State<Arc<AppState>>,
+    Query(list_verification_dto): Query<ListVerificationDto>,
+    Query(page_query): Query<PageQuery>,
+) -> Result<Json<Page<VerificationCaseDto>>> {
+    let cases = VerificationService::find_queue(state, list_verification_dto, page_query).await?;
+    Ok(Json(cases.map(|val| val.into())))
+}
+
+pub async fn find_one(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i32>,
+) -> Result<Json<VerificationCaseDetailsDto>> {
+    let case = VerificationService::find_one(state, id).await?;
+    Ok(Json(case.into()))
+}
+
+pub async fn start_review(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+) -> Result<Json<VerificationCaseDetailsDto>> {
+    let case = VerificationService::start_review(state, ctx, id).await?;
+    Ok(Json(case.into()))
+}
+
+pub async fn decide(
+    State(state): State<Arc<AppState>>,
+    ctx: Ctx,
+    Path(id): Path<i32>,
+    Json(decide_verification_dto): Json<DecideVerificationDto>,
+) -> Result<Json<VerificationCaseDetailsDto>> {
+    decide_verification_dto.validate()?;
+
+    let case = VerificationService::decide(state, ctx, id, decide_verification_dto).await?;
+    Ok(Json(case.into()))
+}
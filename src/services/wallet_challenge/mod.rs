@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use crate::{
+    constants::WALLET_NONCE_EXPIRY_SECS,
+    db::entity::{
+        prelude::WalletChallenge,
+        sea_orm_active_enums::WalletChallengePurpose,
+        wallet_challenge::{self, Model as WalletChallengeModel},
+    },
+    services::{
+        error::{Result, ServiceError},
+        web3::verify_message_signature,
+    },
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter,
+    QuerySelect,
+};
+use spl_token::solana_program::pubkey::Pubkey;
+use uuid::Uuid;
+
+pub struct WalletChallengeService;
+
+impl WalletChallengeService {
+    /**
+     * Issues a single use challenge for the wallet. Only the latest challenge of a purpose, wallet
+     * and owner is valid, expired challenges of any wallet are cleaned up on the way.
+     */
+    pub(in crate::services) async fn issue<C: ConnectionTrait>(
+        db: &C,
+        purpose: WalletChallengePurpose,
+        address: &str,
+        user_id: Option<i32>,
+        merchant_id: Option<i32>,
+    ) -> Result<WalletChallengeModel> {
+        let now = Utc::now().naive_utc();
+        WalletChallenge::delete_many()
+            .filter(
+                Condition::any()
+                    .add(wallet_challenge::Column::ExpiresAt.lte(now))
+                    .add(owned_by(purpose.clone(), address, user_id, merchant_id)),
+            )
+            .exec(db)
+            .await?;
+
+        let data = wallet_challenge::ActiveModel {
+            purpose: Set(purpose),
+            address: Set(address.to_string()),
+            nonce: Set(Uuid::new_v4().simple().to_string()),
+            user_id: Set(user_id),
+            merchant_id: Set(merchant_id),
+            expires_at: Set(now + chrono::Duration::seconds(WALLET_NONCE_EXPIRY_SECS)),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        let challenge = WalletChallenge::insert(data)
+            .exec_with_returning(db)
+            .await?;
+
+        Ok(challenge)
+    }
+
+    /**
+     * Consumes the challenge once `signature` is the wallet's signature of the message built from
+     * it. Runs in the caller's transaction so the challenge is only spent with the change it guards.
+     */
+    pub(in crate::services) async fn verify<C: ConnectionTrait>(
+        db: &C,
+        purpose: WalletChallengePurpose,
+        address: &str,
+        user_id: Option<i32>,
+        merchant_id: Option<i32>,
+        signature: &str,
+        message: impl Fn(&WalletChallengeModel) -> String,
+    ) -> Result<WalletChallengeModel> {
+        let challenge = WalletChallenge::find()
+            .filter(owned_by(purpose, address, user_id, merchant_id))
+            .lock_exclusive()
+            .one(db)
+            .await?
+            .filter(|challenge| challenge.expires_at > Utc::now().naive_utc())
+            .ok_or(ServiceError::DtoError(
+                "Wallet challenge is missing or expired, request a new one".to_string(),
+            ))?;
+
+        let wallet = Pubkey::from_str(&challenge.address)
+            .map_err(|_| ServiceError::DtoError("Invalid wallet address".to_string()))?;
+        verify_message_signature(&wallet, message(&challenge).as_bytes(), signature)?;
+
+        WalletChallenge::delete_by_id(challenge.id).exec(db).await?;
+
+        Ok(challenge)
+    }
+}
+
+// Challenges of the same purpose, wallet and owner replace each other
+fn owned_by(
+    purpose: WalletChallengePurpose,
+    address: &str,
+    user_id: Option<i32>,
+    merchant_id: Option<i32>,
+) -> Condition {
+    Condition::all()
+        .add(wallet_challenge::Column::Purpose.eq(purpose))
+        .add(wallet_challenge::Column::Address.eq(address))
+        .add(match user_id {
+            Some(user_id) => wallet_challenge::Column::UserId.eq(user_id),
+            None => wallet_challenge::Column::UserId.is_null(),
+        })
+        .add(match merchant_id {
+            Some(merchant_id) => wallet_challenge::Column::MerchantId.eq(merchant_id),
+            None => wallet_challenge::Column::MerchantId.is_null(),
+        })
+}
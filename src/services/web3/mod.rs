@@ -11,11 +11,13 @@ use base64::{
     Engine,
     engine::{GeneralPurpose, general_purpose},
 };
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use solana_client::{
     rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
     rpc_response::RpcConfirmedTransactionStatusWithSignature,
 };
+use solana_hash::Hash;
 use solana_keypair::Keypair;
 use solana_message::Message;
 use solana_signature::Signature;
@@ -37,82 +39,86 @@ use std::{
 use tokio::sync::OnceCell;
 use validator::ValidateLength;
 
+/// A single token transfer within a payment transaction: treasury fee, split recipient or merchant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferLeg {
+    pub receiver_wallet: String,
+    pub amount: u64,
+}
+
 pub struct Web3Service {
     pub rpc_client: Arc<RpcClient>,
     fee_faucet: Keypair,
+    escrow: Keypair,
 }
 
 impl Web3Service {
-    pub fn new() -> Result<Self> {
-        let rpc_client = Arc::new(RpcClient::new(&config().RPC_URL));
+    pub fn new(rpc_url: &str) -> Result<Self> {
+        let rpc_client = Arc::new(RpcClient::new(rpc_url));
         let fee_faucet = get_fee_faucet_keypair()?;
+        let escrow = get_escrow_keypair()?;
 
         Ok(Web3Service {
             rpc_client,
             fee_faucet,
+            escrow,
         })
     }
 
-    pub async fn create_transfer_transaction(
+    pub async fn get_latest_blockhash(&self) -> Result<Hash> {
+        let rpc_client = self.rpc_client.clone();
+        tokio::task::spawn_blocking(move || {
+            rpc_client
+                .get_latest_blockhash()
+                .map_err(ServiceError::from)
+        })
+        .await
+        .map_err(|e| ServiceError::Custom(e.to_string()))?
+    }
+
+    // `latest_blockhash` is fetched beforehand, see `get_latest_blockhash`
+    pub fn create_transfer_transaction(
         self: &Self,
         sender_wallet: &String,
-        receiver_wallet: &String,
-        amount: u64,
+        legs: &[TransferLeg],
         token_mint_address: &String,
         reference_key: Pubkey,
+        latest_blockhash: Hash,
     ) -> Result<Transaction> {
         let sender = Pubkey::from_str(sender_wallet)?;
-        let receiver = Pubkey::from_str(receiver_wallet)?;
         let token_mint = Pubkey::from_str(token_mint_address)?;
 
         // Check and create token accounts ?
         let sender_token_account = get_associated_token_address(&sender, &token_mint);
-        let receiver_token_account = get_associated_token_address(&receiver, &token_mint);
-
-        let treasury = Pubkey::from_str(TREASURY_PUBKEY)?;
-        let treasury_token_account = get_associated_token_address(&treasury, &token_mint);
-
-        // 1% fee for transaction processing
-        let fee = amount
-            .checked_mul(1)
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?
-            .checked_div(100)
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
-
-        let amount_after_fee = amount
-            .checked_sub(fee)
-            .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
-
-        let transfer_fee_instruction = transfer(
-            &TOKEN_PROGRAM_ID,
-            &sender_token_account,
-            &treasury_token_account,
-            &sender,
-            &[&sender],
-            fee,
-        )?;
-
-        let mut transfer_instruction = transfer(
-            &TOKEN_PROGRAM_ID,
-            &sender_token_account,
-            &receiver_token_account,
-            &sender,
-            &[&sender],
-            amount_after_fee,
-        )?;
-        transfer_instruction.accounts.push(AccountMeta {
-            pubkey: reference_key,
-            is_signer: false,
-            is_writable: false,
-        });
+
+        // One transfer instruction per leg, each carrying the reference so the indexer can verify all of them
+        let mut instructions = Vec::with_capacity(legs.len());
+        for leg in legs {
+            let receiver = Pubkey::from_str(&leg.receiver_wallet)?;
+            let receiver_token_account = get_associated_token_address(&receiver, &token_mint);
+
+            let mut transfer_instruction = transfer(
+                &TOKEN_PROGRAM_ID,
+                &sender_token_account,
+                &receiver_token_account,
+                &sender,
+                &[&sender],
+                leg.amount,
+            )?;
+            transfer_instruction.accounts.push(AccountMeta {
+                pubkey: reference_key,
+                is_signer: false,
+                is_writable: false,
+            });
+
+            instructions.push(transfer_instruction);
+        }
 
         let fee_faucet_pubkey = self.fee_faucet.pubkey();
-        let mut transfer_transaction = Transaction::new_with_payer(
-            &[transfer_fee_instruction, transfer_instruction],
-            Some(&fee_faucet_pubkey),
-        );
+        let mut transfer_transaction =
+            Transaction::new_with_payer(&instructions, Some(&fee_faucet_pubkey));
 
-        let latest_blockhash = self.rpc_client.get_latest_blockhash()?;
         transfer_transaction
             .try_partial_sign(&[&self.fee_faucet], latest_blockhash)
             .map_err(|_| {
@@ -126,6 +132,105 @@ impl Web3Service {
         Ok(transfer_transaction)
     }
 
+    /**
+     * Pays out legs from the backend controlled escrow token account, fee faucet pays the network fee.
+     * Every instruction carries `reference_key`, so a payout that landed can be found again.
+     */
+    pub async fn send_from_escrow(
+        &self,
+        legs: &[TransferLeg],
+        token_mint_address: &str,
+        reference_key: Pubkey,
+    ) -> Result<String> {
+        let escrow = self.escrow.pubkey();
+        let token_mint = Pubkey::from_str(token_mint_address)?;
+        let escrow_token_account = get_associated_token_address(&escrow, &token_mint);
+
+        let instructions = legs
+            .iter()
+            .map(|leg| {
+                let receiver = Pubkey::from_str(&leg.receiver_wallet)?;
+                let receiver_token_account = get_associated_token_address(&receiver, &token_mint);
+
+                let mut instruction = transfer(
+                    &TOKEN_PROGRAM_ID,
+                    &escrow_token_account,
+                    &receiver_token_account,
+                    &escrow,
+                    &[&escrow],
+                    leg.amount,
+                )?;
+                instruction.accounts.push(AccountMeta {
+                    pubkey: reference_key,
+                    is_signer: false,
+                    is_writable: false,
+                });
+                Ok(instruction)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let fee_faucet_pubkey = self.fee_faucet.pubkey();
+        let latest_blockhash = self.rpc_client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&fee_faucet_pubkey),
+            &[&self.fee_faucet, &self.escrow],
+            latest_blockhash,
+        );
+
+        self.send_and_confirm_transaction(&transaction).await
+    }
+
+    // Raw token balance of the owner's associated token account
+    pub async fn get_token_balance(&self, owner: &Pubkey, token_mint_address: &str) -> Result<u64> {
+        let token_mint = Pubkey::from_str(token_mint_address)?;
+        let token_account = get_associated_token_address(owner, &token_mint);
+
+        let balance = self.rpc_client.get_token_account_balance(&token_account)?;
+        balance.amount.parse::<u64>().map_err(|_| {
+            ServiceError::Web3Error(Web3ErrorType::Custom("Invalid token balance".to_string()))
+        })
+    }
+
+    // Signatures involving the address, newest first, back to the `since` unix timestamp
+    pub async fn find_signatures_since(
+        &self,
+        address: &Pubkey,
+        since: i64,
+        max_pages: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+        for _ in 0..max_pages {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                ..Default::default()
+            };
+
+            let page = self
+                .rpc_client
+                .get_signatures_for_address_with_config(address, config)?;
+            let is_last_page = page.len() < 1000;
+
+            let Some(last) = page.last() else { break };
+            let reached_since = last.block_time.is_some_and(|time| time < since);
+            before = Some(
+                Signature::from_str(&last.signature)
+                    .map_err(|e| ServiceError::Web3Error(Web3ErrorType::Custom(e.to_string())))?,
+            );
+
+            signatures.extend(
+                page.into_iter()
+                    .filter(|status| status.block_time.is_none_or(|time| time >= since)),
+            );
+            if is_last_page || reached_since {
+                break;
+            }
+        }
+
+        Ok(signatures)
+    }
+
     pub async fn send_and_confirm_transaction(&self, transaction: &Transaction) -> Result<String> {
         let signature = self.rpc_client.send_and_confirm_transaction(transaction)?;
 
@@ -193,6 +298,19 @@ impl Web3Service {
     }
 }
 
+/// Returns (fee, amount_after_fee), 1% fee for transaction processing
+pub fn calculate_transfer_fee(amount: u64) -> Result<(u64, u64)> {
+    let fee = amount
+        .checked_div(100)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+    let amount_after_fee = amount
+        .checked_sub(fee)
+        .ok_or(ServiceError::MathError(MathErrorType::NumericalOverflow))?;
+
+    Ok((fee, amount_after_fee))
+}
+
 pub fn get_reference_from_transfer_transaction(transaction: &Transaction) -> Result<Pubkey> {
     use super::error::{ServiceError, Web3ErrorType};
 
@@ -238,6 +356,18 @@ pub fn verify_transaction_signature(transaction: &Transaction, wallet: &Pubkey)
     Ok(())
 }
 
+// Verifies an ed25519 signature of `message` by the wallet, as produced by wallet `signMessage`
+pub fn verify_message_signature(wallet: &Pubkey, message: &[u8], signature: &str) -> Result<()> {
+    let signature = Signature::from_str(signature)
+        .map_err(|_| ServiceError::DtoError("Invalid signature".to_string()))?;
+
+    if !signature.verify(wallet.as_ref(), message) {
+        return Err(ServiceError::Web3Error(Web3ErrorType::InvalidSigner));
+    }
+
+    Ok(())
+}
+
 pub fn deserialize_transaction(encoded_tx: &str) -> Result<Transaction> {
     let transaction_bytes = general_purpose::STANDARD.decode(encoded_tx).map_err(|_| {
         ServiceError::Web3Error(Web3ErrorType::Custom(
@@ -258,6 +388,18 @@ pub fn get_fee_faucet_pubkey() -> Result<Pubkey> {
     Ok(get_fee_faucet_keypair()?.pubkey())
 }
 
+pub fn get_escrow_pubkey() -> Result<Pubkey> {
+    Ok(get_escrow_keypair()?.pubkey())
+}
+
+fn get_escrow_keypair() -> Result<Keypair> {
+    let secret = &config().ESCROW_SECRET;
+    let private_key = &config().ESCROW_PRIVATE_KEY;
+
+    let keypair = decode_keypair(private_key, secret)?;
+    Ok(keypair)
+}
+
 fn get_fee_faucet_keypair() -> Result<Keypair> {
     let secret = &config().FEE_FAUCET_SECRET;
     let private_key = &config().FEE_FAUCET_PRIVATE_KEY;